@@ -0,0 +1,111 @@
+use proc_macro2::TokenStream;
+use quote::quote;
+use syn::{
+    parse::{Parse, ParseStream},
+    punctuated::Punctuated,
+    Error, Fields, Ident, ItemStruct, Path, Token, Variant,
+};
+
+use crate::typed::Args;
+
+/// The attributes of the struct that are given to the `bitflag` attribute unchanged.
+const FORWARDED_ATTRS: &[&str] = &[
+    "extra_valid_bits",
+    "bitflag_convert",
+    "bitflag_widen",
+    "bitflag_fields",
+    "deny_gaps",
+];
+
+/// The input of the `Bitflags` derive macro.
+///
+/// It is turned into the arguments and the enum of the `bitflag` attribute.
+pub struct DeriveBitflags {
+    pub args: Args,
+    pub item: TokenStream,
+}
+
+/// The arguments of the `#[bitflags(bits = ty, ...)]` attribute.
+struct DeriveArgs(Args);
+
+impl Parse for DeriveArgs {
+    fn parse(input: ParseStream) -> syn::Result<Self> {
+        let bits: Ident = input.parse()?;
+        if bits != "bits" {
+            return Err(Error::new_spanned(
+                bits,
+                "expected `bits = <type>` as the first option",
+            ));
+        }
+        input.parse::<Token![=]>()?;
+        let ty: Path = input.parse()?;
+
+        // The other options are the ones of the `bitflag` attribute
+        let rest: TokenStream = input.parse()?;
+
+        syn::parse2(quote!(#ty #rest)).map(Self)
+    }
+}
+
+impl Parse for DeriveBitflags {
+    fn parse(input: ParseStream) -> syn::Result<Self> {
+        let item: ItemStruct = input.parse()?;
+
+        if !item.generics.params.is_empty() || item.generics.where_clause.is_some() {
+            return Err(Error::new_spanned(
+                &item.generics,
+                "`Bitflags` can't be derived for a generic struct",
+            ));
+        }
+
+        if !matches!(&item.fields, Fields::Unnamed(fields) if fields.unnamed.len() == 1) {
+            return Err(Error::new_spanned(
+                &item.fields,
+                "`Bitflags` can only be derived for a tuple struct with a single field, like `struct Flags(u32);`",
+            ));
+        }
+
+        let mut args = None;
+        let mut variants = Vec::new();
+        let mut forwarded = Vec::new();
+
+        for attr in &item.attrs {
+            if attr.path().is_ident("bitflags") {
+                if args.is_some() {
+                    return Err(Error::new_spanned(attr, "duplicated `bitflags` attribute"));
+                }
+                args = Some(attr.parse_args::<DeriveArgs>()?.0);
+            } else if attr.path().is_ident("flags") {
+                let flags =
+                    attr.parse_args_with(Punctuated::<Variant, Token![,]>::parse_terminated)?;
+                variants.extend(flags);
+            } else if FORWARDED_ATTRS
+                .iter()
+                .any(|name| attr.path().is_ident(name))
+            {
+                forwarded.push(attr);
+            }
+        }
+
+        let args = args.ok_or_else(|| {
+            Error::new_spanned(
+                &item.ident,
+                "missing `#[bitflags(bits = <type>)]` attribute with the bits type",
+            )
+        })?;
+
+        // The struct is written by hand, so the derives of the enum only pass the checks of the
+        // `bitflag` attribute
+        let vis = &item.vis;
+        let name = &item.ident;
+        let item = quote! {
+            #[derive(Clone, Copy)]
+            #(#forwarded)*
+            #vis enum #name {
+                #(#variants,)*
+            }
+        };
+
+        Ok(Self { args, item })
+    }
+}
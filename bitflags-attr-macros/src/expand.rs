@@ -0,0 +1,276 @@
+use std::path::PathBuf;
+
+use proc_macro2::{Delimiter, Spacing, TokenStream, TokenTree};
+use syn::{Error, LitStr};
+
+/// Write the code generated for the flags type `name` to the file of the `debug_expand` option.
+///
+/// Relative paths are relative to the crate root, and the missing parent directories are created.
+pub fn write_expansion(path: &LitStr, name: &str, tokens: &TokenStream) -> syn::Result<()> {
+    let manifest_dir = std::env::var("CARGO_MANIFEST_DIR").unwrap_or_default();
+    let file = PathBuf::from(manifest_dir).join(path.value());
+
+    let mut source = format!("// Code generated by `#[bitflag]` for `{name}`\n\n");
+    Printer::new(&mut source, true).stream(tokens.clone());
+    source.push('\n');
+
+    file.parent()
+        .map_or(Ok(()), std::fs::create_dir_all)
+        .and_then(|()| std::fs::write(&file, source))
+        .map_err(|err| {
+            Error::new_spanned(
+                path,
+                format_args!(
+                    "failed to write the expansion to `{}`: {err}",
+                    file.display()
+                ),
+            )
+        })
+}
+
+/// The keywords that can be followed by a parenthesized expression or a tuple type.
+const KEYWORDS: &[&str] = &[
+    "as", "const", "dyn", "else", "fn", "for", "if", "impl", "in", "let", "match", "move", "mut",
+    "return", "static", "where", "while",
+];
+
+/// A simple code printer that puts items, statements and fields on their own lines, indented by
+/// their block. It doesn't try to match `rustfmt`, only to make the generated code readable.
+struct Printer<'a> {
+    out: &'a mut String,
+    /// Whether the tokens are in a block, where statements and fields go on their own lines.
+    block: bool,
+    indent: usize,
+    line_start: bool,
+    /// Whether a space is needed before the next token.
+    space: bool,
+    /// Whether the previous token is a punctuation joined to the next one, like the `:` of `::`.
+    joint: bool,
+    /// The depth of generic arguments, to write `<` and `>` without spaces.
+    generics: usize,
+    /// Whether the generic arguments at the top level are the parameters of an `impl`.
+    impl_generics: bool,
+    /// Whether the previous token is the `>` closing generic arguments, other than the parameters
+    /// of an `impl`.
+    generics_end: bool,
+    /// Whether the previous token is a block, that usually ends the line.
+    block_end: bool,
+}
+
+impl<'a> Printer<'a> {
+    fn new(out: &'a mut String, block: bool) -> Self {
+        Self {
+            out,
+            block,
+            indent: 0,
+            line_start: !block,
+            space: false,
+            joint: false,
+            generics: 0,
+            impl_generics: false,
+            generics_end: false,
+            block_end: false,
+        }
+    }
+
+    fn stream(&mut self, tokens: TokenStream) {
+        let mut tokens = tokens.into_iter().peekable();
+
+        while let Some(token) = tokens.next() {
+            let generics_end = std::mem::take(&mut self.generics_end);
+            let joint =
+                matches!(&token, TokenTree::Punct(punct) if punct.spacing() == Spacing::Joint);
+
+            // A block ends its line, unless followed by a `;`, a `,`, a method call or `else`
+            if self.block_end {
+                self.block_end = false;
+                match &token {
+                    TokenTree::Punct(punct) if matches!(punct.as_char(), ';' | ',' | '.' | '?') => {
+                    }
+                    TokenTree::Ident(ident) if ident == "else" => {}
+                    _ => self.newline(),
+                }
+            }
+
+            match token {
+                TokenTree::Group(group) if group.delimiter() == Delimiter::Brace => {
+                    if group.stream().is_empty() {
+                        self.word("{}");
+                    } else if self.block {
+                        self.word("{");
+                        self.indent += 1;
+                        self.newline();
+                        self.stream(group.stream());
+                        self.indent -= 1;
+                        self.newline();
+                        self.word("}");
+                        self.block_end = true;
+                    } else {
+                        self.word("{");
+                        self.inline(group.stream());
+                        self.word("}");
+                    }
+                }
+                TokenTree::Group(group) => {
+                    let (open, close) = match group.delimiter() {
+                        Delimiter::Parenthesis => ("(", ")"),
+                        Delimiter::Bracket => ("[", "]"),
+                        _ => ("", ""),
+                    };
+
+                    // Calls, tuple structs, indexing and attributes are written without a space
+                    if self.after_name() || self.out.ends_with(['!', '#']) || generics_end {
+                        self.space = false;
+                    }
+
+                    self.word(open);
+                    self.space = false;
+                    self.inline(group.stream());
+                    self.space = false;
+                    self.word(close);
+
+                    if self.block && group.delimiter() == Delimiter::Bracket && self.in_attribute()
+                    {
+                        self.newline();
+                    }
+                }
+                TokenTree::Punct(punct) => {
+                    let next = match tokens.peek() {
+                        Some(TokenTree::Punct(next)) => Some(next.as_char()),
+                        _ => None,
+                    };
+                    self.punct(punct.as_char(), joint, next, generics_end);
+                }
+                TokenTree::Ident(ident) => self.word(&ident.to_string()),
+                TokenTree::Literal(lit) => self.word(&lit.to_string()),
+            }
+
+            self.joint = joint;
+        }
+
+        if self.block_end {
+            self.block_end = false;
+            self.newline();
+        }
+    }
+
+    fn punct(&mut self, ch: char, joint: bool, next: Option<char>, generics_end: bool) {
+        // `->` and `=>`
+        let arrow = ch == '>' && self.joint && self.out.ends_with(['-', '=']);
+        // A `<` followed by the `<` of a qualified path is a comparison
+        let open_generics = ch == '<'
+            && !joint
+            && !self.joint
+            && !self.after_value()
+            && (!self.after_name() || next != Some('<'));
+        let close_generics = ch == '>' && !arrow && self.generics > 0;
+        // The second `:` of `::`
+        let path_sep = ch == ':' && self.joint;
+        let name_sep = ch == ':' && (self.after_name() || generics_end);
+        let unary = matches!(ch, '&' | '!' | '*' | '-') && !joint && !self.after_operand();
+        let macro_bang = ch == '!' && !joint && self.after_name();
+
+        if matches!(ch, ',' | ';' | '.' | '?')
+            || path_sep
+            || name_sep
+            || (open_generics && !self.last_word().is_empty())
+            || close_generics
+            || macro_bang
+        {
+            self.space = false;
+        }
+
+        if open_generics {
+            if self.generics == 0 {
+                self.impl_generics = self.last_word() == "impl";
+            }
+            self.generics += 1;
+        } else if close_generics {
+            self.generics -= 1;
+        }
+
+        let mut buf = [0; 4];
+        self.word(ch.encode_utf8(&mut buf));
+        self.generics_end = close_generics && !(self.generics == 0 && self.impl_generics);
+
+        if ch == ';' {
+            self.generics = 0;
+        }
+
+        if self.block && (ch == ';' || (ch == ',' && self.generics == 0)) {
+            self.newline();
+        } else if joint
+            || path_sep
+            || open_generics
+            || unary
+            || macro_bang
+            || matches!(ch, '.' | '#')
+        {
+            self.space = false;
+        }
+    }
+
+    /// Write the stream of a group on the current line.
+    fn inline(&mut self, tokens: TokenStream) {
+        let mut line = String::new();
+        Printer::new(&mut line, false).stream(tokens);
+        self.word(&line);
+    }
+
+    fn word(&mut self, word: &str) {
+        if word.is_empty() {
+            return;
+        }
+
+        if self.line_start {
+            if self.block {
+                self.out.push_str(&"    ".repeat(self.indent));
+            }
+        } else if self.space {
+            self.out.push(' ');
+        }
+
+        self.out.push_str(word);
+        self.line_start = false;
+        self.space = true;
+    }
+
+    fn newline(&mut self) {
+        if !self.line_start {
+            self.out.push('\n');
+            self.line_start = true;
+        }
+        self.space = false;
+    }
+
+    /// Whether the last token is a name, that can be followed by the arguments of a call.
+    fn after_name(&self) -> bool {
+        let word = self.last_word();
+        !word.is_empty() && !KEYWORDS.contains(&word)
+    }
+
+    /// Whether the last token ends an operand, so a following operator is a binary operator.
+    fn after_operand(&self) -> bool {
+        self.after_name() || self.after_value()
+    }
+
+    /// Whether the last token ends a value, so a following `<` is a comparison instead of the
+    /// start of generic arguments.
+    fn after_value(&self) -> bool {
+        self.out.ends_with([')', ']', '"', '\''])
+            || self.last_word().starts_with(|ch: char| ch.is_ascii_digit())
+    }
+
+    fn last_word(&self) -> &str {
+        self.out
+            .rsplit(|ch: char| !(ch.is_alphanumeric() || ch == '_'))
+            .next()
+            .unwrap_or_default()
+    }
+
+    /// Whether the current line is an attribute.
+    fn in_attribute(&self) -> bool {
+        let line = self.out.rsplit('\n').next().unwrap_or_default();
+        line.trim_start().starts_with('#')
+    }
+}
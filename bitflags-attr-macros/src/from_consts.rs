@@ -0,0 +1,110 @@
+use proc_macro2::TokenStream;
+use quote::quote;
+use syn::{
+    braced,
+    parse::{Parse, ParseStream},
+    punctuated::Punctuated,
+    Attribute, Error, Expr, Ident, LitStr, Path, Token, Visibility,
+};
+
+use crate::typed::Args;
+
+/// The input of the `bitflag_from_consts!` macro.
+///
+/// It is turned into the arguments and the enum of the `bitflag` attribute.
+pub struct FromConsts {
+    pub args: Args,
+    pub item: TokenStream,
+}
+
+/// A flag defined from an existing constant, optionally renamed with `Name = expr`.
+struct ConstFlag {
+    attrs: Vec<Attribute>,
+    name: Ident,
+    value: Expr,
+}
+
+impl Parse for ConstFlag {
+    fn parse(input: ParseStream) -> syn::Result<Self> {
+        let attrs = input.call(Attribute::parse_outer)?;
+
+        if input.peek(Ident) && input.peek2(Token![=]) {
+            let name: Ident = input.parse()?;
+            input.parse::<Token![=]>()?;
+            let value: Expr = input.parse()?;
+
+            return Ok(Self { attrs, name, value });
+        }
+
+        let path: Path = input.parse()?;
+        let name = match path.segments.last() {
+            Some(segment) if segment.arguments.is_none() => segment.ident.clone(),
+            _ => {
+                return Err(Error::new_spanned(
+                    path,
+                    "expected a path to a constant, or `Name = <expr>`",
+                ))
+            }
+        };
+
+        Ok(Self {
+            attrs,
+            name,
+            value: syn::parse_quote!(#path),
+        })
+    }
+}
+
+impl Parse for FromConsts {
+    fn parse(input: ParseStream) -> syn::Result<Self> {
+        let mut attrs = input.call(Attribute::parse_outer)?;
+        let vis: Visibility = input.parse()?;
+        let enum_token: Token![enum] = input.parse()?;
+        let name: Ident = input.parse()?;
+
+        let content;
+        braced!(content in input);
+        let flags = Punctuated::<ConstFlag, Token![,]>::parse_terminated(&content)?;
+
+        let position = attrs
+            .iter()
+            .position(|attr| attr.path().is_ident("bitflag"))
+            .ok_or_else(|| {
+                Error::new_spanned(
+                    enum_token,
+                    "missing `#[bitflag(...)]` attribute with the bits type",
+                )
+            })?;
+        let args: Args = attrs.remove(position).parse_args()?;
+
+        let variants = flags.iter().map(|flag| {
+            let ConstFlag { attrs, name, value } = flag;
+
+            // A renamed constant stays searchable by its original name
+            let alias = match value {
+                Expr::Path(path) => path
+                    .path
+                    .segments
+                    .last()
+                    .map(|segment| &segment.ident)
+                    .filter(|ident| *ident != name)
+                    .map(|ident| {
+                        let alias = LitStr::new(&ident.to_string(), ident.span());
+                        quote!(#[doc(alias = #alias)])
+                    }),
+                _ => None,
+            };
+
+            quote!(#(#attrs)* #alias #name = #value)
+        });
+
+        let item = quote! {
+            #(#attrs)*
+            #vis enum #name {
+                #(#variants,)*
+            }
+        };
+
+        Ok(Self { args, item })
+    }
+}
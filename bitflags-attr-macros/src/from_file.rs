@@ -0,0 +1,203 @@
+use std::path::PathBuf;
+
+use syn::{Attribute, Error, Expr, Ident, LitStr, Variant};
+
+/// A field of a flag in the file, with the types of both TOML and JSON.
+enum Field {
+    Int(i128),
+    Str(String),
+    /// A value of another type, with the name of the type.
+    Other(&'static str),
+}
+
+/// The flags of the file of the `from_file` option, as the variants of the flags enum.
+///
+/// Relative paths are relative to the crate root. The file is TOML or JSON by its extension, with
+/// a `flags` array of tables with the `name`, the `value` and an optional `doc` of each flag.
+/// Returns the variants with the absolute path of the file.
+pub fn read_flags(path: &LitStr) -> syn::Result<(Vec<Variant>, LitStr)> {
+    let manifest_dir = std::env::var("CARGO_MANIFEST_DIR").unwrap_or_default();
+    let file = PathBuf::from(manifest_dir).join(path.value());
+
+    let error = |msg: std::fmt::Arguments| {
+        Error::new_spanned(path, format_args!("`{}`: {msg}", file.display()))
+    };
+
+    let source = std::fs::read_to_string(&file)
+        .map_err(|err| error(format_args!("failed to read the flags: {err}")))?;
+
+    let flags = match file.extension().and_then(|ext| ext.to_str()) {
+        Some("toml") => toml_flags(&source),
+        Some("json") => json_flags(&source),
+        _ => Err(String::from(
+            "unknown file format, expected a `.toml` or a `.json` file",
+        )),
+    }
+    .map_err(|msg| error(format_args!("{msg}")))?;
+
+    let mut variants = Vec::with_capacity(flags.len());
+    for (i, fields) in flags.into_iter().enumerate() {
+        let variant = flag_variant(fields, path)
+            .map_err(|msg| error(format_args!("flag {}: {msg}", i + 1)))?;
+        variants.push(variant);
+    }
+
+    let file = LitStr::new(&file.display().to_string(), path.span());
+    Ok((variants, file))
+}
+
+/// Build the variant of a flag from its fields.
+fn flag_variant(fields: Vec<(String, Field)>, path: &LitStr) -> Result<Variant, String> {
+    let mut name = None;
+    let mut value = None;
+    let mut doc = None;
+
+    for (key, field) in fields {
+        let slot = match key.as_str() {
+            "name" => &mut name,
+            "value" => &mut value,
+            "doc" => &mut doc,
+            _ => {
+                return Err(format!(
+                    "unknown field `{key}`, expected `name`, `value` or `doc`"
+                ))
+            }
+        };
+        *slot = Some(field);
+    }
+
+    let name = match name {
+        Some(Field::Str(name)) => name,
+        Some(Field::Int(_)) => {
+            return Err(String::from("`name` must be a string, found an integer"))
+        }
+        Some(Field::Other(ty)) => return Err(format!("`name` must be a string, found {ty}")),
+        None => return Err(String::from("missing field `name`")),
+    };
+    let ident: Ident =
+        syn::parse_str(&name).map_err(|_| format!("flag name `{name}` is not an identifier"))?;
+    let ident = Ident::new(&ident.to_string(), path.span());
+
+    // Strings are expressions, like `1 << 3` or `Read | Write`
+    let value: Expr = match value {
+        Some(Field::Int(value)) => syn::parse_str(&value.to_string()),
+        Some(Field::Str(value)) => syn::parse_str(&value),
+        Some(Field::Other(ty)) => {
+            return Err(format!(
+                "the value of flag `{name}` must be an integer or a string with an expression, found {ty}"
+            ))
+        }
+        None => return Err(format!("missing field `value` of flag `{name}`")),
+    }
+    .map_err(|err| format!("invalid value of flag `{name}`: {err}"))?;
+
+    let doc = match doc {
+        Some(Field::Str(doc)) => doc,
+        Some(Field::Int(_)) => {
+            return Err(format!(
+                "the doc of flag `{name}` must be a string, found an integer"
+            ))
+        }
+        Some(Field::Other(ty)) => {
+            return Err(format!(
+                "the doc of flag `{name}` must be a string, found {ty}"
+            ))
+        }
+        None => String::new(),
+    };
+    // One attribute per line, like the doc comments
+    let attrs: Vec<Attribute> = doc
+        .lines()
+        .map(|line| {
+            let line = format!(" {line}");
+            syn::parse_quote!(#[doc = #line])
+        })
+        .collect();
+
+    Ok(syn::parse_quote! {
+        #(#attrs)*
+        #ident = #value
+    })
+}
+
+/// The fields of the flags of a TOML file.
+fn toml_flags(source: &str) -> Result<Vec<Vec<(String, Field)>>, String> {
+    let table: toml::Table = source
+        .parse()
+        .map_err(|err| format!("invalid TOML: {err}"))?;
+
+    let Some(flags) = table.get("flags") else {
+        return Err(String::from("missing `flags` array of tables"));
+    };
+    let toml::Value::Array(flags) = flags else {
+        return Err(String::from("`flags` must be an array of tables"));
+    };
+
+    flags
+        .iter()
+        .map(|flag| {
+            let toml::Value::Table(flag) = flag else {
+                return Err(String::from("`flags` must be an array of tables"));
+            };
+
+            Ok(flag
+                .iter()
+                .map(|(key, value)| {
+                    let field = match value {
+                        toml::Value::Integer(value) => Field::Int((*value).into()),
+                        toml::Value::String(value) => Field::Str(value.clone()),
+                        toml::Value::Float(_) => Field::Other("a float"),
+                        toml::Value::Boolean(_) => Field::Other("a boolean"),
+                        toml::Value::Datetime(_) => Field::Other("a datetime"),
+                        toml::Value::Array(_) => Field::Other("an array"),
+                        toml::Value::Table(_) => Field::Other("a table"),
+                    };
+                    (key.clone(), field)
+                })
+                .collect())
+        })
+        .collect()
+}
+
+/// The fields of the flags of a JSON file.
+fn json_flags(source: &str) -> Result<Vec<Vec<(String, Field)>>, String> {
+    let root: serde_json::Value =
+        serde_json::from_str(source).map_err(|err| format!("invalid JSON: {err}"))?;
+
+    let Some(flags) = root.get("flags") else {
+        return Err(String::from("missing `flags` array of objects"));
+    };
+    let serde_json::Value::Array(flags) = flags else {
+        return Err(String::from("`flags` must be an array of objects"));
+    };
+
+    flags
+        .iter()
+        .map(|flag| {
+            let serde_json::Value::Object(flag) = flag else {
+                return Err(String::from("`flags` must be an array of objects"));
+            };
+
+            Ok(flag
+                .iter()
+                .map(|(key, value)| {
+                    let field = match value {
+                        serde_json::Value::Number(value) => {
+                            match (value.as_i64(), value.as_u64()) {
+                                (Some(value), _) => Field::Int(value.into()),
+                                (_, Some(value)) => Field::Int(value.into()),
+                                _ => Field::Other("a float"),
+                            }
+                        }
+                        serde_json::Value::String(value) => Field::Str(value.clone()),
+                        serde_json::Value::Null => Field::Other("null"),
+                        serde_json::Value::Bool(_) => Field::Other("a boolean"),
+                        serde_json::Value::Array(_) => Field::Other("an array"),
+                        serde_json::Value::Object(_) => Field::Other("an object"),
+                    };
+                    (key.clone(), field)
+                })
+                .collect())
+        })
+        .collect()
+}
@@ -0,0 +1,353 @@
+use std::path::PathBuf;
+
+use proc_macro2::TokenStream;
+use quote::quote;
+use syn::{
+    braced,
+    parse::{Parse, ParseStream},
+    Attribute, Error, Expr, Ident, LitStr, Token, Variant, Visibility,
+};
+
+use crate::typed::Args;
+
+/// The input of the `bitflag_from_header!` macro.
+///
+/// It is turned into the arguments and the enum of the `bitflag` attribute, with the flags read
+/// from the header added before the ones defined in the enum body.
+pub struct FromHeader {
+    pub args: Args,
+    pub item: TokenStream,
+    /// Makes the compiler track the header, so the flags are regenerated when it changes.
+    pub tracking: TokenStream,
+}
+
+/// A `#[header("path.h", prefix = "PREFIX_")]` attribute.
+struct HeaderAttr {
+    path: LitStr,
+    prefix: Option<LitStr>,
+}
+
+impl Parse for HeaderAttr {
+    fn parse(input: ParseStream) -> syn::Result<Self> {
+        let path: LitStr = input.parse()?;
+        let mut prefix = None;
+
+        while !input.is_empty() {
+            input.parse::<Token![,]>()?;
+            if input.is_empty() {
+                break;
+            }
+
+            let option: Ident = input.parse()?;
+            if option != "prefix" {
+                return Err(Error::new_spanned(
+                    &option,
+                    format_args!("unknown header option `{option}`"),
+                ));
+            }
+            if prefix.is_some() {
+                return Err(Error::new_spanned(option, "duplicated option `prefix`"));
+            }
+
+            input.parse::<Token![=]>()?;
+            prefix = Some(input.parse()?);
+        }
+
+        Ok(Self { path, prefix })
+    }
+}
+
+impl Parse for FromHeader {
+    fn parse(input: ParseStream) -> syn::Result<Self> {
+        let mut attrs = input.call(Attribute::parse_outer)?;
+        let vis: Visibility = input.parse()?;
+        let enum_token: Token![enum] = input.parse()?;
+        let name: Ident = input.parse()?;
+
+        let content;
+        braced!(content in input);
+        let variants = content.parse_terminated(Variant::parse, Token![,])?;
+
+        let args = take_attr(&mut attrs, "bitflag", &enum_token)?.parse_args::<Args>()?;
+        let header = take_attr(&mut attrs, "header", &enum_token)?.parse_args::<HeaderAttr>()?;
+
+        let manifest_dir = std::env::var("CARGO_MANIFEST_DIR").unwrap_or_default();
+        let path = PathBuf::from(manifest_dir).join(header.path.value());
+        let source = std::fs::read_to_string(&path).map_err(|err| {
+            Error::new_spanned(
+                &header.path,
+                format_args!("failed to read `{}`: {err}", path.display()),
+            )
+        })?;
+
+        let prefix = header
+            .prefix
+            .as_ref()
+            .map(LitStr::value)
+            .unwrap_or_default();
+        let mut flags = Vec::new();
+
+        let mut names = Vec::new();
+
+        for constant in parse_constants(&source) {
+            if !constant.name.starts_with(&prefix) || names.contains(&constant.name) {
+                continue;
+            }
+
+            let value: Expr = syn::parse_str(&constant.value).map_err(|_| {
+                Error::new_spanned(
+                    &header.path,
+                    format_args!(
+                        "unsupported value `{}` for `{}` in the header",
+                        constant.value, constant.name
+                    ),
+                )
+            })?;
+            let name = Ident::new(&constant.name, header.path.span());
+
+            flags.push(quote!(#name = #value));
+            names.push(constant.name);
+        }
+
+        if flags.is_empty() {
+            return Err(Error::new_spanned(
+                &header.path,
+                format_args!("no constant starting with `{prefix}` found in the header"),
+            ));
+        }
+
+        let path = path.to_string_lossy();
+
+        let item = quote! {
+            #(#attrs)*
+            #vis enum #name {
+                #(#flags,)*
+                #variants
+            }
+        };
+
+        let tracking = quote! {
+            const _: &[u8] = ::core::include_bytes!(#path);
+        };
+
+        Ok(Self {
+            args,
+            item,
+            tracking,
+        })
+    }
+}
+
+fn take_attr(
+    attrs: &mut Vec<Attribute>,
+    name: &str,
+    span: &Token![enum],
+) -> syn::Result<Attribute> {
+    match attrs.iter().position(|attr| attr.path().is_ident(name)) {
+        Some(position) => Ok(attrs.remove(position)),
+        None => Err(Error::new_spanned(
+            span,
+            format_args!("missing `#[{name}(...)]` attribute"),
+        )),
+    }
+}
+
+/// An integer constant found in a C header.
+struct Constant {
+    name: String,
+    /// The value, already translated to a Rust expression.
+    value: String,
+}
+
+/// Find the `#define NAME value` object-like macros and the enumerators in a C header.
+fn parse_constants(source: &str) -> Vec<Constant> {
+    let source = strip_comments(&source.replace("\\\r\n", " ").replace("\\\n", " "));
+    let mut constants = Vec::new();
+
+    for line in source.lines() {
+        let line = line.trim();
+
+        let Some(define) = line.strip_prefix('#') else {
+            continue;
+        };
+        let Some(define) = define.trim_start().strip_prefix("define") else {
+            continue;
+        };
+        if !define.starts_with(char::is_whitespace) {
+            continue;
+        }
+
+        let define = define.trim_start();
+        let name_len = define
+            .find(|c: char| !(c.is_ascii_alphanumeric() || c == '_'))
+            .unwrap_or(define.len());
+        let (name, value) = define.split_at(name_len);
+
+        // Function-like macros have the parenthesis right after the name, and headers commonly
+        // define enumerators as themselves to allow `#ifdef` checks
+        if name.is_empty()
+            || value.starts_with('(')
+            || value.trim().is_empty()
+            || value.trim() == name
+        {
+            continue;
+        }
+
+        constants.push(Constant {
+            name: name.to_string(),
+            value: translate_value(value.trim()),
+        });
+    }
+
+    // Preprocessor lines can appear between enumerators
+    let declarations: String = source
+        .lines()
+        .filter(|line| !line.trim_start().starts_with('#'))
+        .collect::<Vec<_>>()
+        .join("\n");
+
+    let mut rest = declarations.as_str();
+    while let Some(start) = find_keyword(rest, "enum") {
+        rest = &rest[start + 4..];
+
+        let (Some(open), Some(close)) = (rest.find('{'), rest.find('}')) else {
+            break;
+        };
+        // Not an enum definition, like `enum foo bar;`
+        if rest[..open].contains(';') || close < open {
+            continue;
+        }
+
+        let mut previous: Option<String> = None;
+        for enumerator in rest[open + 1..close].split(',') {
+            let enumerator = enumerator.trim();
+            if enumerator.is_empty() {
+                continue;
+            }
+
+            let (name, value) = match enumerator.split_once('=') {
+                Some((name, value)) => (name.trim(), translate_value(value.trim())),
+                None => match &previous {
+                    Some(previous) => (enumerator, format!("{previous} + 1")),
+                    None => (enumerator, "0".to_string()),
+                },
+            };
+
+            previous = Some(name.to_string());
+            constants.push(Constant {
+                name: name.to_string(),
+                value,
+            });
+        }
+
+        rest = &rest[close + 1..];
+    }
+
+    constants
+}
+
+fn strip_comments(source: &str) -> String {
+    let mut stripped = String::with_capacity(source.len());
+    let mut rest = source;
+
+    loop {
+        let block = rest.find("/*");
+        let line = rest.find("//");
+
+        match (block, line) {
+            (Some(block), Some(line)) if line < block => {
+                stripped.push_str(&rest[..line]);
+                rest = match rest[line..].find('\n') {
+                    Some(end) => &rest[line + end..],
+                    None => "",
+                };
+            }
+            (Some(block), _) => {
+                stripped.push_str(&rest[..block]);
+                stripped.push(' ');
+                rest = match rest[block + 2..].find("*/") {
+                    Some(end) => &rest[block + 2 + end + 2..],
+                    None => "",
+                };
+            }
+            (None, Some(line)) => {
+                stripped.push_str(&rest[..line]);
+                rest = match rest[line..].find('\n') {
+                    Some(end) => &rest[line + end..],
+                    None => "",
+                };
+            }
+            (None, None) => {
+                stripped.push_str(rest);
+                return stripped;
+            }
+        }
+    }
+}
+
+fn find_keyword(source: &str, keyword: &str) -> Option<usize> {
+    let is_ident = |c: char| c.is_ascii_alphanumeric() || c == '_';
+    let mut offset = 0;
+
+    while let Some(found) = source[offset..].find(keyword) {
+        let start = offset + found;
+        let end = start + keyword.len();
+
+        let before = source[..start].chars().next_back().is_some_and(is_ident);
+        let after = source[end..].chars().next().is_some_and(is_ident);
+        if !before && !after {
+            return Some(start);
+        }
+
+        offset = end;
+    }
+
+    None
+}
+
+/// Translate the integer literals of a C expression to Rust: remove the `u`/`l` suffixes and write
+/// octal literals with the `0o` prefix.
+fn translate_value(value: &str) -> String {
+    let mut translated = String::with_capacity(value.len());
+    let mut chars = value.char_indices().peekable();
+
+    while let Some((start, c)) = chars.next() {
+        if c.is_ascii_alphabetic() || c == '_' {
+            // Identifiers are kept as they are
+            translated.push(c);
+            while let Some(&(_, c)) = chars.peek() {
+                if !(c.is_ascii_alphanumeric() || c == '_') {
+                    break;
+                }
+                translated.push(c);
+                chars.next();
+            }
+        } else if c.is_ascii_digit() {
+            let mut end = start + 1;
+            while let Some(&(i, c)) = chars.peek() {
+                if !c.is_ascii_alphanumeric() {
+                    break;
+                }
+                end = i + c.len_utf8();
+                chars.next();
+            }
+
+            let literal = &value[start..end];
+            let is_hex = literal.starts_with("0x") || literal.starts_with("0X");
+            let digits = literal.trim_end_matches(['u', 'U', 'l', 'L']);
+
+            if is_hex {
+                translated.push_str(digits);
+            } else if digits.len() > 1 && digits.starts_with('0') {
+                translated.push_str("0o");
+                translated.push_str(&digits[1..]);
+            } else {
+                translated.push_str(digits);
+            }
+        } else {
+            translated.push(c);
+        }
+    }
+
+    translated
+}
@@ -0,0 +1,484 @@
+use syn::{
+    braced, parenthesized,
+    parse::{Parse, ParseStream},
+    spanned::Spanned,
+    token, BinOp, Error, Expr, ExprBlock, Ident, Path, Token,
+};
+
+use proc_macro2::{Span, TokenStream};
+
+use quote::{quote, quote_spanned, ToTokens, TokenStreamExt};
+
+pub struct BitflagMatch {
+    /// The path of the runtime crate, set with the optional `crate = path` before the value.
+    krate: Path,
+    scrutinee: Expr,
+    arms: Vec<MatchArm>,
+    default: Option<DefaultArm>,
+}
+
+struct MatchArm {
+    binding: Option<Ident>,
+    pattern: ArmPattern,
+    body: Expr,
+}
+
+struct DefaultArm {
+    binding: Option<Ident>,
+    body: Expr,
+}
+
+/// The semantics used to decide if a arm matches the value.
+enum ArmPattern {
+    /// The value is exactly the flags value of the pattern.
+    Exact(Expr),
+    /// The value contains all flags of the pattern.
+    Contains(Expr),
+    /// The value contains any flag of the pattern.
+    Intersects(Expr),
+}
+
+impl Parse for BitflagMatch {
+    fn parse(input: ParseStream) -> syn::Result<Self> {
+        let krate = if input.peek(Token![crate]) && input.peek2(Token![=]) {
+            input.parse::<Token![crate]>()?;
+            input.parse::<Token![=]>()?;
+            let krate = input.parse()?;
+            input.parse::<Token![,]>()?;
+            krate
+        } else {
+            syn::parse_quote!(::bitflag_attr)
+        };
+
+        let scrutinee: Expr = input.parse()?;
+        input.parse::<Token![,]>()?;
+
+        let content;
+        braced!(content in input);
+
+        let mut arms = Vec::new();
+        let mut default = None;
+
+        while !content.is_empty() {
+            let binding = parse_binding(&content)?;
+
+            if content.peek(Token![_]) {
+                let underscore = content.parse::<Token![_]>()?;
+                content.parse::<Token![=>]>()?;
+                let body = parse_arm_body(&content)?;
+                default = Some(DefaultArm { binding, body });
+
+                if !content.is_empty() {
+                    return Err(Error::new(
+                        underscore.span,
+                        "the `_` arm must be the last arm of `bitflag_match!`",
+                    ));
+                }
+
+                break;
+            }
+
+            let pattern = content.parse()?;
+            content.parse::<Token![=>]>()?;
+            let body = parse_arm_body(&content)?;
+
+            arms.push(MatchArm {
+                binding,
+                pattern,
+                body,
+            });
+        }
+
+        // Without a `_` arm the exhaustiveness can only be proven if every pattern is const-evaluable
+        if default.is_none() && !is_analyzable(&arms) {
+            return Err(Error::new(
+                Span::call_site(),
+                "`bitflag_match!` requires a `_` arm as the last arm, unless all patterns are const-evaluable flags expressions that cover every value",
+            ));
+        }
+
+        // Allow a trailing comma after the macro body
+        let _: Option<Token![,]> = input.parse()?;
+
+        Ok(Self {
+            krate,
+            scrutinee,
+            arms,
+            default,
+        })
+    }
+}
+
+impl ArmPattern {
+    fn expr(&self) -> &Expr {
+        match self {
+            ArmPattern::Exact(expr) | ArmPattern::Contains(expr) | ArmPattern::Intersects(expr) => {
+                expr
+            }
+        }
+    }
+
+    /// The pattern as written by the user, used in the diagnostics.
+    fn describe(&self) -> String {
+        let expr = self
+            .expr()
+            .to_token_stream()
+            .to_string()
+            .replace(" :: ", "::");
+
+        match self {
+            ArmPattern::Exact(_) => expr,
+            ArmPattern::Contains(_) => format!("contains({expr})"),
+            ArmPattern::Intersects(_) => format!("intersects({expr})"),
+        }
+    }
+}
+
+impl Parse for ArmPattern {
+    fn parse(input: ParseStream) -> syn::Result<Self> {
+        if input.peek(Ident) && input.peek2(token::Paren) {
+            let fork = input.fork();
+            let ident: Ident = fork.parse()?;
+
+            if ident == "contains" || ident == "intersects" {
+                input.parse::<Ident>()?;
+
+                let content;
+                parenthesized!(content in input);
+                let expr: Expr = content.parse()?;
+
+                return Ok(if ident == "contains" {
+                    ArmPattern::Contains(expr)
+                } else {
+                    ArmPattern::Intersects(expr)
+                });
+            }
+        }
+
+        Ok(ArmPattern::Exact(input.parse()?))
+    }
+}
+
+/// Parse the optional `name @` binding before the pattern of a arm.
+fn parse_binding(input: ParseStream) -> syn::Result<Option<Ident>> {
+    if input.peek(Ident) && input.peek2(Token![@]) {
+        let binding = input.parse()?;
+        input.parse::<Token![@]>()?;
+
+        return Ok(Some(binding));
+    }
+
+    Ok(None)
+}
+
+/// Parse the body of a arm with the same rules of a `match` arm: block bodies may omit the
+/// trailing comma, expressions bodies need it unless it is the last arm.
+fn parse_arm_body(input: ParseStream) -> syn::Result<Expr> {
+    if input.peek(token::Brace) {
+        let body: ExprBlock = input.parse()?;
+        let _: Option<Token![,]> = input.parse()?;
+
+        return Ok(Expr::Block(body));
+    }
+
+    let body: Expr = input.parse()?;
+
+    if !input.is_empty() {
+        input.parse::<Token![,]>()?;
+    }
+
+    Ok(body)
+}
+
+impl ToTokens for BitflagMatch {
+    fn to_tokens(&self, tokens: &mut TokenStream) {
+        let Self {
+            krate,
+            scrutinee,
+            arms,
+            default,
+        } = self;
+
+        // `mixed_site` so the arms can never refer to the value by accident
+        let value = Ident::new("__bitflag_match_value", Span::mixed_site());
+
+        let conditions = arms.iter().map(|arm| match &arm.pattern {
+            ArmPattern::Exact(pattern) => quote! {
+                #krate::Flags::bits(&#value) == #krate::Flags::bits(&(#pattern))
+            },
+            ArmPattern::Contains(pattern) => quote! {
+                #krate::Flags::contains(&#value, #pattern)
+            },
+            ArmPattern::Intersects(pattern) => quote! {
+                #krate::Flags::intersects(&#value, #pattern)
+            },
+        });
+        let bodies = arms
+            .iter()
+            .map(|arm| arm_body(&value, arm.binding.as_ref(), &arm.body));
+        let default = match default {
+            Some(default) => arm_body(&value, default.binding.as_ref(), &default.body),
+            // Proven unreachable by the analysis
+            None => quote!({ ::core::unreachable!() }),
+        };
+
+        let analysis = if is_analyzable(arms) {
+            analysis(arms, self.default.is_some())
+        } else {
+            quote!()
+        };
+
+        tokens.append_all(quote! {
+            match #scrutinee {
+                #value => {
+                    #analysis
+
+                    #(if #conditions #bodies else)* #default
+                }
+            }
+        });
+    }
+}
+
+/// Generate the block of a arm, binding the matched value if requested.
+///
+/// The statements of block bodies are used as is, so no unnecessary braces are added around the
+/// user block.
+fn arm_body(value: &Ident, binding: Option<&Ident>, body: &Expr) -> TokenStream {
+    let binding = binding.map(|binding| {
+        quote! {
+            let #binding = #value;
+        }
+    });
+
+    match body {
+        Expr::Block(block) if block.label.is_none() && block.attrs.is_empty() => {
+            let stmts = &block.block.stmts;
+            quote!({ #binding #(#stmts)* })
+        }
+        body => quote!({ #binding #body }),
+    }
+}
+
+/// The maximum number of bit groups the exhaustiveness and reachability analysis will walk through.
+///
+/// Each group can be in 3 states (unset, set or partially set), so the number of evaluated values
+/// grows as `3^MAX_ANALYZED_GROUPS`.
+const MAX_ANALYZED_GROUPS: usize = 8;
+
+/// Returns `true` if all arms patterns are flags expressions that can be evaluated at compile time.
+fn is_analyzable(arms: &[MatchArm]) -> bool {
+    !arms.is_empty()
+        && arms
+            .iter()
+            .all(|arm| const_bits(arm.pattern.expr(), &mut Vec::new()).is_some())
+}
+
+/// Convert a pattern into a const expression of its bits, collecting the flags values used on it.
+///
+/// Only associated constants, `empty()`, `all()`, `all_bits()` and the `|`, `&` and `^` operators
+/// between them are considered const-evaluable. Paths starting with `Self` are not, since they
+/// can't be used inside the generated `const` item.
+fn const_bits(expr: &Expr, atoms: &mut Vec<TokenStream>) -> Option<TokenStream> {
+    match expr {
+        Expr::Path(path) if is_type_path(&path.path) && path.qself.is_none() => {
+            let atom = quote!(#path.bits());
+            atoms.push(atom.clone());
+            Some(atom)
+        }
+        Expr::Call(call) if call.args.is_empty() => {
+            let Expr::Path(func) = &*call.func else {
+                return None;
+            };
+
+            let name = &func.path.segments.last()?.ident;
+            if func.qself.is_some()
+                || !is_type_path(&func.path)
+                || (name != "empty" && name != "all" && name != "all_bits")
+            {
+                return None;
+            }
+
+            let atom = quote!(#call.bits());
+            atoms.push(atom.clone());
+            Some(atom)
+        }
+        Expr::Binary(bin) => {
+            let op = match bin.op {
+                BinOp::BitOr(_) | BinOp::BitAnd(_) | BinOp::BitXor(_) => &bin.op,
+                _ => return None,
+            };
+
+            let left = const_bits(&bin.left, atoms)?;
+            let right = const_bits(&bin.right, atoms)?;
+            Some(quote!((#left #op #right)))
+        }
+        Expr::Paren(paren) => const_bits(&paren.expr, atoms),
+        Expr::Group(group) => const_bits(&group.expr, atoms),
+        _ => None,
+    }
+}
+
+/// A path of the form `Type::NAME`.
+fn is_type_path(path: &Path) -> bool {
+    path.segments.len() >= 2 && path.segments.first().is_some_and(|seg| seg.ident != "Self")
+}
+
+/// Generate a `const` item that evaluates every arm against a value of each possible "shape" and
+/// fails to compile if any arm is unreachable or, without a `_` arm, if some value is not covered.
+///
+/// The bits of the patterns are split into groups of bits that are always set or unset together
+/// in all patterns (plus the group of the bits not used by any pattern). Every arm only depends on
+/// whether each group is unset, fully set or partially set on the value, so testing one value per
+/// combination of states is the same as testing all values.
+fn analysis(arms: &[MatchArm], has_default: bool) -> TokenStream {
+    let mut atoms = Vec::new();
+    let patterns: Vec<_> = arms
+        .iter()
+        .map(|arm| const_bits(arm.pattern.expr(), &mut atoms).expect("checked to be analyzable"))
+        .collect();
+
+    let mut seen = Vec::new();
+    atoms.retain(|atom| {
+        let repr = atom.to_string();
+        let new = !seen.contains(&repr);
+        seen.push(repr);
+        new
+    });
+
+    let number_arms = arms.len();
+
+    let checks = arms
+        .iter()
+        .zip(patterns.iter())
+        .enumerate()
+        .map(|(i, (arm, bits))| {
+            let condition = match &arm.pattern {
+                ArmPattern::Exact(_) => quote!(value == #bits),
+                ArmPattern::Contains(_) => quote!((value & #bits) == #bits),
+                ArmPattern::Intersects(_) => quote!((value & #bits) != zero),
+            };
+
+            quote! {
+                if !matched && #condition {
+                    matched = true;
+                    reached[#i] = true;
+                }
+            }
+        });
+
+    let exhaustive_check = if has_default {
+        quote!()
+    } else {
+        quote! {
+            if !matched {
+                ::core::panic!("`bitflag_match!` arms do not cover every possible value, add a `_` arm");
+            }
+        }
+    };
+
+    let too_complex_check = if has_default {
+        quote!()
+    } else {
+        quote! {
+            if len > #MAX_ANALYZED_GROUPS {
+                ::core::panic!("`bitflag_match!` patterns are too complex to prove they cover every possible value, add a `_` arm");
+            }
+        }
+    };
+
+    let reached_checks = arms.iter().enumerate().map(|(i, arm)| {
+        let msg = format!(
+            "unreachable `bitflag_match!` arm: `{}` is always matched by a previous arm",
+            arm.pattern.describe()
+        )
+        .replace('{', "{{")
+        .replace('}', "}}");
+
+        quote_spanned! {arm.pattern.expr().span()=>
+            if !reached[#i] {
+                ::core::panic!(#msg);
+            }
+        }
+    });
+
+    quote! {
+        #[allow(clippy::eq_op, clippy::identity_op)]
+        const _: () = {
+            let atoms = [#(#atoms),*];
+            let zero = atoms[0] ^ atoms[0];
+
+            // Split all bits in groups that are never split by any flags value used in the patterns
+            let mut groups = [zero; 128];
+            groups[0] = !zero;
+            let mut len = 1;
+
+            let mut i = 0;
+            while i < atoms.len() {
+                let current_len = len;
+                let mut j = 0;
+                while j < current_len {
+                    let inside = groups[j] & atoms[i];
+                    let outside = groups[j] & !atoms[i];
+
+                    if inside != zero && outside != zero {
+                        groups[j] = inside;
+                        groups[len] = outside;
+                        len += 1;
+                    }
+
+                    j += 1;
+                }
+                i += 1;
+            }
+
+            #too_complex_check
+
+            if len <= #MAX_ANALYZED_GROUPS {
+                let mut reached = [false; #number_arms];
+
+                let mut states = 1usize;
+                let mut i = 0;
+                while i < len {
+                    states *= 3;
+                    i += 1;
+                }
+
+                let mut state = 0;
+                while state < states {
+                    // Build a value where each group is unset (0), fully set (1) or
+                    // partially set (2)
+                    let mut value = zero;
+                    let mut valid = true;
+                    let mut rest = state;
+                    let mut i = 0;
+                    while i < len {
+                        match rest % 3 {
+                            1 => value = value | groups[i],
+                            2 if groups[i].count_ones() > 1 => {
+                                value = value | (groups[i] & groups[i].wrapping_neg())
+                            }
+                            2 => valid = false,
+                            _ => {}
+                        }
+
+                        rest /= 3;
+                        i += 1;
+                    }
+
+                    if valid {
+                        let mut matched = false;
+
+                        #(#checks)*
+
+                        #exhaustive_check
+                    }
+
+                    state += 1;
+                }
+
+                #(#reached_checks)*
+            }
+        };
+    }
+}
@@ -1,43 +1,248 @@
 use syn::{
-    parse::Parse, spanned::Spanned, Attribute, Error, Expr, Ident, ItemConst, ItemEnum, LitStr,
-    Meta, MetaNameValue, Path, Visibility,
+    parse::Parse, punctuated::Punctuated, spanned::Spanned, Attribute, Error, Expr, Ident,
+    ItemEnum, LitInt, LitStr, Meta, MetaNameValue, Path, Visibility,
 };
 
-use proc_macro2::TokenStream;
+use std::ops::Range;
 
-use quote::{quote, ToTokens, TokenStreamExt};
+use proc_macro2::{Span, TokenStream, TokenTree};
+
+use quote::{quote, quote_spanned, ToTokens, TokenStreamExt};
+
+use crate::visualizer::Visualizer;
 
 pub struct Bitflag {
     vis: Visibility,
     attrs: Vec<Attribute>,
+    /// The attributes only applied to the generated struct, set with `#[bitflag_forward_attr(...)]`.
+    forward_attrs: Vec<Meta>,
     name: Ident,
     inner_ty: Path,
-    derived_traits: Vec<Ident>,
+    derived_traits: Vec<Path>,
     impl_debug: bool,
     impl_serialize: bool,
     impl_deserialize: bool,
+    impl_encode: bool,
+    impl_decode: bool,
+    impl_scale_encode: bool,
+    impl_scale_decode: bool,
+    impl_type_info: bool,
+    impl_zvariant_type: bool,
+    impl_subset_ord: bool,
+    strict: bool,
+    not_retains: bool,
+    nonzero: bool,
+    consts_mod: Option<Ident>,
+    /// The type-specific error of `FromStr`, set with the `parse_error` option.
+    parse_error: Option<Ident>,
+    iter_bit_order: bool,
+    /// The number of bits used of the bits type, set with the `width` option.
+    width: Option<LitInt>,
+    aggressive_inline: bool,
+    /// Whether the `with_<flag>` and `without_<flag>` methods are generated.
+    with_methods: bool,
+    /// Whether the `is_<flag>` and `set_<flag>` methods are generated.
+    accessors: bool,
+    /// Whether the operator traits are left out, set with the `no_ops` option.
+    no_ops: bool,
+    /// Whether the conversions from and to the bits are left out, set with the `no_from` option.
+    no_from: bool,
+    /// How the bits are converted to the type, set with the `from_bits` option.
+    from_bits: ConvertMode,
+    /// Whether `FromStr` is left out, set with the `no_from_str` option.
+    no_from_str: bool,
+    /// Whether the iterator traits are left out, set with the `no_iter` option.
+    no_iter: bool,
+    /// Whether `Display` is implemented, set with the `display` option.
+    display: bool,
+    /// Whether the operator traits are implemented as `impl const`, set with the `const_ops` option.
+    const_ops: bool,
+    /// The file to write the generated code to, set with the `debug_expand` option.
+    debug_expand: Option<LitStr>,
+    /// The debugger visualizers, set with the `debugger_visualizer` option.
+    debugger_visualizer: Option<Visualizer>,
+    /// The absolute path of the file the flags are read from, set with the `from_file` option.
+    from_file: Option<LitStr>,
+    /// How `Deserialize` handles unknown bits, set with the `deserialize_unknown_bits` option.
+    deserialize_unknown_bits: UnknownBitsPolicy,
+    /// The names of the methods setting and unsetting flags, set with the `method_names` option.
+    method_names: MethodNames,
+    /// The parser of the `FromStr` implementation, set with the `from_str` option.
+    from_str: FromStrParser,
+    /// How the flags are converted to BSON, set with the `bson` option, if they are.
+    bson: Option<BsonRepr>,
+    /// Whether `strum::VariantNames` and `strum::EnumCount` are implemented, set with the `strum` option.
+    strum: bool,
+    /// Whether `log::kv::ToValue` is implemented, set with the `log_kv` option.
+    log_kv: bool,
+    /// Whether the `godot` property traits are implemented, set with the `godot` option.
+    godot: bool,
+    /// Whether the `sea-orm` value traits are implemented, set with the `sea_orm` option.
+    sea_orm: bool,
+    /// Whether `juniper::GraphQLScalar` is derived, set with the `juniper` option.
+    juniper: bool,
+    /// Whether the `rocket` parsing traits are implemented, set with the `rocket` option.
+    rocket: bool,
+    /// The function checking the invariants of a value, set with the `validate` option.
+    validate: Option<Path>,
+    /// The oldest Rust version the generated code must compile with, set with the `msrv` option.
+    msrv: Option<[u32; 3]>,
+    /// The path of the `bitflag_attr` crate in the generated code, set with the `crate` option.
+    krate: Path,
     all_attrs: Vec<Vec<Attribute>>,
     all_flags: Vec<TokenStream>,
     all_flags_names: Vec<LitStr>,
-    flags: Vec<ItemConst>,
+    /// The names of the flags when formatted and parsed, without the `strip_prefix`.
+    all_flags_texts: Vec<LitStr>,
+    all_flags_options: Vec<FlagOptions>,
+    /// The associated constants of the flags.
+    flags: Vec<TokenStream>,
+    all_variants: Vec<Ident>,
+    /// The attributes of the flags, with their documentation.
+    all_variant_attrs: Vec<Vec<Attribute>>,
     custom_known_bits: Option<Expr>,
-    orig_enum: ItemEnum,
+    /// The span of the `deny_gaps` attribute, if present.
+    deny_gaps: Option<Span>,
+    /// The composite flags with bits not covered by any single-bit flag, with those bits.
+    stray_bits: Vec<(Ident, u128)>,
+    conversions: Vec<BitflagConvert>,
+    /// The wider flags types to generate name-mapping conversions with.
+    widenings: Vec<Path>,
+    /// The flags types composed into bit ranges of this type.
+    fields: Vec<BitflagField>,
+    orig_enum: TokenStream,
+    /// Whether the struct is written by hand, with the `Bitflags` derive, so it isn't generated.
+    hand_written: bool,
 }
 
 impl Bitflag {
     pub fn parse(args: Args, item: proc_macro::TokenStream) -> syn::Result<Self> {
-        let ty = args.ty;
+        let Args {
+            ty,
+            subset_ord,
+            strict,
+            not_retains,
+            nonzero,
+            consts,
+            auto_derive,
+            iter_bit_order,
+            width,
+            aggressive_inline,
+            with_methods,
+            accessors,
+            no_ops,
+            no_from,
+            from_bits,
+            no_from_str,
+            no_iter,
+            display,
+            const_param_ty,
+            const_ops,
+            debug_expand,
+            debugger_visualizer,
+            debugger_module,
+            deserialize_unknown_bits,
+            method_names,
+            from_str,
+            from_file,
+            bson,
+            strip_prefix,
+            strum,
+            log_kv,
+            godot,
+            sea_orm,
+            juniper,
+            rocket,
+            validate,
+            msrv,
+            parse_error,
+            krate,
+        } = args;
+
+        let mut item: ItemEnum = syn::parse(item)?;
+
+        // The flags of the file come after the ones of the enum
+        let from_file = match from_file {
+            #[cfg(feature = "from-file")]
+            Some(path) => {
+                let (variants, file) = crate::from_file::read_flags(&path)?;
+                item.variants.extend(variants);
+                Some(file)
+            }
+            #[cfg(not(feature = "from-file"))]
+            Some(path) => {
+                return Err(Error::new_spanned(
+                    path,
+                    "the `from_file` option requires the `from-file` feature of `bitflag-attr`",
+                ))
+            }
+            None => None,
+        };
+
+        // Const trait implementations and const generic parameter types are only available on
+        // nightly
+        for (enabled, option) in [(const_ops, "const_ops"), (const_param_ty, "const_param_ty")] {
+            if let (true, Some(msrv)) = (enabled, &msrv) {
+                return Err(Error::new_spanned(
+                    msrv,
+                    format_args!(
+                        "the `msrv` option can't be used with the `{option}` option, which requires nightly"
+                    ),
+                ));
+            }
+        }
 
-        let item: ItemEnum = syn::parse(item)?;
+        let msrv = msrv.as_ref().map(parse_msrv).transpose()?;
         let item_span = item.span();
-        let og_attrs = item
-            .attrs
-            .iter()
-            .filter(|att| !att.path().is_ident("extra_valid_bits"));
+
+        if const_ops && no_ops {
+            return Err(Error::new(
+                item_span,
+                "the `const_ops` option can't be used with the `no_ops` option",
+            ));
+        }
+
+        check_helper_attrs(&item.attrs, TYPE_HELPER_ATTRS)?;
+        for variant in item.variants.iter() {
+            check_helper_attrs(&variant.attrs, VARIANT_HELPER_ATTRS)?;
+
+            // Without this, a variant with fields would fail with a confusing error, or even be
+            // accepted if it has a discriminant
+            if !matches!(variant.fields, syn::Fields::Unit) {
+                let name = &variant.ident;
+                return Err(Error::new_spanned(
+                    &variant.fields,
+                    format_args!(
+                        "flag `{name}` has fields, only unit variants with a discriminant are supported, like `{name} = 1 << 0`"
+                    ),
+                ));
+            }
+        }
+        let og_attrs = item.attrs.iter().filter(|att| {
+            !att.path().is_ident("extra_valid_bits")
+                && !att.path().is_ident("bitflag_convert")
+                && !att.path().is_ident("bitflag_widen")
+                && !att.path().is_ident("bitflag_fields")
+                && !att.path().is_ident("bitflag_forward_attr")
+                && !att.path().is_ident("deny_gaps")
+        });
 
         let vis = item.vis;
         let name = item.ident;
 
+        let consts_mod = consts.map(|ident| {
+            ident.unwrap_or_else(|| {
+                Ident::new(
+                    &format!("{}_consts", to_snake_case(&name.to_string())),
+                    name.span(),
+                )
+            })
+        });
+
+        let parse_error = parse_error.map(|ident| {
+            ident.unwrap_or_else(|| Ident::new(&format!("{name}ParseError"), name.span()))
+        });
+
         let has_non_exhaustive = item
             .attrs
             .iter()
@@ -48,16 +253,101 @@ impl Bitflag {
             .attrs
             .iter()
             .filter(|att| {
-                !att.path().is_ident("derive") && !att.path().is_ident("extra_valid_bits")
+                !att.path().is_ident("derive")
+                    && !att.path().is_ident("extra_valid_bits")
+                    && !att.path().is_ident("bitflag_convert")
+                    && !att.path().is_ident("bitflag_widen")
+                    && !att.path().is_ident("bitflag_fields")
+                    && !att.path().is_ident("bitflag_forward_attr")
+                    && !att.path().is_ident("deny_gaps")
             })
             .cloned()
             .collect();
 
+        // Attribute macros like `#[wasm_bindgen]` must not be applied to the original enum too
+        let mut forward_attrs = Vec::new();
+        for attr in item
+            .attrs
+            .iter()
+            .filter(|att| att.path().is_ident("bitflag_forward_attr"))
+        {
+            let parsed =
+                attr.parse_args_with(Punctuated::<Meta, syn::Token![,]>::parse_terminated)?;
+            forward_attrs.extend(parsed);
+        }
+
+        let deny_gaps = match item
+            .attrs
+            .iter()
+            .find(|att| att.path().is_ident("deny_gaps"))
+        {
+            Some(attr) => {
+                attr.meta.require_path_only()?;
+                Some(attr.span())
+            }
+            None => None,
+        };
+
         let valid_bits_attr = item
             .attrs
             .iter()
             .find(|att| att.path().is_ident("extra_valid_bits"));
 
+        let conversions = item
+            .attrs
+            .iter()
+            .filter(|att| att.path().is_ident("bitflag_convert"))
+            .map(|att| att.parse_args())
+            .collect::<syn::Result<Vec<BitflagConvert>>>()?;
+
+        let widenings = item
+            .attrs
+            .iter()
+            .filter(|att| att.path().is_ident("bitflag_widen"))
+            .map(|att| att.parse_args())
+            .collect::<syn::Result<Vec<Path>>>()?;
+
+        let mut fields: Vec<BitflagField> = Vec::new();
+        for attr in item
+            .attrs
+            .iter()
+            .filter(|att| att.path().is_ident("bitflag_fields"))
+        {
+            let parsed =
+                attr.parse_args_with(Punctuated::<BitflagField, syn::Token![,]>::parse_terminated)?;
+            fields.extend(parsed);
+        }
+
+        let max_bits = match &width {
+            Some(width) => width.base10_parse()?,
+            None => ty
+                .get_ident()
+                .and_then(|ident| bits_width(&ident.to_string()))
+                .unwrap_or(128),
+        };
+
+        for (i, field) in fields.iter().enumerate() {
+            if field.range.end > max_bits {
+                return Err(Error::new_spanned(
+                    &field.name,
+                    format_args!(
+                        "field `{}` ends at bit {}, past the {max_bits} bits of the type",
+                        field.name, field.range.end
+                    ),
+                ));
+            }
+
+            if let Some(other) = fields[..i]
+                .iter()
+                .find(|other| other.mask() & field.mask() != 0)
+            {
+                return Err(Error::new_spanned(
+                    &field.name,
+                    format_args!("field `{}` overlaps the field `{}`", field.name, other.name),
+                ));
+            }
+        }
+
         let derives = item
             .attrs
             .iter()
@@ -67,41 +357,138 @@ impl Bitflag {
         let mut impl_debug = false;
         let mut impl_serialize = false;
         let mut impl_deserialize = false;
+        let mut impl_encode = false;
+        let mut impl_decode = false;
+        let mut impl_scale_encode = false;
+        let mut impl_scale_decode = false;
+        let mut impl_type_info = false;
+        let mut impl_zvariant_type = false;
+        let mut impl_subset_ord = false;
         let mut clone_found = false;
         let mut copy_found = false;
 
         for derive in derives {
             derive.parse_nested_meta(|meta| {
-                if let Some(ident) = meta.path.get_ident() {
-                    if ident == "Debug" {
+                // Match on the whole path, so the derives of the same name from other crates, like
+                // `rkyv::Serialize`, are kept
+                let path = &meta.path;
+                if let Some(ident) = path.segments.last().map(|segment| &segment.ident) {
+                    if is_derive(path, &["core", "std", "alloc", "fmt"], "Debug") {
                         impl_debug = true;
                         return Ok(());
                     }
 
-                    if ident == "Serialize" {
+                    if is_derive(path, &["serde"], "Serialize") {
                         impl_serialize = true;
                         return Ok(());
                     }
 
-                    if ident == "Deserialize" {
+                    if is_derive(path, &["serde"], "Deserialize") {
                         impl_deserialize = true;
                         return Ok(());
                     }
 
-                    if ident == "Clone" {
+                    // Other crates have derives with these names, so they are kept without the feature
+                    if ident == "Encode" || ident == "Decode" {
+                        let (musli, scale) = codec_of_derive(path);
+
+                        if musli {
+                            impl_encode |= ident == "Encode";
+                            impl_decode |= ident == "Decode";
+                            return Ok(());
+                        }
+
+                        if scale {
+                            impl_scale_encode |= ident == "Encode";
+                            impl_scale_decode |= ident == "Decode";
+                            return Ok(());
+                        }
+                    }
+
+                    // The derive of `scale-info` must be qualified, like the one of `zvariant`
+                    if cfg!(feature = "scale")
+                        && path.segments.len() > 1
+                        && is_derive(path, &["scale_info"], "TypeInfo")
+                    {
+                        impl_type_info = true;
+                        return Ok(());
+                    }
+
+                    // `Type` is too common a name, like `sqlx::Type`, so it must be qualified
+                    if cfg!(feature = "zvariant")
+                        && path.segments.len() > 1
+                        && is_derive(path, &["zvariant", "zbus"], "Type")
+                    {
+                        impl_zvariant_type = true;
+                        return Ok(());
+                    }
+
+                    if subset_ord && is_derive(path, &["core", "std", "cmp"], "PartialOrd") {
+                        impl_subset_ord = true;
+                        return Ok(());
+                    }
+
+                    if subset_ord && is_derive(path, &["core", "std", "cmp"], "Ord") {
+                        return Err(Error::new_spanned(
+                            ident,
+                            "`Ord` can't be derived with the `subset_ord` option, set inclusion is only a partial order",
+                        ));
+                    }
+
+                    if is_derive(path, &["core", "std", "clone"], "Clone") {
                         clone_found = true;
                     }
 
-                    if ident == "Copy" {
+                    if is_derive(path, &["core", "std", "marker"], "Copy") {
                         copy_found = true;
                     }
 
-                    derived_traits.push(ident.clone());
+                    derived_traits.push(meta.path.clone());
                 }
                 Ok(())
             })?;
         }
 
+        if let Some(auto_derive) = auto_derive {
+            for derive in auto_derive.traits() {
+                let name = derive.rsplit("::").next().unwrap_or(derive);
+                let already_derived = derived_traits.iter().any(|path| {
+                    path.segments
+                        .last()
+                        .is_some_and(|segment| segment.ident == name)
+                });
+
+                if !already_derived {
+                    derived_traits.push(syn::parse_str(derive)?);
+                }
+            }
+
+            clone_found = true;
+            copy_found = true;
+        }
+
+        // The derive needs the structural equality of the derived `PartialEq` and `Eq`
+        if const_param_ty {
+            let is_derived = |name: &str| {
+                derived_traits.iter().any(|path| {
+                    path.segments
+                        .last()
+                        .is_some_and(|segment| segment.ident == name)
+                })
+            };
+
+            if !is_derived("PartialEq") || !is_derived("Eq") {
+                return Err(syn::Error::new(
+                    item_span,
+                    "the `const_param_ty` option requires the type to derive `PartialEq` and `Eq`",
+                ));
+            }
+
+            if !is_derived("ConstParamTy") {
+                derived_traits.push(syn::parse_quote!(::core::marker::ConstParamTy));
+            }
+        }
+
         if !clone_found || !copy_found {
             return Err(syn::Error::new(
                 item_span,
@@ -109,12 +496,39 @@ impl Bitflag {
             ));
         }
 
+        if let Some((value, mode)) = &from_bits {
+            if no_from {
+                return Err(Error::new_spanned(
+                    value,
+                    "the `from_bits` option can't be used with the `no_from` option",
+                ));
+            }
+
+            if strict && matches!(mode, ConvertMode::Retain) {
+                return Err(Error::new_spanned(
+                    value,
+                    "`from_bits = retain` can't be used with the `strict` option, which never keeps unknown bits",
+                ));
+            }
+        }
+
+        if subset_ord && !impl_subset_ord {
+            return Err(syn::Error::new(
+                item_span,
+                "the `subset_ord` option requires the type to derive `PartialOrd`",
+            ));
+        }
+
         let number_flags = item.variants.len();
 
         let mut all_attrs = Vec::with_capacity(number_flags);
         let mut all_flags = Vec::with_capacity(number_flags);
-        let mut all_flags_names = Vec::with_capacity(number_flags);
+        let mut all_flags_names: Vec<LitStr> = Vec::with_capacity(number_flags);
+        let mut all_flags_texts: Vec<LitStr> = Vec::with_capacity(number_flags);
         let mut all_variants = Vec::with_capacity(number_flags);
+        let mut all_flags_options = Vec::with_capacity(number_flags);
+        // The variant attributes without the helper attributes
+        let mut all_variant_attrs = Vec::with_capacity(number_flags);
 
         // The raw flags as private itens to allow defining flags referencing other flag definitions
         let mut raw_flags = Vec::with_capacity(number_flags);
@@ -122,10 +536,60 @@ impl Bitflag {
         let mut flags = Vec::with_capacity(number_flags); // Associated constants
 
         // First generate the raw_flags
-        for variant in item.variants.iter() {
-            let var_attrs = &variant.attrs;
+        for variant in item.variants.iter_mut() {
+            let (options, mut var_attrs) = FlagOptions::from_attrs(&variant.attrs)?;
             let var_name = &variant.ident;
 
+            // The values selected by `cfg` replace the discriminant, which is the value of the other
+            // targets, so the rest of the code only sees a single expression. Each value is a
+            // `cfg` gated constant, so the values of the other targets are never type checked, and
+            // the first matching predicate wins
+            if !options.values.is_empty() {
+                let fallback = match &variant.discriminant {
+                    Some((_, expr)) => quote!(#expr),
+                    None => {
+                        let msg = format!("flag `{var_name}` has no value for the target");
+                        quote!(::core::panic!(#msg))
+                    }
+                };
+                let predicates: Vec<_> = options
+                    .values
+                    .iter()
+                    .map(|(predicate, _)| predicate)
+                    .collect();
+                let gates = (0..predicates.len()).map(|i| {
+                    let (previous, predicate) = (&predicates[..i], predicates[i]);
+                    quote!(all(#predicate, not(any(#(#previous),*))))
+                });
+                let values = options.values.iter().map(|(_, value)| value);
+                let expr = syn::parse_quote! {
+                    {
+                        #(
+                            #[cfg(#gates)]
+                            const __VALUE: #ty = #values;
+                        )*
+                        #[cfg(not(any(#(#predicates),*)))]
+                        const __VALUE: #ty = #fallback;
+
+                        __VALUE
+                    }
+                };
+
+                variant.discriminant = Some((Default::default(), expr));
+            }
+
+            // The aliases are also searchable in the documentation of the generated constant
+            for alias in &options.aliases {
+                if *var_name == alias.value() {
+                    return Err(Error::new_spanned(
+                        alias,
+                        format_args!("alias `{var_name}` is the same as the flag name"),
+                    ));
+                }
+
+                var_attrs.push(syn::parse_quote!(#[doc(alias = #alias)]));
+            }
+
             let expr = match variant.discriminant.as_ref() {
                 Some((_, expr)) => expr,
                 None => {
@@ -136,6 +600,76 @@ impl Bitflag {
                 }
             };
 
+            let name = var_name.to_string();
+            let text = match &strip_prefix {
+                Some(prefix) => name.strip_prefix(&prefix.value()).unwrap_or(&name),
+                None => &name,
+            };
+
+            if text.is_empty() {
+                return Err(Error::new_spanned(
+                    var_name,
+                    format_args!("flag `{name}` has no name left after stripping the prefix"),
+                ));
+            }
+
+            if let Some(other) = all_flags_texts
+                .iter()
+                .position(|other| other.value() == text)
+            {
+                return Err(Error::new_spanned(
+                    var_name,
+                    format_args!(
+                        "flag `{name}` has the same name `{text}` as the flag `{}` after stripping the prefix",
+                        all_flags_names[other].value()
+                    ),
+                ));
+            }
+
+            if let Some(symbol) = &options.symbol {
+                if let Some(other) = all_flags_options.iter().position(|other: &FlagOptions| {
+                    other
+                        .symbol
+                        .as_ref()
+                        .is_some_and(|other| other.value() == symbol.value())
+                }) {
+                    return Err(Error::new_spanned(
+                        symbol,
+                        format_args!(
+                            "flag `{name}` has the same symbol as the flag `{}`",
+                            all_flags_names[other].value()
+                        ),
+                    ));
+                }
+            }
+
+            if RESERVED_CONSTS.contains(&name.as_str()) || (name == "WIDTH" && width.is_some()) {
+                return Err(Error::new_spanned(
+                    var_name,
+                    format_args!(
+                        "flag `{name}` conflicts with the generated associated constant of the same name"
+                    ),
+                ));
+            }
+
+            if accessors {
+                let snake = to_snake_case(text);
+                for method in [format!("is_{snake}"), format!("set_{snake}")] {
+                    if ACCESSOR_CONFLICTS.contains(&method.as_str()) {
+                        return Err(Error::new_spanned(
+                            var_name,
+                            format_args!(
+                                "the `{method}` accessor of flag `{name}` conflicts with the generated method of the same name"
+                            ),
+                        ));
+                    }
+                }
+            }
+
+            if text != name {
+                var_attrs.push(syn::parse_quote!(#[doc(alias = #text)]));
+            }
+
             let non_doc_attrs: Vec<Attribute> = var_attrs
                 .iter()
                 .filter(|attr| !attr.path().is_ident("doc"))
@@ -143,9 +677,12 @@ impl Bitflag {
                 .collect();
 
             all_flags.push(quote!(Self::#var_name));
-            all_flags_names.push(syn::LitStr::new(&var_name.to_string(), var_name.span()));
+            all_flags_names.push(syn::LitStr::new(&name, var_name.span()));
+            all_flags_texts.push(syn::LitStr::new(text, var_name.span()));
             all_variants.push(var_name.clone());
             all_attrs.push(non_doc_attrs.clone());
+            all_flags_options.push(options);
+            all_variant_attrs.push(var_attrs);
             raw_flags.push(quote! {
                 #(#non_doc_attrs)*
                 #[allow(non_upper_case_globals, dead_code, unused)]
@@ -153,8 +690,21 @@ impl Bitflag {
             });
         }
 
-        for variant in item.variants.iter() {
-            let var_attrs = &variant.attrs;
+        // The flags referenced by the discriminant of each flag, so a flag referencing other flags
+        // only defines the raw flags it depends on instead of all of them
+        let references: Vec<Vec<usize>> = item
+            .variants
+            .iter()
+            .map(|variant| {
+                let mut found = Vec::new();
+                if let Some((_, expr)) = &variant.discriminant {
+                    find_references(expr.to_token_stream(), &all_variants, &mut found);
+                }
+                found
+            })
+            .collect();
+
+        for (i, (variant, var_attrs)) in item.variants.iter().zip(&all_variant_attrs).enumerate() {
             let var_name = &variant.ident;
 
             let expr = match variant.discriminant.as_ref() {
@@ -167,26 +717,88 @@ impl Bitflag {
                 }
             };
 
+            // Errors in the discriminant must point to the user's expression, not to the generated
+            // constant, and be reported once
             let generated = if can_simplify(expr, &all_variants) {
+                let value = quote_spanned!(expr.span()=> Self(#expr));
+
                 quote! {
                     #(#var_attrs)*
-                    #vis const #var_name: Self = Self(#expr);
+                    #vis const #var_name: Self = #value;
                 }
             } else {
+                let mut needed = vec![false; number_flags];
+                let mut pending = vec![i];
+                while let Some(index) = pending.pop() {
+                    if !std::mem::replace(&mut needed[index], true) {
+                        pending.extend(&references[index]);
+                    }
+                }
+                let raw_flags = raw_flags
+                    .iter()
+                    .zip(needed)
+                    .filter_map(|(raw_flag, needed)| needed.then_some(raw_flag));
+
+                // The raw flag of this variant already has the checked expression
                 quote! {
                     #(#var_attrs)*
                     #vis const #var_name: Self = {
                         #(#raw_flags)*
 
-                        Self(#expr)
+                        Self(#var_name)
                     };
                 }
             };
 
-            flags.push(syn::parse2(generated)?);
+            flags.push(generated);
+        }
+
+        // The aliases are constants of their own, so they can't reuse the name of any flag or alias
+        let mut alias_names: Vec<String> = Vec::new();
+        for (i, options) in all_flags_options.iter().enumerate() {
+            let var_name = &all_variants[i];
+            let attrs = &all_attrs[i];
+
+            for alias in &options.aliases {
+                let alias_name = alias.value();
+
+                if let Some(other) =
+                    all_flags_names
+                        .iter()
+                        .zip(&all_flags_texts)
+                        .position(|(name, text)| {
+                            name.value() == alias_name || text.value() == alias_name
+                        })
+                {
+                    return Err(Error::new_spanned(
+                        alias,
+                        format_args!(
+                            "alias `{alias_name}` is already the name of the flag `{}`",
+                            all_flags_names[other].value()
+                        ),
+                    ));
+                }
+
+                if alias_names.contains(&alias_name) {
+                    return Err(Error::new_spanned(
+                        alias,
+                        format_args!("duplicated alias `{alias_name}`"),
+                    ));
+                }
+
+                let mut ident: Ident = alias.parse()?;
+                ident.set_span(alias.span());
+                let doc = format!(" An alias of [`{var_name}`](Self::{var_name}).");
+                flags.push(quote! {
+                    #(#attrs)*
+                    #[doc = #doc]
+                    #vis const #ident: Self = Self::#var_name;
+                });
+                alias_names.push(alias_name);
+            }
         }
 
-        let orig_enum = syn::parse2(quote! {
+        let orig_enum = quote! {
             #(#og_attrs)*
             enum #name {
                 #(
@@ -194,35 +806,212 @@ impl Bitflag {
                     #all_variants,
                 )*
             }
-        })?;
+        };
+
+        let mut stray_bits = Vec::new();
+        for (variant, stray) in find_stray_bits(&ty, &item.variants, &all_flags_options) {
+            if strict {
+                return Err(Error::new_spanned(
+                    variant,
+                    format_args!(
+                        "composite flag `{variant}` has bits {stray:#X} not covered by any single-bit flag"
+                    ),
+                ));
+            }
+
+            stray_bits.push((variant.clone(), stray));
+        }
+
+        if let (Some(module), None) = (&debugger_module, &debugger_visualizer) {
+            return Err(Error::new_spanned(
+                module,
+                "the `debugger_module` option requires the `debugger_visualizer` option",
+            ));
+        }
+
+        let debugger_visualizer = match debugger_visualizer {
+            Some(dir) => {
+                let Some(bits) = width
+                    .as_ref()
+                    .and_then(|width| width.base10_parse().ok())
+                    .or_else(|| {
+                        ty.get_ident()
+                            .and_then(|ident| bits_width(&ident.to_string()))
+                    })
+                else {
+                    return Err(Error::new_spanned(
+                        &ty,
+                        "the `debugger_visualizer` option requires a fixed-width integer bits type",
+                    ));
+                };
+                let mask = if bits == 128 {
+                    u128::MAX
+                } else {
+                    (1 << bits) - 1
+                };
+
+                // The visualizers are written at expansion time, so every flag value must be known
+                let values = eval_flags(&item.variants, mask);
+                for variant in &item.variants {
+                    if variant.attrs.iter().any(|attr| attr.path().is_ident("cfg")) {
+                        return Err(Error::new_spanned(
+                            &variant.ident,
+                            format_args!(
+                                "flag `{}` is under `cfg`, which the `debugger_visualizer` option doesn't support",
+                                variant.ident
+                            ),
+                        ));
+                    }
+
+                    if !values.iter().any(|(name, _)| *name == &variant.ident) {
+                        return Err(Error::new_spanned(
+                            &variant.ident,
+                            format_args!(
+                                "the value of flag `{}` can't be evaluated for the `debugger_visualizer` option, it must be made of integer literals and other flags",
+                                variant.ident
+                            ),
+                        ));
+                    }
+                }
+
+                // The same flags and order as `Flags::ITER_FLAGS`
+                let mut flags: Vec<(String, u128)> = Vec::new();
+                for (((_, value), text), options) in
+                    values.iter().zip(&all_flags_texts).zip(&all_flags_options)
+                {
+                    if *value != 0
+                        && !options.composite
+                        && !flags.iter().any(|(_, other)| other == value)
+                    {
+                        flags.push((text.value(), *value));
+                    }
+                }
+                if iter_bit_order == Some(true) {
+                    flags.sort_by_key(|(_, value)| (value.trailing_zeros(), value.count_ones()));
+                }
+
+                Some(Visualizer {
+                    dir,
+                    module: debugger_module,
+                    bits,
+                    flags,
+                })
+            }
+            None => None,
+        };
 
         let custom_known_bits: Option<Expr> = if let Some(attr) = valid_bits_attr {
             let parsed = ExtraValidBits::from_meta(&attr.meta)?;
 
             Some(parsed.0)
         } else if has_non_exhaustive {
-            Some(syn::parse2(quote! {!0})?)
+            let all_bits = width_mask(&ty, width.as_ref());
+            Some(syn::parse2(all_bits)?)
         } else {
             None
         };
 
+        // The bits of the fields are known bits, even if no flag is defined for them
+        let fields_mask = fields.iter().fold(0, |mask, field| mask | field.mask());
+        let custom_known_bits = match custom_known_bits {
+            _ if fields.is_empty() => custom_known_bits,
+            Some(expr) => Some(syn::parse_quote!((#expr) | (#fields_mask as #ty))),
+            None => Some(syn::parse_quote!(#fields_mask as #ty)),
+        };
+
         Ok(Self {
             vis,
             attrs,
+            forward_attrs,
             name,
             inner_ty: ty,
             derived_traits,
             impl_debug,
             impl_serialize,
             impl_deserialize,
+            impl_encode,
+            impl_decode,
+            impl_scale_encode,
+            impl_scale_decode,
+            impl_type_info,
+            impl_zvariant_type,
+            impl_subset_ord,
+            strict,
+            not_retains,
+            nonzero,
+            consts_mod,
+            parse_error,
+            iter_bit_order: iter_bit_order.unwrap_or(false),
+            width,
+            aggressive_inline,
+            with_methods,
+            accessors,
+            no_ops,
+            no_from,
+            from_bits: match from_bits {
+                Some((_, mode)) => mode,
+                None => ConvertMode::Truncate,
+            },
+            no_from_str,
+            no_iter,
+            display,
+            const_ops,
+            debug_expand,
+            debugger_visualizer,
+            from_file,
+            deserialize_unknown_bits: deserialize_unknown_bits.unwrap_or(UnknownBitsPolicy::Retain),
+            method_names: method_names.unwrap_or(MethodNames::Default),
+            from_str: from_str.unwrap_or(FromStrParser::Default),
+            bson,
+            strum,
+            log_kv,
+            godot,
+            sea_orm,
+            juniper,
+            rocket,
+            validate,
+            msrv,
+            krate: krate.unwrap_or_else(|| syn::parse_quote!(::bitflag_attr)),
             all_attrs,
             all_flags,
             all_flags_names,
+            all_flags_texts,
+            all_flags_options,
             flags,
+            all_variants,
+            all_variant_attrs,
             custom_known_bits,
+            deny_gaps,
+            stray_bits,
+            conversions,
+            widenings,
+            fields,
             orig_enum,
+            hand_written: false,
         })
     }
+
+    /// Generate the code of a struct written by hand, leaving out the struct itself.
+    pub fn hand_written(mut self) -> Self {
+        self.hand_written = true;
+        self
+    }
+
+    /// Generate the code of the flags type, writing it to the file of the `debug_expand` option if
+    /// set.
+    pub fn expand(&self) -> syn::Result<TokenStream> {
+        let tokens = self.to_token_stream();
+
+        if let Some(path) = &self.debug_expand {
+            crate::expand::write_expansion(path, &self.name.to_string(), &tokens)?;
+        }
+
+        if let Some(visualizer) = &self.debugger_visualizer {
+            visualizer.write(&self.name)?;
+        }
+
+        Ok(tokens)
+    }
 }
 
 impl ToTokens for Bitflag {
@@ -230,20 +1019,87 @@ impl ToTokens for Bitflag {
         let Self {
             vis,
             attrs,
+            forward_attrs,
             name,
             inner_ty,
             derived_traits,
             impl_debug,
             impl_serialize,
             impl_deserialize,
+            impl_encode,
+            impl_decode,
+            impl_scale_encode,
+            impl_scale_decode,
+            impl_type_info,
+            impl_zvariant_type,
+            impl_subset_ord,
+            strict,
+            not_retains,
+            nonzero,
+            consts_mod,
+            parse_error,
+            iter_bit_order,
+            width,
+            aggressive_inline,
+            with_methods,
+            accessors,
+            no_ops,
+            no_from,
+            from_bits,
+            no_from_str,
+            no_iter,
+            display,
+            const_ops,
+            debug_expand: _,
+            debugger_visualizer,
+            from_file,
+            deserialize_unknown_bits,
+            method_names,
+            from_str,
+            bson,
+            strum,
+            log_kv,
+            godot,
+            sea_orm,
+            juniper,
+            rocket,
+            validate,
+            msrv,
+            krate,
             all_attrs,
             all_flags,
             all_flags_names,
+            all_flags_texts,
+            all_flags_options,
             flags,
+            all_variants,
+            all_variant_attrs,
             custom_known_bits,
+            deny_gaps,
+            stray_bits,
+            conversions,
+            widenings,
+            fields,
             orig_enum,
+            hand_written,
         } = self;
 
+        let inline = if *aggressive_inline {
+            quote!(#[inline(always)])
+        } else {
+            quote!(#[inline])
+        };
+
+        // The doc links to the crate items must follow the `crate` option too, without the leading
+        // `::` that intra-doc links don't resolve
+        let krate_doc = krate.to_token_stream().to_string().replace(' ', "");
+        let krate_doc = krate_doc.trim_start_matches("::");
+        let parser_link = |item: &str| format!("[`{item}`]({krate_doc}::parser::{item})");
+        let to_symbolic_doc = format!(" See {} for the format.", parser_link("to_symbolic"));
+        let from_symbolic_doc = format!(" See {} for the format.", parser_link("from_symbolic"));
+        let explain_bits_doc = format!(" See {} for the format.", parser_link("explain_bits"));
+        let to_writer_doc = format!(" {}, so a buffer of this length", parser_link("to_writer"));
+
         let extra_valid_bits = if let Some(expr) = custom_known_bits {
             quote! {all |= #expr;}
         } else {
@@ -268,657 +1124,4010 @@ impl ToTokens for Bitflag {
             }
         };
 
-        let const_mut = if cfg!(feature = "const-mut-ref") {
-            quote!(mut)
+        let from_bits_valid = match validate {
+            Some(validate) => quote! {
+                match #validate(&Self(bits)) {
+                    ::core::result::Result::Ok(()) => ::core::option::Option::Some(Self(bits)),
+                    ::core::result::Result::Err(_) => ::core::option::Option::None,
+                }
+            },
+            None => quote!(::core::option::Option::Some(Self(bits))),
+        };
+
+        let validate_impl = match validate {
+            Some(validate) => quote! {
+                fn validate(&self) -> ::core::result::Result<(), &'static str> {
+                    #validate(self)
+                }
+            },
+            None => quote!(),
+        };
+
+        // Whether the constructs stabilized in `version` can be used with the `msrv` option
+        let supports = |version: [u32; 3]| msrv.map_or(true, |msrv| msrv >= version);
+
+        let const_mut = if cfg!(feature = "const-mut-ref") && supports([1, 83, 0]) {
+            quote!(const)
         } else {
             quote!()
         };
 
-        let debug_impl = if !impl_debug {
-            quote! {}
+        let impl_const = if *const_ops { quote!(const) } else { quote!() };
+
+        let all_bits_mask = width_mask(inner_ty, width.as_ref());
+
+        // The number of bits of the bits type that are used
+        let bits_count = match width {
+            Some(width) => quote!(#width),
+            None => quote!(<#inner_ty>::BITS),
+        };
+
+        // The bits above the width don't exist, so they are never kept
+        let retain_value = if width.is_some() {
+            quote!(Self(bits & #all_bits_mask))
         } else {
-            quote! {
-                #[automatically_derived]
-                impl ::core::fmt::Debug for #name {
-                    fn fmt(&self, f: &mut ::core::fmt::Formatter<'_>) -> ::core::fmt::Result {
-                        struct HumanReadable<'a>(&'a #name);
+            quote!(Self(bits))
+        };
 
-                        impl<'a> ::core::fmt::Debug for HumanReadable<'a> {
-                            fn fmt(&self, f: &mut ::core::fmt::Formatter<'_>) -> ::core::fmt::Result {
-                                if self.0.is_empty() {
-                                    ::core::write!(f, "{:#X}", self.0.0)
-                                } else {
-                                    ::bitflag_attr::parser::to_writer(self.0, f)
-                                }
-                            }
-                        }
+        let trait_from_bits_retain = if *strict {
+            quote!(Self::from_bits_truncate(bits))
+        } else {
+            retain_value.clone()
+        };
 
-                        let name = ::core::stringify!(#name);
+        // The permuted bits are arbitrary, so strict types truncate them, and narrower types permute
+        // the bits within their width
+        let bit_permutations = {
+            let permute = |doc: &str, sig: TokenStream, full: TokenStream, narrow: TokenStream| {
+                let bits = match width {
+                    Some(_) => quote! {
+                        let bits = self.bits_unsigned();
+                        let bits = { #narrow } as #inner_ty;
+                    },
+                    None => quote!(let bits = #full;),
+                };
 
-                        f.debug_struct(name)
-                            .field("flags", &HumanReadable(self))
-                            // The width `2 +` is to account for the 0b printed before the binary number
-                            .field("bits", &::core::format_args!("{:#0width$b}", self.0, width = 2 + #inner_ty::BITS as usize))
-                            .finish()
+                quote! {
+                    #[doc = #doc]
+                    #inline
+                    pub const fn #sig -> Self {
+                        #bits
+                        #trait_from_bits_retain
                     }
                 }
+            };
+
+            let rotate_left = permute(
+                " Returns the flags value with the bits rotated left by `n`, wrapping the truncated bits to the end.",
+                quote!(rotate_left(self, n: u32)),
+                quote!(self.0.rotate_left(n)),
+                quote! {
+                    let n = n % #bits_count;
+                    if n == 0 { bits } else { (bits << n) | (bits >> (#bits_count - n)) }
+                },
+            );
+            let rotate_right = permute(
+                " Returns the flags value with the bits rotated right by `n`, wrapping the truncated bits to the beginning.",
+                quote!(rotate_right(self, n: u32)),
+                quote!(self.0.rotate_right(n)),
+                quote! {
+                    let n = n % #bits_count;
+                    if n == 0 { bits } else { (bits >> n) | (bits << (#bits_count - n)) }
+                },
+            );
+            let reverse_bits = permute(
+                " Returns the flags value with the order of the bits reversed.",
+                quote!(reverse_bits(self)),
+                quote!(self.0.reverse_bits()),
+                quote!(bits.reverse_bits() >> (<#inner_ty>::BITS - #bits_count)),
+            );
+
+            // Swapping the bytes of a width that isn't a whole number of bytes has no meaning
+            let whole_bytes = width.as_ref().map_or(true, |width| {
+                width.base10_parse::<u32>().unwrap_or_default() % 8 == 0
+            });
+            let swap_bytes = if whole_bytes {
+                permute(
+                    " Returns the flags value with the byte order of the bits reversed.",
+                    quote!(swap_bytes(self)),
+                    quote!(self.0.swap_bytes()),
+                    quote!(bits.swap_bytes() >> (<#inner_ty>::BITS - #bits_count)),
+                )
+            } else {
+                quote!()
+            };
+
+            quote! {
+                #rotate_left
+                #rotate_right
+                #swap_bytes
+                #reverse_bits
             }
         };
 
-        let serialize_impl = if cfg!(feature = "serde") && *impl_serialize {
+        // Setting an unknown bit would break the invariant of strict types, so it is dropped
+        let set_bit = {
+            let (strict_doc, set_value) = if *strict {
+                (
+                    quote! {
+                        ///
+                        /// This type is strict, setting an unknown bit has no effect.
+                    },
+                    quote!(self.0 = (self.0 | (1 << index)) & Self::ALL_MASK;),
+                )
+            } else {
+                (quote!(), quote!(self.0 |= 1 << index;))
+            };
+
             quote! {
-                #[automatically_derived]
-                impl ::serde::Serialize for #name {
-                    fn serialize<S>(&self, serializer: S) -> ::core::result::Result<S::Ok, S::Error>
-                    where
-                        S: ::serde::Serializer
-                    {
-                        struct AsDisplay<'a>(&'a #name);
+                /// Set the bit at `index` to `value`, counting from the least significant bit.
+                #strict_doc
+                ///
+                /// # Panics
+                ///
+                /// Panics if `index` is not less than the number of bits of the flags type.
+                #inline
+                pub #const_mut fn set_bit(&mut self, index: u32, value: bool) {
+                    ::core::assert!(index < #bits_count, "bit index out of range");
+                    if value {
+                        #set_value
+                    } else {
+                        self.0 &= !(1 << index);
+                    }
+                }
+            }
+        };
 
-                        impl<'a> ::core::fmt::Display for AsDisplay<'a> {
-                            fn fmt(&self, f: &mut ::core::fmt::Formatter<'_>) -> ::core::fmt::Result {
-                                ::bitflag_attr::parser::to_writer(self.0, f)
-                            }
-                        }
-
-                        // Serialize human-readable flags as a string like `"A | B"`
-                        if serializer.is_human_readable() {
-                            serializer.collect_str(&AsDisplay(self))
-                        }
-                        // Serialize non-human-readable flags directly as the underlying bits
-                        else {
-                            self.bits().serialize(serializer)
-                        }
-                    }
+        // In strict mode the unknown bits are never set, so every way to create a value from
+        // arbitrary bits must truncate
+        let from_bits_retain = if *strict {
+            quote! {
+                /// Convert from `bits` value exactly.
+                ///
+                /// # Safety
+                ///
+                /// This type is strict, `bits` must not have any unknown bits set.
+                #inline
+                pub const unsafe fn from_bits_retain(bits: #inner_ty) -> Self {
+                    Self(bits)
                 }
             }
         } else {
-            quote!()
+            quote! {
+                /// Convert from `bits` value exactly.
+                #inline
+                pub const fn from_bits_retain(bits: #inner_ty) -> Self {
+                    #retain_value
+                }
+            }
         };
 
-        let deserialize_impl = if cfg!(feature = "serde") && *impl_deserialize {
+        // The bits written through the reference can't be truncated, so strict and narrower types
+        // make it unsafe
+        let as_bits_mut_doc = quote! {
+            /// Return a mutable reference to the underlying bits, so a C function filling a flags
+            /// out-parameter can write to the flags value directly.
+        };
+        let as_bits_mut = if *strict {
             quote! {
-                #[automatically_derived]
-                impl<'de> ::serde::Deserialize<'de> for #name {
-                    fn deserialize<D>(deserializer: D) -> ::core::result::Result<Self, D::Error>
-                    where
-                        D: ::serde::Deserializer<'de>
-                    {
-                        if deserializer.is_human_readable() {
-                            struct HelperVisitor(::core::marker::PhantomData<#name>);
+                #as_bits_mut_doc
+                ///
+                /// # Safety
+                ///
+                /// This type is strict, no unknown bits must be written.
+                #inline
+                pub #const_mut unsafe fn as_bits_mut(&mut self) -> &mut #inner_ty {
+                    &mut self.0
+                }
+            }
+        } else if let Some(width) = width {
+            let safety =
+                format!("No bits past the {width} bits width of the type must be written.");
 
-                            impl<'de> ::serde::de::Visitor<'de> for HelperVisitor {
-                                type Value = #name;
+            quote! {
+                #as_bits_mut_doc
+                ///
+                /// # Safety
+                ///
+                #[doc = #safety]
+                #inline
+                pub #const_mut unsafe fn as_bits_mut(&mut self) -> &mut #inner_ty {
+                    &mut self.0
+                }
+            }
+        } else {
+            quote! {
+                #as_bits_mut_doc
+                #inline
+                pub #const_mut fn as_bits_mut(&mut self) -> &mut #inner_ty {
+                    &mut self.0
+                }
+            }
+        };
 
-                                fn expecting(&self,  f: &mut ::core::fmt::Formatter<'_>) -> ::core::fmt::Result {
-                                    f.write_str("a string value of `|` separated flags")
-                                }
+        let all_bits_value = if *strict {
+            quote!(Self::all())
+        } else {
+            quote!(Self(#all_bits_mask))
+        };
 
-                                fn visit_str<E>(self, flags: &str) -> ::core::result::Result<Self::Value, E>
-                                where
-                                    E: ::serde::de::Error,
-                                {
-                                    ::bitflag_attr::parser::from_text(flags).map_err(|e| E::custom(e))
-                                }
-                            }
+        let not_value = if *strict {
+            quote!(Self(!self.0 & Self::ALL_MASK))
+        } else if width.is_some() {
+            quote!(Self(!self.0 & #all_bits_mask))
+        } else {
+            quote!(Self(!self.0))
+        };
 
-                            deserializer.deserialize_str(HelperVisitor(::core::marker::PhantomData))
-                        } else {
-                            let bits = #inner_ty::deserialize(deserializer)?;
+        let not_op = if *not_retains {
+            quote! {
+                /// The bitwise NOT of the flags value, keeping unknown bits.
+                ///
+                /// This type uses the `not_retains` option, so this is equivalent to [`not`](Self::not)
+                /// instead of [`complement`](Self::complement).
+                #inline
+                fn not(self) -> Self::Output {
+                    #name::not(self)
+                }
+            }
+        } else {
+            quote! {
+                /// The complement of the flags value, truncating unknown bits.
+                ///
+                /// This is equivalent to [`complement`](Self::complement), use [`not`](Self::not) for
+                /// a bitwise NOT keeping unknown bits.
+                #inline
+                fn not(self) -> Self::Output {
+                    self.complement()
+                }
+            }
+        };
 
-                            Ok(#name::from_bits_retain(bits))
+        let nonzero_impl = if *nonzero {
+            quote! {
+                #[allow(non_upper_case_globals)]
+                impl #name {
+                    /// Convert to a flags value stored as a non-zero integer, returning [`None`] if no
+                    /// bits are set.
+                    #inline
+                    pub const fn to_non_empty(self) -> ::core::option::Option<#krate::NonEmpty<Self>> {
+                        match <<#inner_ty as #krate::BitsPrimitive>::NonZero>::new(self.0) {
+                            ::core::option::Option::Some(bits) => {
+                                ::core::option::Option::Some(#krate::NonEmpty::__private_const_new(bits))
+                            }
+                            ::core::option::Option::None => ::core::option::Option::None,
                         }
                     }
+
+                    /// Convert from a flags value stored as a non-zero integer.
+                    #inline
+                    pub const fn from_non_empty(value: #krate::NonEmpty<Self>) -> Self {
+                        Self(value.non_zero_bits().get())
+                    }
+                }
+
+                #[automatically_derived]
+                impl ::core::convert::From<#krate::NonEmpty<#name>> for #name {
+                    #inline
+                    fn from(value: #krate::NonEmpty<#name>) -> Self {
+                        Self::from_non_empty(value)
+                    }
                 }
             }
         } else {
             quote!()
         };
 
-        let doc_from_iter = format!("Create a `{name}` from a iterator of flags.");
-        let generated = quote! {
-            #[repr(transparent)]
-            #(#attrs)*
-            #[derive(#(#derived_traits,)*)]
-            #vis struct #name(#inner_ty)
-            where
-                #inner_ty: ::bitflag_attr::BitsPrimitive;
+        // The known bits, including the extra valid bits, must be contiguous from the lowest bit,
+        // which is the case when adding one to them doesn't carry into any of them.
+        let deny_gaps_check = if let Some(span) = deny_gaps {
+            let message = format!("`{name}` has bits without a named flag below its highest flag");
 
-            #[allow(non_upper_case_globals)]
-            impl #name {
-                #[doc(hidden)]
-                #[allow(clippy::unused_unit)]
-                const __OG: () = {
-                    {
-                        // Original enum
-                        // This is a hack to make LSP coloring to still sees the original enum variant as a Enum variant token.
-                        #orig_enum
+            quote_spanned! {*span=>
+                const _: () = {
+                    let known = #name::all().0;
+                    if known & known.wrapping_add(1) != 0 {
+                        ::core::panic!(#message);
                     }
-                    ()
                 };
-
-                #(#flags)*
             }
+        } else {
+            quote!()
+        };
 
-            #[allow(non_upper_case_globals)]
-            impl #name {
-                /// Return the underlying bits of this bitflag.
-                #[inline]
-                pub const fn bits(&self) -> #inner_ty {
-                    self.0
-                }
+        let width_impl = if let Some(width) = width {
+            let bytes = width.base10_parse::<u32>().unwrap_or_default().div_ceil(8) as usize;
+            let width_message = format!("`width` is larger than the bits type of `{name}`");
+            let fit_message = format!("the flags of `{name}` don't fit in its {width} bits width");
+
+            // Only the assertions point at the option, the methods would get the dead code lints
+            // of the user's type
+            let width_checks = quote_spanned! {width.span()=>
+                const _: () = {
+                    if #width > <#inner_ty>::BITS {
+                        ::core::panic!(#width_message);
+                    }
 
-                /// Converts from a `bits` value. Returning [`None`] is any unknown bits are set.
-                #[inline]
-                pub const fn from_bits(bits: #inner_ty) -> Option<Self> {
-                    let truncated = Self::from_bits_truncate(bits).0;
+                    if #name::all().0 & !(#all_bits_mask) != 0 {
+                        ::core::panic!(#fit_message);
+                    }
+                };
+            };
 
-                    if truncated == bits {
-                        Some(Self(bits))
-                    } else {
-                        None
+            quote! {
+                #width_checks
+
+                impl #name {
+                    /// The number of bits of the flags type.
+                    pub const WIDTH: u32 = #width;
+
+                    /// Return the memory representation of the flags value as a byte array in
+                    /// little-endian byte order, using only the bytes needed for its width.
+                    #inline
+                    pub const fn to_le_bytes(self) -> [u8; #bytes] {
+                        let full = self.0.to_le_bytes();
+                        let mut bytes = [0; #bytes];
+                        let mut i = 0;
+                        while i < #bytes {
+                            bytes[i] = full[i];
+                            i += 1;
+                        }
+                        bytes
                     }
-                }
 
-                /// Convert from `bits` value, unsetting any unknown bits.
-                #[inline]
-                pub const fn from_bits_truncate(bits: #inner_ty) -> Self {
-                    Self(bits & Self::all().0)
-                }
+                    /// Return the memory representation of the flags value as a byte array in
+                    /// big-endian byte order, using only the bytes needed for its width.
+                    #inline
+                    pub const fn to_be_bytes(self) -> [u8; #bytes] {
+                        let full = self.0.to_be_bytes();
+                        let offset = full.len() - #bytes;
+                        let mut bytes = [0; #bytes];
+                        let mut i = 0;
+                        while i < #bytes {
+                            bytes[i] = full[offset + i];
+                            i += 1;
+                        }
+                        bytes
+                    }
 
-                /// Convert from `bits` value exactly.
-                #[inline]
-                pub const fn from_bits_retain(bits: #inner_ty) -> Self {
-                    Self(bits)
-                }
+                    /// Create a flags value from its representation as a byte array in
+                    /// little-endian byte order, like the one returned by [`to_le_bytes`](Self::to_le_bytes).
+                    #inline
+                    pub const fn from_le_bytes(bytes: [u8; #bytes]) -> Self {
+                        let mut full = [0; ::core::mem::size_of::<#inner_ty>()];
+                        let mut i = 0;
+                        while i < #bytes {
+                            full[i] = bytes[i];
+                            i += 1;
+                        }
+                        let bits = <#inner_ty>::from_le_bytes(full);
+                        #trait_from_bits_retain
+                    }
 
-                /// Convert from a flag `name`.
-                #[inline]
-                pub fn from_flag_name(name: &str) -> Option<Self> {
-                    match name {
-                        #(
-                            #(#all_attrs)*
-                            #all_flags_names => Some(#all_flags),
-                        )*
-                        _ => None
+                    /// Create a flags value from its representation as a byte array in
+                    /// big-endian byte order, like the one returned by [`to_be_bytes`](Self::to_be_bytes).
+                    #inline
+                    pub const fn from_be_bytes(bytes: [u8; #bytes]) -> Self {
+                        let mut full = [0; ::core::mem::size_of::<#inner_ty>()];
+                        let offset = full.len() - #bytes;
+                        let mut i = 0;
+                        while i < #bytes {
+                            full[offset + i] = bytes[i];
+                            i += 1;
+                        }
+                        let bits = <#inner_ty>::from_be_bytes(full);
+                        #trait_from_bits_retain
                     }
                 }
+            }
+        } else {
+            quote!()
+        };
 
-                /// Construct a flags value with all bits unset.
-                #[inline]
-                pub const fn empty() -> Self {
-                    Self(0)
+        // The trait defaults use all the bits of the bits type
+        let width_flags_impl = if width.is_some() {
+            quote! {
+                fn all_bits() -> Self {
+                    #all_bits_value
                 }
 
-                /// Returns `true` if the flag value has all bits unset.
-                #[inline]
-                pub const fn is_empty(&self) -> bool {
-                    self.0 == 0
+                fn is_all_bits(&self) -> bool {
+                    self.0 == #all_bits_mask
                 }
+            }
+        } else {
+            quote!()
+        };
 
-                /// Returns a flag value that contains all value.
-                ///
-                /// This will include bits that do not have any flags/meaning.
-                /// Use [`all`](Self::all) if you want only the specified flags set.
-                #[inline]
-                pub const fn all_bits() -> Self {
-                    Self(!0)
+        // Zero-bit flags are always contained and never intersected, so they must be opted into
+        let zero_check = all_flags_options
+            .iter()
+            .zip(all_attrs.iter().zip(all_flags_names))
+            .filter(|(opts, _)| !opts.zero)
+            .map(|(_, (attrs, flag_name))| {
+                let flag = Ident::new(&flag_name.value(), flag_name.span());
+                let message = format!(
+                    "flag `{flag_name}` of `{name}` has no bits set, mark it with `#[flag(zero)]` if this is intended",
+                    flag_name = flag_name.value()
+                );
+
+                // One constant per flag, so every zero-bit flag is reported
+                quote_spanned! {flag_name.span()=>
+                    #(#attrs)*
+                    const _: () = {
+                        if #name::#flag.0 == 0 {
+                            ::core::panic!(#message);
+                        }
+                    };
                 }
+            });
 
-                /// Returns `true` if the flag value contains all value bits set.
-                ///
-                /// This will check for all bits.
-                /// Use [`is_all`](Self::is_all) if you want to check for all specified flags.
-                #[inline]
-                pub const fn is_all_bits(&self) -> bool {
-                    self.0 == !0
+        // There is no stable way for a proc-macro to emit a warning, so use a deprecated item
+        let stray_bits_warnings = stray_bits.iter().map(|(flag, stray)| {
+            let note = format!(
+                "composite flag `{flag}` has bits {stray:#X} not covered by any single-bit flag, mark it with `#[flag(allow_stray_bits)]` if this is intended"
+            );
+
+            quote_spanned! {flag.span()=>
+                const _: () = {
+                    #[deprecated(note = #note)]
+                    #[allow(non_upper_case_globals)]
+                    const #flag: () = ();
+                    #flag
+                };
+            }
+        });
+
+        // Stable sort of the yielded flags by their lowest bit, and then by their number of bits
+        let sort_iter_flags = if *iter_bit_order {
+            quote! {
+                const fn goes_after(a: #inner_ty, b: #inner_ty) -> bool {
+                    let (a_low, b_low) = (a.trailing_zeros(), b.trailing_zeros());
+                    a_low > b_low || (a_low == b_low && a.count_ones() > b.count_ones())
                 }
 
-                /// Construct a flag value with all known flags set.
-                ///
-                /// This will only set the flags specified as associated constant.
-                #[inline]
-                pub const fn all() -> Self {
-                    let mut all = 0;
+                let mut i = 1;
+                while i < LEN {
+                    let mut j = i;
+                    while j > 0 && goes_after(table[j - 1].1 .0, table[j].1 .0) {
+                        let previous = table[j - 1];
+                        table[j - 1] = table[j];
+                        table[j] = previous;
+                        j -= 1;
+                    }
+                    i += 1;
+                }
+            }
+        } else {
+            quote!()
+        };
 
-                    #(
-                        #(#all_attrs)*{
-                            all |= #all_flags.0;
+        let with_methods_impl = if *with_methods {
+            let methods = all_variants.iter().zip(all_attrs).zip(all_flags_texts).map(
+                |((var_name, attrs), text)| {
+                    let snake = to_snake_case(&text.value());
+                    let with = Ident::new(&format!("with_{snake}"), var_name.span());
+                    let without = Ident::new(&format!("without_{snake}"), var_name.span());
+                    let with_doc = format!(
+                        " Returns the flags value with [`{var_name}`](Self::{var_name}) set."
+                    );
+                    let without_doc = format!(
+                        " Returns the flags value with [`{var_name}`](Self::{var_name}) unset."
+                    );
+
+                    quote! {
+                        #(#attrs)*
+                        #[doc = #with_doc]
+                        #inline
+                        pub const fn #with(self) -> Self {
+                            self.or(Self::#var_name)
                         }
-                    )*
 
-                    #extra_valid_bits;
+                        #(#attrs)*
+                        #[doc = #without_doc]
+                        #inline
+                        pub const fn #without(self) -> Self {
+                            self.difference(Self::#var_name)
+                        }
+                    }
+                },
+            );
 
-                    Self(all)
+            quote! {
+                impl #name {
+                    #(#methods)*
                 }
+            }
+        } else {
+            quote!()
+        };
 
-                /// Returns `true` if the flag value contais all known flags.
-                #[inline]
-                pub const fn is_all(&self) -> bool {
-                    Self::all().0 | self.0 == self.0
-                }
+        let accessors_impl = if *accessors {
+            let methods = all_variants.iter().zip(all_attrs).zip(all_flags_texts).map(
+                |((var_name, attrs), text)| {
+                    let snake = to_snake_case(&text.value());
+                    let is = Ident::new(&format!("is_{snake}"), var_name.span());
+                    let set = Ident::new(&format!("set_{snake}"), var_name.span());
+                    let is_doc = format!(
+                        " Returns `true` if [`{var_name}`](Self::{var_name}) is set in the flags value."
+                    );
+                    let set_doc = format!(" Set or unset [`{var_name}`](Self::{var_name}) according to `value`.");
+
+                    quote! {
+                        #(#attrs)*
+                        #[doc = #is_doc]
+                        #inline
+                        pub const fn #is(&self) -> bool {
+                            self.contains(Self::#var_name)
+                        }
 
-                /// Returns `true` if there are any unknown bits set in the flag value.
-                #[inline]
-                pub const fn contains_unknown_bits(&self) -> bool {
-                    Self::all().0 & self.0 != self.0
-                }
+                        #(#attrs)*
+                        #[doc = #set_doc]
+                        #inline
+                        pub #const_mut fn #set(&mut self, value: bool) {
+                            if value {
+                                self.0 = self.or(Self::#var_name).0
+                            } else {
+                                self.0 = self.difference(Self::#var_name).0
+                            }
+                        }
+                    }
+                },
+            );
 
-                /// Returns a bit flag that only has bits corresponding to the specified flags as associated constant.
-                #[inline]
-                pub const fn truncated(&self) -> Self {
-                    Self(self.0 & Self::all().0)
+            quote! {
+                impl #name {
+                    #(#methods)*
                 }
+            }
+        } else {
+            quote!()
+        };
 
-                /// Removes unknown bits from the flag value.
-                #[inline]
-                pub #const_mut fn truncate(&mut self) {
-                    *self = Self::from_bits_truncate(self.0);
-                }
+        let consts_impl = if let Some(consts_mod) = consts_mod {
+            let doc = format!("The flags of [`{name}`] as free constants, to be glob-imported.");
+            let consts = all_variants
+                .iter()
+                .zip(all_variant_attrs)
+                .map(|(ident, attrs)| {
+                    quote! {
+                        #(#attrs)*
+                        pub const #ident: super::#name = super::#name::#ident;
+                    }
+                });
 
-                /// Returns `true` if this flag value intersects with any value in `other`.
-                ///
-                /// This is equivalent to `(self & other) != Self::empty()`
-                #[inline]
-                pub const fn intersects(&self, other: Self) -> bool {
-                    (self.0 & other.0) != Self::empty().0
+            quote! {
+                #[doc = #doc]
+                #[allow(dead_code, non_upper_case_globals)]
+                #vis mod #consts_mod {
+                    #(#consts)*
                 }
+            }
+        } else {
+            quote!()
+        };
 
-                /// Returns `true` if this flag value contains all values of `other`.
-                ///
-                /// This is equivalent to `(self & other) == other`
-                #[inline]
-                pub const fn contains(&self, other: Self) -> bool {
-                    (self.0 & other.0) == other.0
+        let (parse_error_ty, parse_error_map, parse_error_impl) = if let Some(parse_error) =
+            parse_error
+        {
+            let doc = format!("An error encountered while parsing a [`{name}`] from text.");
+
+            // `core::error` is only stable on Rust 1.81, so it is opt-in with the `core-error` feature
+            // and left out for older `msrv` versions
+            let error_impl = if cfg!(feature = "core-error") && supports([1, 81, 0]) {
+                quote! {
+                    #[automatically_derived]
+                    impl ::core::error::Error for #parse_error {
+                        fn source(&self) -> ::core::option::Option<&(dyn ::core::error::Error + 'static)> {
+                            ::core::option::Option::Some(&self.0)
+                        }
+                    }
                 }
+            } else {
+                quote!()
+            };
 
-                /// Returns the bitwise NOT of the flag.
+            let parse_error_impl = quote! {
+                #[doc = #doc]
                 ///
-                /// This function does not truncate unused bits (bits that do not have any flags/meaning).
-                /// Use [`complement`](Self::complement) if you want that the result to be truncated in one call.
-                #[inline]
-                #[doc(alias = "complement")]
-                pub const fn not(self) -> Self {
-                    Self(!self.0)
+                /// Its message has the name of the flags type and the names of the valid flags.
+                #[derive(::core::fmt::Debug)]
+                #vis struct #parse_error(#krate::parser::ParseError);
+
+                impl #parse_error {
+                    /// Return the error of the parser.
+                    #inline
+                    pub const fn parse_error(&self) -> &#krate::parser::ParseError {
+                        &self.0
+                    }
                 }
 
-                /// Returns the bitwise AND of the flag.
-                #[inline]
-                #[doc(alias = "intersection")]
-                pub const fn and(self, other: Self) -> Self {
-                    Self(self.0 & other.0)
+                #[automatically_derived]
+                impl ::core::convert::From<#krate::parser::ParseError> for #parse_error {
+                    #inline
+                    fn from(err: #krate::parser::ParseError) -> Self {
+                        Self(err)
+                    }
                 }
 
-                /// Returns the bitwise OR of the flag with `other`.
-                #[inline]
-                #[doc(alias = "union")]
-                pub const fn or(self, other: Self) -> Self {
-                    Self(self.0 | other.0)
+                #[automatically_derived]
+                impl ::core::fmt::Display for #parse_error {
+                    fn fmt(&self, f: &mut ::core::fmt::Formatter<'_>) -> ::core::fmt::Result {
+                        ::core::write!(f, "invalid value for {} (valid: ", ::core::stringify!(#name))?;
+                        for (i, (name, _)) in ::core::iter::Iterator::enumerate(<#name as #krate::Flags>::KNOWN_FLAGS.iter()) {
+                            if i != 0 {
+                                f.write_str(", ")?;
+                            }
+                            f.write_str(name)?;
+                        }
+                        ::core::write!(f, "): {}", self.0)
+                    }
                 }
 
-                /// Returns the bitwise XOR of the flag with `other`.
-                #[inline]
-                #[doc(alias = "symmetric_difference")]
+                #error_impl
+            };
+
+            (
+                quote!(#parse_error),
+                quote!(.map_err(#parse_error)),
+                parse_error_impl,
+            )
+        } else {
+            (quote!(#krate::parser::ParseError), quote!(), quote!())
+        };
+
+        let labels = if all_flags_options.iter().any(|opts| opts.label.is_some()) {
+            let labels = all_flags_options
+                .iter()
+                .zip(all_attrs.iter().zip(all_flags_texts))
+                .filter_map(|(opts, (attrs, flag_name))| {
+                    let label = opts.label.as_ref()?;
+                    Some(quote! {
+                        #(#attrs)*
+                        (#flag_name, #label),
+                    })
+                });
+
+            quote! {
+                const LABELS: &'static [(&'static str, &'static str)] = &[#(#labels)*];
+            }
+        } else {
+            quote!()
+        };
+
+        // The aliases are parsed by the generated `from_flag_name`, which the parser must use instead
+        // of looking up `KNOWN_FLAGS`
+        let alias_arms: Vec<_> = all_flags_options
+            .iter()
+            .zip(all_attrs.iter().zip(all_flags))
+            .flat_map(|(opts, (attrs, flag))| {
+                opts.aliases.iter().map(move |alias| {
+                    quote! {
+                        #(#attrs)*
+                        #alias => ::core::option::Option::Some(#flag),
+                    }
+                })
+            })
+            .collect();
+
+        // The composite flags are known, but never yielded
+        let iter_known = if all_flags_options.iter().any(|opts| opts.composite) {
+            let iter_flags = all_flags_options
+                .iter()
+                .zip(
+                    all_attrs
+                        .iter()
+                        .zip(all_flags_texts.iter().zip(all_variants)),
+                )
+                .filter(|(opts, _)| !opts.composite)
+                .map(|(_, (attrs, (text, variant)))| {
+                    quote! {
+                        #(#attrs)*
+                        (#text, #name::#variant),
+                    }
+                });
+
+            quote! {
+                const KNOWN: &[(&str, #name)] = &[#(#iter_flags)*];
+            }
+        } else {
+            quote! {
+                const KNOWN: &[(&str, #name)] = <#name as #krate::Flags>::KNOWN_FLAGS;
+            }
+        };
+
+        let from_name_impl = if alias_arms.is_empty() {
+            quote!()
+        } else {
+            quote! {
+                fn from_flag_name(name: &str) -> ::core::option::Option<Self> {
+                    #name::from_flag_name(name)
+                }
+
+                fn from_name(name: &str) -> ::core::option::Option<Self> {
+                    #name::from_flag_name(name)
+                }
+            }
+        };
+
+        let symbols: Vec<_> = all_flags_options
+            .iter()
+            .zip(all_attrs.iter().zip(all_flags))
+            .filter_map(|(opts, (attrs, flag))| {
+                let symbol = opts.symbol.as_ref()?;
+                Some(quote! {
+                    #(#attrs)*
+                    (#symbol, #flag),
+                })
+            })
+            .collect();
+
+        let (symbols, symbolic_methods) = if symbols.is_empty() {
+            (quote!(), quote!())
+        } else {
+            (
+                quote! {
+                    const SYMBOLS: &'static [(char, Self)] = &[#(#symbols)*];
+                },
+                quote! {
+                    /// Display the flags in the symbolic format, like `rw-`, with the symbol of each
+                    /// contained flag and `-` for the others.
+                    ///
+                    #[doc = #to_symbolic_doc]
+                    #inline
+                    pub fn to_symbolic(&self) -> #krate::parser::Symbolic<Self> {
+                        #krate::parser::to_symbolic(self)
+                    }
+
+                    /// Parse flags from the symbolic format, like `rw-`, with the symbol of a flag to set
+                    /// it or `-` to leave it unset, for each flag with a symbol.
+                    ///
+                    #[doc = #from_symbolic_doc]
+                    #inline
+                    pub fn from_symbolic(input: &str) -> ::core::result::Result<Self, #krate::parser::ParseError> {
+                        #krate::parser::from_symbolic(input)
+                    }
+                },
+            )
+        };
+
+        // The first paragraph of the documentation of each flag, joined in a single line
+        let docs: Vec<_> = all_variant_attrs
+            .iter()
+            .zip(all_attrs.iter().zip(all_flags_texts))
+            .filter_map(|(var_attrs, (attrs, flag_name))| {
+                let doc = doc_summary(var_attrs)?;
+                Some(quote! {
+                    #(#attrs)*
+                    (#flag_name, #doc),
+                })
+            })
+            .collect();
+
+        let docs = if docs.is_empty() {
+            quote!()
+        } else {
+            quote! {
+                const DOCS: &'static [(&'static str, &'static str)] = &[#(#docs)*];
+            }
+        };
+
+        let version_impl = if all_flags_options.iter().any(|opts| opts.since.is_some()) {
+            let available = all_flags_options.iter().map(|opts| match &opts.since {
+                Some([major, minor, patch]) => quote! {
+                    version.at_least(#krate::Version::new(#major, #minor, #patch))
+                },
+                None => quote!(true),
+            });
+
+            let since = all_flags_options
+                .iter()
+                .zip(all_attrs.iter().zip(all_flags))
+                .filter_map(|(opts, (attrs, flag))| {
+                    let [major, minor, patch] = opts.since.as_ref()?;
+
+                    Some(quote! {
+                        #(#attrs)*
+                        {
+                            if self.0 == #flag.0 {
+                                return ::core::option::Option::Some(#krate::Version::new(#major, #minor, #patch));
+                            }
+                        }
+                    })
+                });
+
+            quote! {
+                #[allow(non_upper_case_globals)]
+                impl #name {
+                    /// Returns a flags value with the flags available at `version`.
+                    ///
+                    /// Flags without an introduction version are always available.
+                    #inline
+                    pub const fn valid_for_version(version: #krate::Version) -> Self {
+                        let mut bits = 0;
+
+                        #(
+                            #(#all_attrs)*
+                            {
+                                if #available {
+                                    bits |= #all_flags.0;
+                                }
+                            }
+                        )*
+
+                        Self(bits)
+                    }
+
+                    /// Returns the version a named flag was introduced in.
+                    ///
+                    /// Returns [`None`] if the value is not exactly a named flag with an introduction
+                    /// version.
+                    #inline
+                    pub const fn since(&self) -> ::core::option::Option<#krate::Version> {
+                        #(#since)*
+
+                        ::core::option::Option::None
+                    }
+                }
+            }
+        } else {
+            quote!()
+        };
+
+        let debug_impl = if !impl_debug {
+            quote! {}
+        } else {
+            quote! {
+                #[automatically_derived]
+                impl ::core::fmt::Debug for #name {
+                    fn fmt(&self, f: &mut ::core::fmt::Formatter<'_>) -> ::core::fmt::Result {
+                        struct HumanReadable<'a>(&'a #name);
+
+                        impl<'a> ::core::fmt::Debug for HumanReadable<'a> {
+                            fn fmt(&self, f: &mut ::core::fmt::Formatter<'_>) -> ::core::fmt::Result {
+                                if self.0.is_empty() {
+                                    ::core::write!(f, "{:#X}", self.0.0)
+                                } else if f.alternate() {
+                                    // One contained flag per line, then the unknown bits
+                                    let mut list = f.debug_list();
+                                    let mut iter = self.0.iter_names();
+                                    for (name, _) in &mut iter {
+                                        list.entry(&::core::format_args!("{}", name));
+                                    }
+
+                                    let remaining = iter.remaining();
+                                    if !remaining.is_empty() {
+                                        list.entry(&::core::format_args!("{:#X}", remaining.0));
+                                    }
+
+                                    list.finish()
+                                } else {
+                                    #krate::parser::to_writer(self.0, f)
+                                }
+                            }
+                        }
+
+                        let name = ::core::stringify!(#name);
+
+                        f.debug_struct(name)
+                            .field("flags", &HumanReadable(self))
+                            // The width `2 +` is to account for the 0b printed before the binary number
+                            .field("bits", &::core::format_args!("{:#0width$b}", self.0, width = 2 + #bits_count as usize))
+                            .finish()
+                    }
+                }
+            }
+        };
+
+        let partial_ord_impl = if *impl_subset_ord {
+            quote! {
+                #[automatically_derived]
+                impl ::core::cmp::PartialOrd for #name {
+                    /// Compare flags values by set inclusion: `a <= b` if `b` contains all bits of `a`.
+                    #inline
+                    fn partial_cmp(&self, other: &Self) -> ::core::option::Option<::core::cmp::Ordering> {
+                        if self.0 == other.0 {
+                            ::core::option::Option::Some(::core::cmp::Ordering::Equal)
+                        } else if other.contains(*self) {
+                            ::core::option::Option::Some(::core::cmp::Ordering::Less)
+                        } else if self.contains(*other) {
+                            ::core::option::Option::Some(::core::cmp::Ordering::Greater)
+                        } else {
+                            ::core::option::Option::None
+                        }
+                    }
+                }
+            }
+        } else {
+            quote!()
+        };
+
+        let serialize_impl = if cfg!(feature = "serde") && *impl_serialize && *impl_zvariant_type {
+            // The D-Bus signature of the `zvariant::Type` implementation is the one of the bits
+            quote! {
+                #[automatically_derived]
+                impl ::serde::Serialize for #name {
+                    fn serialize<S>(&self, serializer: S) -> ::core::result::Result<S::Ok, S::Error>
+                    where
+                        S: ::serde::Serializer
+                    {
+                        ::serde::Serialize::serialize(&self.0, serializer)
+                    }
+                }
+            }
+        } else if cfg!(feature = "serde") && *impl_serialize {
+            quote! {
+                #[automatically_derived]
+                impl ::serde::Serialize for #name {
+                    fn serialize<S>(&self, serializer: S) -> ::core::result::Result<S::Ok, S::Error>
+                    where
+                        S: ::serde::Serializer
+                    {
+                        struct AsDisplay<'a>(&'a #name);
+
+                        impl<'a> ::core::fmt::Display for AsDisplay<'a> {
+                            fn fmt(&self, f: &mut ::core::fmt::Formatter<'_>) -> ::core::fmt::Result {
+                                #krate::parser::to_writer(self.0, f)
+                            }
+                        }
+
+                        // Serialize human-readable flags as a string like `"A | B"`
+                        if serializer.is_human_readable() {
+                            serializer.collect_str(&AsDisplay(self))
+                        }
+                        // Serialize non-human-readable flags directly as the underlying bits
+                        else {
+                            ::serde::Serialize::serialize(&self.0, serializer)
+                        }
+                    }
+                }
+            }
+        } else {
+            quote!()
+        };
+
+        // Every decoder builds the value from its bits with the unknown bits policy, and checks it
+        // with `validate`
+        let decode_unknown_bits = match deserialize_unknown_bits {
+            UnknownBitsPolicy::Retain => quote!(<Self as #krate::Flags>::from_bits_retain(bits)),
+            UnknownBitsPolicy::Truncate => {
+                quote!(<Self as #krate::Flags>::from_bits_truncate(bits))
+            }
+            UnknownBitsPolicy::Deny => quote! {{
+                let unknown = bits & !#krate::Flags::bits(&<Self as #krate::Flags>::all());
+                if unknown != 0 {
+                    return ::core::result::Result::Err(#krate::DecodeError::UnknownBits(
+                        #krate::UnknownBitsError::new(unknown),
+                    ));
+                }
+
+                <Self as #krate::Flags>::from_bits_retain(bits)
+            }},
+        };
+        let decode_bits_impl = quote! {
+            impl #name {
+                // Used by the generated decoders
+                #[doc(hidden)]
+                pub fn __decode_bits(bits: #inner_ty) -> ::core::result::Result<Self, #krate::DecodeError<#inner_ty>> {
+                    let flags = #decode_unknown_bits;
+
+                    match #krate::Flags::validate(&flags) {
+                        ::core::result::Result::Ok(()) => ::core::result::Result::Ok(flags),
+                        ::core::result::Result::Err(reason) => {
+                            ::core::result::Result::Err(#krate::DecodeError::Invalid(reason))
+                        }
+                    }
+                }
+            }
+        };
+
+        // Build the deserialized value from its `bits`, with `error` the error type of the path
+        let deserialize_bits = |error: TokenStream| {
+            quote! {
+                #name::__decode_bits(bits).map_err(|error| <#error as ::serde::de::Error>::custom(error))
+            }
+        };
+        let from_text_bits = deserialize_bits(quote!(E));
+        let from_raw_bits = deserialize_bits(quote!(D::Error));
+
+        let deserialize_impl = if cfg!(feature = "serde")
+            && *impl_deserialize
+            && *impl_zvariant_type
+        {
+            quote! {
+                #[automatically_derived]
+                impl<'de> ::serde::Deserialize<'de> for #name {
+                    fn deserialize<D>(deserializer: D) -> ::core::result::Result<Self, D::Error>
+                    where
+                        D: ::serde::Deserializer<'de>
+                    {
+                        let bits = <#inner_ty as ::serde::Deserialize<'de>>::deserialize(deserializer)?;
+
+                        #from_raw_bits
+                    }
+                }
+            }
+        } else if cfg!(feature = "serde") && *impl_deserialize {
+            quote! {
+                #[automatically_derived]
+                impl<'de> ::serde::Deserialize<'de> for #name {
+                    fn deserialize<D>(deserializer: D) -> ::core::result::Result<Self, D::Error>
+                    where
+                        D: ::serde::Deserializer<'de>
+                    {
+                        if deserializer.is_human_readable() {
+                            struct HelperVisitor(::core::marker::PhantomData<#name>);
+
+                            impl<'de> ::serde::de::Visitor<'de> for HelperVisitor {
+                                type Value = #name;
+
+                                fn expecting(&self,  f: &mut ::core::fmt::Formatter<'_>) -> ::core::fmt::Result {
+                                    f.write_str("a string value of `|` separated flags")
+                                }
+
+                                fn visit_str<E>(self, flags: &str) -> ::core::result::Result<Self::Value, E>
+                                where
+                                    E: ::serde::de::Error,
+                                {
+                                    // Validated once the unknown bits are handled
+                                    let flags: #name = #krate::parser::from_text_unvalidated(flags).map_err(|e| E::custom(e))?;
+                                    let bits = #krate::Flags::bits(&flags);
+
+                                    #from_text_bits
+                                }
+                            }
+
+                            deserializer.deserialize_str(HelperVisitor(::core::marker::PhantomData))
+                        } else {
+                            let bits = <#inner_ty as ::serde::Deserialize<'de>>::deserialize(deserializer)?;
+
+                            #from_raw_bits
+                        }
+                    }
+                }
+            }
+        } else {
+            quote!()
+        };
+
+        let to_json_value = if cfg!(feature = "serde_json") && *impl_serialize {
+            quote! {
+                /// Convert the value to a `serde_json::Value`, with its `Serialize` representation.
+                pub fn to_json_value(&self) -> ::serde_json::Value {
+                    ::core::result::Result::expect(
+                        ::serde_json::to_value(self),
+                        "flags values always serialize to JSON",
+                    )
+                }
+            }
+        } else {
+            quote!()
+        };
+
+        let from_json_value = if cfg!(feature = "serde_json") && *impl_deserialize {
+            quote! {
+                /// Convert a `serde_json::Value` to a value, with its `Deserialize` representation.
+                pub fn from_json_value(
+                    value: &::serde_json::Value,
+                ) -> ::core::result::Result<Self, ::serde_json::Error> {
+                    <Self as ::serde::Deserialize>::deserialize(value)
+                }
+            }
+        } else {
+            quote!()
+        };
+
+        let json_impl = if cfg!(feature = "serde_json") && (*impl_serialize || *impl_deserialize) {
+            quote! {
+                impl #name {
+                    #to_json_value
+                    #from_json_value
+                }
+            }
+        } else {
+            quote!()
+        };
+
+        let encode_impl = if *impl_encode {
+            quote! {
+                #[automatically_derived]
+                impl<M> ::musli::Encode<M> for #name {
+                    type Encode = Self;
+
+                    #inline
+                    fn encode<E>(&self, encoder: E) -> ::core::result::Result<(), E::Error>
+                    where
+                        E: ::musli::Encoder<Mode = M>,
+                    {
+                        ::musli::Encode::<M>::encode(&self.0, encoder)
+                    }
+
+                    #inline
+                    fn as_encode(&self) -> &Self::Encode {
+                        self
+                    }
+                }
+            }
+        } else {
+            quote!()
+        };
+
+        let decode_impl = if *impl_decode {
+            quote! {
+                #[automatically_derived]
+                impl<'de, M, A> ::musli::Decode<'de, M, A> for #name
+                where
+                    A: ::musli::Allocator,
+                {
+                    #inline
+                    fn decode<D>(decoder: D) -> ::core::result::Result<Self, D::Error>
+                    where
+                        D: ::musli::Decoder<'de, Mode = M, Allocator = A>,
+                    {
+                        let cx = ::musli::Decoder::cx(&decoder);
+                        let bits = <#inner_ty as ::musli::Decode<'de, M, A>>::decode(decoder)?;
+
+                        #name::__decode_bits(bits).map_err(|error| ::musli::Context::message(cx, error))
+                    }
+                }
+            }
+        } else {
+            quote!()
+        };
+
+        let strum_impl = if *strum {
+            quote! {
+                #[automatically_derived]
+                impl ::strum::VariantNames for #name {
+                    const VARIANTS: &'static [&'static str] = &[#(
+                        #(#all_attrs)*
+                        #all_flags_texts,
+                    )*];
+                }
+
+                #[automatically_derived]
+                impl ::strum::EnumCount for #name {
+                    const COUNT: usize = <#name as #krate::Flags>::KNOWN_FLAGS.len();
+                }
+            }
+        } else {
+            quote!()
+        };
+
+        let scale_encode_impl = if *impl_scale_encode {
+            quote! {
+                #[automatically_derived]
+                impl ::parity_scale_codec::Encode for #name {
+                    #inline
+                    fn size_hint(&self) -> usize {
+                        ::parity_scale_codec::Encode::size_hint(&::parity_scale_codec::Compact(self.bits_unsigned()))
+                    }
+
+                    #inline
+                    fn encode_to<T: ::parity_scale_codec::Output + ?::core::marker::Sized>(&self, dest: &mut T) {
+                        ::parity_scale_codec::Encode::encode_to(&::parity_scale_codec::Compact(self.bits_unsigned()), dest)
+                    }
+                }
+
+                #[automatically_derived]
+                impl ::parity_scale_codec::EncodeLike for #name {}
+            }
+        } else {
+            quote!()
+        };
+
+        let scale_decode_impl = if *impl_scale_decode {
+            quote! {
+                #[automatically_derived]
+                impl ::parity_scale_codec::Decode for #name {
+                    #inline
+                    fn decode<I: ::parity_scale_codec::Input>(input: &mut I) -> ::core::result::Result<Self, ::parity_scale_codec::Error> {
+                        let ::parity_scale_codec::Compact(bits) = <::parity_scale_codec::Compact<
+                            <#inner_ty as #krate::BitsPrimitive>::Unsigned,
+                        > as ::parity_scale_codec::Decode>::decode(input)?;
+
+                        let bits = <#inner_ty as #krate::BitsPrimitive>::from_unsigned(bits);
+
+                        Self::__decode_bits(bits).map_err(|error| match error {
+                            #krate::DecodeError::UnknownBits(_) => ::parity_scale_codec::Error::from("unknown bits set"),
+                            #krate::DecodeError::Invalid(reason) => ::parity_scale_codec::Error::from(reason),
+                        })
+                    }
+                }
+            }
+        } else {
+            quote!()
+        };
+
+        // The flags are listed in the documentation of the type, as the encoding is only the bits
+        let type_info_impl = if *impl_type_info {
+            let flag_docs = all_flags_texts
+                .iter()
+                .map(|flag| format!("Flag `{}`", flag.value()));
+
+            quote! {
+                #[automatically_derived]
+                impl ::scale_info::TypeInfo for #name {
+                    type Identity = Self;
+
+                    fn type_info() -> ::scale_info::Type {
+                        ::scale_info::Type::builder()
+                            .path(::scale_info::Path::new(::core::stringify!(#name), ::core::module_path!()))
+                            .docs(&[#(
+                                #(#all_attrs)*
+                                #flag_docs,
+                            )*])
+                            .composite(::scale_info::build::Fields::unnamed().field(|field| {
+                                field.compact::<<#inner_ty as #krate::BitsPrimitive>::Unsigned>()
+                            }))
+                    }
+                }
+            }
+        } else {
+            quote!()
+        };
+
+        // Rebuild the type when the file of its flags changes
+        let from_file_include = from_file.as_ref().map(|file| {
+            quote! {
+                const _: &[u8] = ::core::include_bytes!(#file);
+            }
+        });
+
+        let visualizer_module = debugger_visualizer
+            .as_ref()
+            .map(|visualizer| visualizer.module(name));
+
+        let zvariant_type_impl = if *impl_zvariant_type {
+            quote! {
+                #[automatically_derived]
+                impl ::zvariant::Type for #name {
+                    const SIGNATURE: &'static ::zvariant::Signature = <#inner_ty as ::zvariant::Type>::SIGNATURE;
+                }
+            }
+        } else {
+            quote!()
+        };
+
+        let log_kv_impl = if *log_kv {
+            quote! {
+                #[automatically_derived]
+                impl ::log::kv::ToValue for #name {
+                    fn to_value(&self) -> ::log::kv::Value<'_> {
+                        ::log::kv::Value::from_display(#krate::parser::AsDisplay::from_ref(self))
+                    }
+                }
+            }
+        } else {
+            quote!()
+        };
+
+        let godot_impl = if *godot {
+            // The width of the aliases of the `custom-types` feature is only known by the compiler
+            let wide_check = if ty_bits_width(inner_ty).is_none() {
+                quote! {
+                    const _: () = {
+                        if <#inner_ty>::BITS > 64 {
+                            ::core::panic!("the `godot` option can't be used with bits types wider than 64 bits");
+                        }
+                    };
+                }
+            } else {
+                quote!()
+            };
+
+            quote! {
+                #wide_check
+
+                #[automatically_derived]
+                impl ::godot::meta::GodotConvert for #name {
+                    type Via = i64;
+                }
+
+                #[automatically_derived]
+                impl ::godot::register::property::Var for #name {
+                    fn get_property(&self) -> Self::Via {
+                        self.0 as i64
+                    }
+
+                    // Godot can't report an error, so a value out of the range of the bits type or
+                    // invalid leaves the flags unchanged. The `u64` bits past `i64::MAX` wrap around, as
+                    // in `get_property`, so they are recovered. Wider bits types are rejected by the option
+                    fn set_property(&mut self, value: Self::Via) {
+                        let bits = <#inner_ty as ::core::convert::TryFrom<i64>>::try_from(value)
+                            .or_else(|_| <#inner_ty as ::core::convert::TryFrom<u64>>::try_from(value as u64));
+
+                        if let ::core::result::Result::Ok(bits) = bits {
+                            if let ::core::result::Result::Ok(flags) = Self::__decode_bits(bits) {
+                                *self = flags;
+                            }
+                        }
+                    }
+
+                    fn var_hint() -> ::godot::register::property::PropertyHintInfo {
+                        use ::core::fmt::Write;
+
+                        // Godot expects the flags as `Name:value` pairs separated by commas
+                        let mut hint_string = ::std::string::String::new();
+                        for (i, (name, flag)) in ::core::iter::Iterator::enumerate(<Self as #krate::Flags>::ITER_FLAGS.iter()) {
+                            let separator = if i == 0 { "" } else { "," };
+                            let _ = ::core::write!(hint_string, "{separator}{name}:{}", flag.0 as i64);
+                        }
+
+                        ::godot::register::property::PropertyHintInfo {
+                            hint: ::godot::global::PropertyHint::FLAGS,
+                            hint_string: ::core::convert::From::from(hint_string.as_str()),
+                        }
+                    }
+                }
+
+                #[automatically_derived]
+                impl ::godot::register::property::Export for #name {
+                    fn export_hint() -> ::godot::register::property::PropertyHintInfo {
+                        <Self as ::godot::register::property::Var>::var_hint()
+                    }
+                }
+            }
+        } else {
+            quote!()
+        };
+
+        let sea_orm_impl = if *sea_orm {
+            quote! {
+                #[automatically_derived]
+                impl ::core::convert::From<#name> for ::sea_orm::Value {
+                    #inline
+                    fn from(value: #name) -> Self {
+                        ::core::convert::From::from(value.0)
+                    }
+                }
+
+                #[automatically_derived]
+                impl ::sea_orm::TryGetable for #name {
+                    fn try_get_by<I: ::sea_orm::ColIdx>(
+                        res: &::sea_orm::QueryResult,
+                        index: I,
+                    ) -> ::core::result::Result<Self, ::sea_orm::TryGetError> {
+                        let bits = <#inner_ty as ::sea_orm::TryGetable>::try_get_by(res, index)?;
+
+                        #name::__decode_bits(bits).map_err(|error| {
+                            ::sea_orm::TryGetError::DbErr(::sea_orm::DbErr::Type(
+                                ::std::string::ToString::to_string(&error),
+                            ))
+                        })
+                    }
+                }
+
+                #[automatically_derived]
+                impl ::sea_orm::sea_query::ValueType for #name {
+                    fn try_from(value: ::sea_orm::Value) -> ::core::result::Result<Self, ::sea_orm::sea_query::ValueTypeErr> {
+                        let bits = <#inner_ty as ::sea_orm::sea_query::ValueType>::try_from(value)?;
+
+                        #name::__decode_bits(bits).map_err(|_| ::sea_orm::sea_query::ValueTypeErr)
+                    }
+
+                    fn type_name() -> ::std::string::String {
+                        ::std::string::ToString::to_string(::core::stringify!(#name))
+                    }
+
+                    fn array_type() -> ::sea_orm::sea_query::ArrayType {
+                        <#inner_ty as ::sea_orm::sea_query::ValueType>::array_type()
+                    }
+
+                    fn column_type() -> ::sea_orm::sea_query::ColumnType {
+                        <#inner_ty as ::sea_orm::sea_query::ValueType>::column_type()
+                    }
+                }
+
+                #[automatically_derived]
+                impl ::sea_orm::sea_query::Nullable for #name {
+                    fn null() -> ::sea_orm::Value {
+                        <#inner_ty as ::sea_orm::sea_query::Nullable>::null()
+                    }
+                }
+            }
+        } else {
+            quote!()
+        };
+
+        // The parser selected by the `from_str` option, used by every decoder of text
+        let from_text = match from_str {
+            FromStrParser::Default => quote!(from_text),
+            FromStrParser::Strict => quote!(from_text_strict),
+            FromStrParser::Truncate => quote!(from_text_truncate),
+        };
+
+        // The same parser, for the decoders that validate the value after handling its unknown bits
+        let from_text_unvalidated = match from_str {
+            FromStrParser::Default => quote!(from_text_unvalidated),
+            FromStrParser::Strict => quote!(from_text_strict_unvalidated),
+            FromStrParser::Truncate => quote!(from_text_truncate_unvalidated),
+        };
+
+        let juniper_impl = if *juniper {
+            quote! {
+                impl #name {
+                    // Used by the derived `juniper::GraphQLScalar`
+                    #[doc(hidden)]
+                    pub fn __juniper_to_output<S: ::juniper::ScalarValue>(&self) -> ::juniper::Value<S> {
+                        let mut text = ::std::string::String::new();
+                        let _ = #krate::parser::to_writer(self, &mut text);
+                        ::juniper::Value::scalar(text)
+                    }
+
+                    // Used by the derived `juniper::GraphQLScalar`
+                    #[doc(hidden)]
+                    pub fn __juniper_from_input<S: ::juniper::ScalarValue>(
+                        input: &::juniper::InputValue<S>,
+                    ) -> ::core::result::Result<Self, ::std::string::String> {
+                        let text = input.as_string_value().ok_or_else(|| {
+                            ::std::format!("expected a `String` of flags, found: {input}")
+                        })?;
+
+                        #krate::parser::#from_text(text)
+                            .map_err(|err| ::std::string::ToString::to_string(&err))
+                    }
+                }
+            }
+        } else {
+            quote!()
+        };
+
+        let rocket_impl = if *rocket {
+            quote! {
+                #[automatically_derived]
+                impl<'a> ::rocket::request::FromParam<'a> for #name {
+                    type Error = #krate::parser::ParseError;
+
+                    fn from_param(param: &'a str) -> ::core::result::Result<Self, Self::Error> {
+                        #krate::parser::#from_text(param)
+                    }
+                }
+
+                #[automatically_derived]
+                impl<'v> ::rocket::form::FromFormField<'v> for #name {
+                    fn from_value(field: ::rocket::form::ValueField<'v>) -> ::rocket::form::Result<'v, Self> {
+                        #krate::parser::#from_text(field.value).map_err(|error| {
+                            let kind = ::rocket::form::error::ErrorKind::Custom(
+                                ::rocket::http::Status::BadRequest,
+                                ::std::boxed::Box::new(error),
+                            );
+                            let error: ::rocket::form::Error<'v> = ::core::convert::From::from(kind);
+                            ::core::convert::From::from(error)
+                        })
+                    }
+                }
+            }
+        } else {
+            quote!()
+        };
+
+        let verify_impl = if cfg!(feature = "verify") {
+            let proofs_mod = quote::format_ident!("__{}_proofs", name);
+
+            // `kani` is not a cfg known by rustc, so the lint is silenced for the whole block
+            quote! {
+                #[allow(unexpected_cfgs)]
+                const _: () = {
+                #[cfg(kani)]
+                #[allow(non_snake_case)]
+                mod #proofs_mod {
+                    use super::*;
+
+                    #[automatically_derived]
+                    impl ::kani::Arbitrary for #name {
+                        fn any() -> Self {
+                            <Self as #krate::Flags>::from_bits_retain(::kani::any())
+                        }
+                    }
+
+                    #[::kani::proof]
+                    fn truncate_is_idempotent() {
+                        let value: #name = ::kani::any();
+                        let once = value.truncated();
+
+                        ::core::assert!(!once.contains_unknown_bits());
+                        ::core::assert!(once.truncated().bits() == once.bits());
+                    }
+
+                    #[::kani::proof]
+                    fn complement_is_involutive_within_known_bits() {
+                        let value: #name = ::kani::any();
+
+                        ::core::assert!(value.complement().complement().bits() == value.truncated().bits());
+                        ::core::assert!(!value.complement().intersects(value.truncated()));
+                    }
+
+                    #[::kani::proof]
+                    fn contains_and_intersects_are_consistent() {
+                        let a: #name = ::kani::any();
+                        let b: #name = ::kani::any();
+
+                        ::core::assert!(a.contains(b) == (a.and(b).bits() == b.bits()));
+                        ::core::assert!(a.intersects(b) == !a.and(b).is_empty());
+                        ::core::assert!(a.intersects(b) == b.intersects(a));
+                        ::core::assert!(!a.contains(b) || b.is_empty() || a.intersects(b));
+                        ::core::assert!(a.or(b).contains(a) && a.or(b).contains(b));
+                        ::core::assert!(!a.difference(b).intersects(b));
+                    }
+                }
+                };
+            }
+        } else {
+            quote!()
+        };
+
+        let convert_impls = conversions.iter().map(|BitflagConvert { ty, mode }| {
+            let impls = [(name.to_token_stream(), ty.to_token_stream()), (ty.to_token_stream(), name.to_token_stream())];
+
+            let impls = impls.iter().map(|(from, to)| match mode {
+                ConvertMode::Retain => quote! {
+                    #[automatically_derived]
+                    impl ::core::convert::From<#from> for #to {
+                        #inline
+                        fn from(value: #from) -> Self {
+                            <#to as #krate::Flags>::from_bits_retain(#krate::Flags::bits(&value))
+                        }
+                    }
+                },
+                ConvertMode::Truncate => quote! {
+                    #[automatically_derived]
+                    impl ::core::convert::From<#from> for #to {
+                        #inline
+                        fn from(value: #from) -> Self {
+                            <#to as #krate::Flags>::from_bits_truncate(#krate::Flags::bits(&value))
+                        }
+                    }
+                },
+                ConvertMode::Checked => quote! {
+                    #[automatically_derived]
+                    impl ::core::convert::TryFrom<#from> for #to {
+                        type Error = #krate::UnknownBitsError<#inner_ty>;
+
+                        #inline
+                        fn try_from(value: #from) -> ::core::result::Result<Self, Self::Error> {
+                            let bits = #krate::Flags::bits(&value);
+                            let unknown = bits & !#krate::Flags::bits(&<#to as #krate::Flags>::all());
+
+                            if unknown == 0 {
+                                ::core::result::Result::Ok(<#to as #krate::Flags>::from_bits_retain(bits))
+                            } else {
+                                ::core::result::Result::Err(#krate::UnknownBitsError::new(unknown))
+                            }
+                        }
+                    }
+                },
+            });
+
+            quote!(#(#impls)*)
+        });
+
+        let flag_idents: Vec<Ident> = all_flags_names
+            .iter()
+            .map(|name| Ident::new(&name.value(), name.span()))
+            .collect();
+
+        // Flags are mapped by name, so the wider type must define every flag of this type
+        let widen_impls = widenings.iter().map(|wide| {
+            quote! {
+                #[automatically_derived]
+                impl ::core::convert::From<#name> for #wide {
+                    #inline
+                    fn from(value: #name) -> Self {
+                        let mut flags = <#wide as #krate::Flags>::empty();
+                        let mut remaining = value.0;
+
+                        #(
+                            #(#all_attrs)*
+                            {
+                                if value.contains(#name::#flag_idents) {
+                                    #krate::Flags::set(&mut flags, #wide::#flag_idents);
+                                    remaining &= !#name::#flag_idents.0;
+                                }
+                            }
+                        )*
+
+                        // The other bits, unknown or only valid through `extra_valid_bits`, are
+                        // kept in the same position, without sign extension
+                        let remaining = #krate::BitsPrimitive::to_unsigned(remaining)
+                            as <<#wide as #krate::Flags>::Bits as #krate::BitsPrimitive>::Unsigned;
+                        #krate::Flags::set(
+                            &mut flags,
+                            <#wide as #krate::Flags>::from_bits_retain(
+                                #krate::BitsPrimitive::from_unsigned(remaining),
+                            ),
+                        );
+
+                        flags
+                    }
+                }
+
+                #[automatically_derived]
+                impl ::core::convert::TryFrom<#wide> for #name {
+                    type Error = #krate::UnknownBitsError<<#wide as #krate::Flags>::Bits>;
+
+                    #inline
+                    fn try_from(value: #wide) -> ::core::result::Result<Self, Self::Error> {
+                        let mut flags = Self::empty();
+                        let mut remaining = value;
+                        let mut named = 0 as #inner_ty;
+
+                        #(
+                            #(#all_attrs)*
+                            {
+                                named |= Self::#flag_idents.0;
+                                if #krate::Flags::contains(&value, #wide::#flag_idents) {
+                                    flags.0 |= Self::#flag_idents.0;
+                                    #krate::Flags::unset(&mut remaining, #wide::#flag_idents);
+                                }
+                            }
+                        )*
+
+                        // The remaining bits must not be part of a flag of either type and fit in
+                        // this one, without sign extension
+                        let wide_named = ::core::iter::Iterator::any(
+                            &mut <#wide as #krate::Flags>::KNOWN_FLAGS.iter(),
+                            |(_, flag)| #krate::Flags::intersects(&remaining, *flag),
+                        );
+                        let remaining = #krate::Flags::bits(&remaining);
+                        let unsigned = #krate::BitsPrimitive::to_unsigned(remaining);
+                        let bits = <#inner_ty as #krate::BitsPrimitive>::from_unsigned(
+                            unsigned as <#inner_ty as #krate::BitsPrimitive>::Unsigned,
+                        );
+
+                        if wide_named
+                            || #krate::BitsPrimitive::to_unsigned(bits)
+                                as <<#wide as #krate::Flags>::Bits as #krate::BitsPrimitive>::Unsigned
+                                != unsigned
+                            || bits & named != 0
+                        {
+                            return ::core::result::Result::Err(#krate::UnknownBitsError::new(remaining));
+                        }
+
+                        ::core::result::Result::Ok(Self(flags.0 | bits))
+                    }
+                }
+            }
+        });
+
+        let field_accessors = fields.iter().map(|field| {
+            let BitflagField { name: field_name, ty, range } = field;
+            let setter = Ident::new(&format!("set_{field_name}"), field_name.span());
+            let start = range.start;
+            let mask = field.mask() >> start;
+            let width = range.end - range.start;
+
+            let doc_get = format!(
+                "Returns the `{field_name}` field, the bits `{}..{}` shifted down to a `{}` value.",
+                range.start,
+                range.end,
+                ty.to_token_stream(),
+            );
+            let doc_set = format!(
+                "Sets the `{field_name}` field to `value`. The bits of `value` past the {width} bits of the field are dropped.",
+            );
+
+            quote! {
+                #[doc = #doc_get]
+                #inline
+                pub fn #field_name(&self) -> #ty {
+                    let bits = (self.0 >> #start) & (#mask as #inner_ty);
+                    <#ty as #krate::Flags>::from_bits_retain(bits as <#ty as #krate::Flags>::Bits)
+                }
+
+                #[doc = #doc_set]
+                #inline
+                pub fn #setter(&mut self, value: #ty) {
+                    let bits = (#krate::Flags::bits(&value) as #inner_ty) & (#mask as #inner_ty);
+                    self.0 = (self.0 & !((#mask as #inner_ty) << #start)) | (bits << #start);
+                }
+            }
+        });
+
+        let fields_impl = if fields.is_empty() {
+            quote!()
+        } else {
+            quote! {
+                impl #name {
+                    #(#field_accessors)*
+                }
+            }
+        };
+
+        let doc_from_iter = format!("Create a `{name}` from a iterator of flags.");
+        // The names of the methods setting and unsetting flags, set with the `method_names` option
+        let set_methods = match method_names {
+            MethodNames::Default => quote! {
+                /// Set the flags in `other` in the value.
+                #inline
+                #[doc(alias = "insert")]
+                pub #const_mut fn set(&mut self, other: Self) {
+                    self.0 = self.or(other).0
+                }
+
+                /// Unset the flags bits in `other` in the value.
+                #inline
+                #[doc(alias = "remove")]
+                pub #const_mut fn unset(&mut self, other: Self) {
+                    self.0 = self.difference(other).0
+                }
+
+                /// Set the flags in `other` in the value, failing if any of them is already set.
+                ///
+                /// The value is left unchanged if it fails.
+                #inline
+                #[doc(alias = "try_insert")]
+                pub #const_mut fn try_set(&mut self, other: Self) -> ::core::result::Result<(), #krate::AlreadySetError<#inner_ty>> {
+                    let already_set = self.0 & other.0;
+                    if already_set != 0 {
+                        return ::core::result::Result::Err(#krate::AlreadySetError::new(already_set));
+                    }
+
+                    self.0 = self.or(other).0;
+                    ::core::result::Result::Ok(())
+                }
+
+                /// Unset the flags in `other` in the value, failing if any of them is not set.
+                ///
+                /// The value is left unchanged if it fails.
+                #inline
+                #[doc(alias = "try_remove")]
+                pub #const_mut fn try_unset(&mut self, other: Self) -> ::core::result::Result<(), #krate::NotSetError<#inner_ty>> {
+                    let not_set = other.0 & !self.0;
+                    if not_set != 0 {
+                        return ::core::result::Result::Err(#krate::NotSetError::new(not_set));
+                    }
+
+                    self.0 = self.difference(other).0;
+                    ::core::result::Result::Ok(())
+                }
+            },
+            MethodNames::Bitflags => quote! {
+                /// Set the flags in `other` in the value.
+                #inline
+                pub #const_mut fn insert(&mut self, other: Self) {
+                    self.0 = self.or(other).0
+                }
+
+                /// Unset the flags bits in `other` in the value.
+                #inline
+                pub #const_mut fn remove(&mut self, other: Self) {
+                    self.0 = self.difference(other).0
+                }
+
+                /// Set the flags in `other` in the value if `value` is `true`, or unset them otherwise.
+                #inline
+                pub #const_mut fn set(&mut self, other: Self, value: bool) {
+                    if value {
+                        self.insert(other)
+                    } else {
+                        self.remove(other)
+                    }
+                }
+
+                /// Set the flags in `other` in the value, failing if any of them is already set.
+                ///
+                /// The value is left unchanged if it fails.
+                #inline
+                pub #const_mut fn try_insert(&mut self, other: Self) -> ::core::result::Result<(), #krate::AlreadySetError<#inner_ty>> {
+                    let already_set = self.0 & other.0;
+                    if already_set != 0 {
+                        return ::core::result::Result::Err(#krate::AlreadySetError::new(already_set));
+                    }
+
+                    self.0 = self.or(other).0;
+                    ::core::result::Result::Ok(())
+                }
+
+                /// Unset the flags in `other` in the value, failing if any of them is not set.
+                ///
+                /// The value is left unchanged if it fails.
+                #inline
+                pub #const_mut fn try_remove(&mut self, other: Self) -> ::core::result::Result<(), #krate::NotSetError<#inner_ty>> {
+                    let not_set = other.0 & !self.0;
+                    if not_set != 0 {
+                        return ::core::result::Result::Err(#krate::NotSetError::new(not_set));
+                    }
+
+                    self.0 = self.difference(other).0;
+                    ::core::result::Result::Ok(())
+                }
+            },
+        };
+
+        // The text written by `Display` is parsed back by `FromStr`
+        let display_impl = if *display {
+            let to_text = match from_str {
+                FromStrParser::Default => quote!(to_writer),
+                FromStrParser::Strict => quote!(to_writer_strict),
+                FromStrParser::Truncate => quote!(to_writer_truncate),
+            };
+
+            quote! {
+                #[automatically_derived]
+                impl ::core::fmt::Display for #name {
+                    fn fmt(&self, f: &mut ::core::fmt::Formatter<'_>) -> ::core::fmt::Result {
+                        #krate::parser::#to_text(self, f)
+                    }
+                }
+            }
+        } else {
+            quote!()
+        };
+
+        let bson_impl = if let Some(bson) = bson {
+            // The width of the aliases of the `custom-types` feature is only known by the compiler
+            let wide_check = if matches!(bson, BsonRepr::Int) && ty_bits_width(inner_ty).is_none() {
+                quote! {
+                    const _: () = {
+                        if <#inner_ty>::BITS > 64 {
+                            ::core::panic!("the `bson = int` option can't be used with bits types wider than 64 bits, use `bson = text` instead");
+                        }
+                    };
+                }
+            } else {
+                quote!()
+            };
+
+            let to_bson = match bson {
+                BsonRepr::Int => quote! {
+                    // Signed bits are sign-extended and unsigned bits wrap around, so the bits of
+                    // the types up to 64 bits, the only ones allowed, are recovered by `TryFrom`
+                    let bits = value.0 as i64;
+                    match <i32 as ::core::convert::TryFrom<i64>>::try_from(bits) {
+                        ::core::result::Result::Ok(bits) => ::bson::Bson::Int32(bits),
+                        ::core::result::Result::Err(_) => ::bson::Bson::Int64(bits),
+                    }
+                },
+                BsonRepr::Text => quote! {
+                    let mut text = ::std::string::String::new();
+                    let _ = #krate::parser::to_writer(&value, &mut text);
+                    ::bson::Bson::String(text)
+                },
+            };
+
+            quote! {
+                #wide_check
+
+                #[automatically_derived]
+                impl ::core::convert::From<#name> for ::bson::Bson {
+                    fn from(value: #name) -> Self {
+                        #to_bson
+                    }
+                }
+
+                #[automatically_derived]
+                impl ::core::convert::TryFrom<::bson::Bson> for #name {
+                    type Error = #krate::parser::ParseError;
+
+                    fn try_from(value: ::bson::Bson) -> ::core::result::Result<Self, Self::Error> {
+                        let bits = match value {
+                            ::bson::Bson::Int32(bits) => bits as i64,
+                            ::bson::Bson::Int64(bits) => bits,
+                            ::bson::Bson::String(text) => {
+                                let flags: #name = #krate::parser::#from_text_unvalidated(&text)?;
+                                return ::core::result::Result::Ok(#name::__decode_bits(flags.0)?);
+                            }
+                            _ => {
+                                return ::core::result::Result::Err(
+                                    #krate::parser::ParseError::unexpected_value(
+                                        "an integer or a string of flags",
+                                    ),
+                                )
+                            }
+                        };
+
+                        let bits = <#inner_ty as ::core::convert::TryFrom<i64>>::try_from(bits)
+                            .or_else(|_| <#inner_ty as ::core::convert::TryFrom<u64>>::try_from(bits as u64))
+                            .map_err(|_| {
+                                #krate::parser::ParseError::unexpected_value(::core::concat!(
+                                    "an integer in the range of `",
+                                    ::core::stringify!(#inner_ty),
+                                    "`"
+                                ))
+                            })?;
+
+                        ::core::result::Result::Ok(#name::__decode_bits(bits)?)
+                    }
+                }
+            }
+        } else {
+            quote!()
+        };
+
+        // The traits of a GraphQL scalar are implemented by its derive, with the conversions of
+        // `juniper_impl`
+        let juniper_derive = if *juniper {
+            quote! {
+                #[derive(::juniper::GraphQLScalar)]
+                #[graphql(
+                    to_output_with = #name::__juniper_to_output,
+                    from_input_with = #name::__juniper_from_input,
+                    parse_token(::std::string::String),
+                )]
+            }
+        } else {
+            quote!()
+        };
+
+        let struct_def = if *hand_written {
+            quote!()
+        } else {
+            quote! {
+                #(#[#forward_attrs])*
+                #[repr(transparent)]
+                #(#attrs)*
+                #[derive(#(#derived_traits,)*)]
+                #juniper_derive
+                #vis struct #name(#inner_ty)
+                where
+                    #inner_ty: #krate::BitsPrimitive;
+            }
+        };
+
+        // The operators can be left out with `no_ops`, for types implementing them by hand
+        let ops_impls = if *no_ops {
+            quote!()
+        } else {
+            quote! {
+                #[automatically_derived]
+                impl #impl_const ::core::ops::Not for #name {
+                    type Output = Self;
+
+                    #not_op
+                }
+
+                #[automatically_derived]
+                impl #impl_const ::core::ops::BitAnd for #name {
+                    type Output = Self;
+
+                    #inline
+                    fn bitand(self, rhs: Self) -> Self::Output {
+                        self.and(rhs)
+                    }
+                }
+
+                #[automatically_derived]
+                impl #impl_const ::core::ops::BitOr for #name {
+                    type Output = Self;
+
+                    #inline
+                    fn bitor(self, rhs: Self) -> Self::Output {
+                        self.or(rhs)
+                    }
+                }
+
+                #[automatically_derived]
+                impl #impl_const ::core::ops::BitXor for #name {
+                    type Output = Self;
+
+                    #inline
+                    fn bitxor(self, rhs: Self) -> Self::Output {
+                        self.xor(rhs)
+                    }
+                }
+
+                #[automatically_derived]
+                impl #impl_const ::core::ops::BitAndAssign for #name {
+                    #inline
+                    fn bitand_assign(&mut self, rhs: Self) {
+                        self.0 &= rhs.0;
+                    }
+                }
+
+                #[automatically_derived]
+                impl #impl_const ::core::ops::BitOrAssign for #name {
+                    #inline
+                    fn bitor_assign(&mut self, rhs: Self) {
+                        self.0 |= rhs.0;
+                    }
+                }
+
+                #[automatically_derived]
+                impl #impl_const ::core::ops::BitXorAssign for #name {
+                    #inline
+                    fn bitxor_assign(&mut self, rhs: Self) {
+                        self.0 ^= rhs.0;
+                    }
+                }
+
+                #[automatically_derived]
+                impl #impl_const ::core::ops::Sub for #name {
+                    type Output = Self;
+
+                    /// The intersection of a source flag with the complement of a target flags value
+                    #inline
+                    fn sub(self, rhs: Self) -> Self::Output {
+                        self.difference(rhs)
+                    }
+                }
+
+                #[automatically_derived]
+                impl #impl_const ::core::ops::SubAssign for #name {
+                    /// The intersection of a source flag with the complement of a target flags value
+                    #inline
+                    fn sub_assign(&mut self, rhs: Self) {
+                        self.0 = self.difference(rhs).0
+                    }
+                }
+            }
+        };
+
+        // The conversions from and to the bits can be left out with `no_from`
+        let from_impls = if *no_from {
+            quote!()
+        } else {
+            let from_bits_impl = match from_bits {
+                ConvertMode::Truncate => quote! {
+                    #[automatically_derived]
+                    impl ::core::convert::From<#inner_ty> for #name {
+                        #inline
+                        fn from(val: #inner_ty) -> Self {
+                            Self::from_bits_truncate(val)
+                        }
+                    }
+                },
+                ConvertMode::Retain => quote! {
+                    #[automatically_derived]
+                    impl ::core::convert::From<#inner_ty> for #name {
+                        #inline
+                        fn from(val: #inner_ty) -> Self {
+                            Self::from_bits_retain(val)
+                        }
+                    }
+                },
+                ConvertMode::Checked => quote! {
+                    #[automatically_derived]
+                    impl ::core::convert::TryFrom<#inner_ty> for #name {
+                        type Error = #krate::DecodeError<#inner_ty>;
+
+                        #inline
+                        fn try_from(val: #inner_ty) -> ::core::result::Result<Self, Self::Error> {
+                            match <Self as #krate::Flags>::from_bits(val) {
+                                ::core::option::Option::Some(flags) => ::core::result::Result::Ok(flags),
+                                ::core::option::Option::None => {
+                                    let unknown = val & !Self::ALL_MASK;
+                                    let invalid = #krate::Flags::validate(&Self::from_bits_truncate(val));
+
+                                    ::core::result::Result::Err(match invalid {
+                                        // Without unknown bits, `from_bits` only fails the `validate` check
+                                        ::core::result::Result::Err(reason) if unknown == 0 => {
+                                            #krate::DecodeError::Invalid(reason)
+                                        }
+                                        _ => #krate::DecodeError::UnknownBits(#krate::UnknownBitsError::new(unknown)),
+                                    })
+                                }
+                            }
+                        }
+                    }
+                },
+            };
+
+            quote! {
+                #from_bits_impl
+
+                #[automatically_derived]
+                impl ::core::convert::From<#name> for #inner_ty {
+                    #inline
+                    fn from(val: #name) -> Self {
+                        val.0
+                    }
+                }
+            }
+        };
+
+        // `FromStr` can be left out with `no_from_str`
+        let from_str_impl = if *no_from_str {
+            quote!()
+        } else {
+            quote! {
+                #[automatically_derived]
+                impl ::core::str::FromStr for #name {
+                    type Err = #parse_error_ty;
+
+                    fn from_str(input: &str) -> ::core::result::Result<Self, Self::Err> {
+                        #krate::parser::#from_text(input) #parse_error_map
+                    }
+                }
+            }
+        };
+
+        // The iterator traits can be left out with `no_iter`
+        let iter_impls = if *no_iter {
+            quote!()
+        } else {
+            quote! {
+                #[automatically_derived]
+                impl ::core::iter::Extend<#name> for #name {
+                    /// Set all flags of `iter` to self
+                    fn extend<T: ::core::iter::IntoIterator<Item = Self>>(&mut self, iter: T) {
+                        for item in iter {
+                            self.0 = self.or(item).0;
+                        }
+                    }
+                }
+
+                #[automatically_derived]
+                impl ::core::iter::FromIterator<#name> for #name {
+                    #[doc = #doc_from_iter]
+                    fn from_iter<T: ::core::iter::IntoIterator<Item = Self>>(iter: T) -> Self {
+                        use ::core::iter::Extend;
+
+                        let mut res = Self::empty();
+                        res.extend(iter);
+                        res
+                    }
+                }
+
+                #[automatically_derived]
+                impl ::core::iter::IntoIterator for #name {
+                    type Item = Self;
+                    type IntoIter = #krate::iter::Iter<Self>;
+
+                    fn into_iter(self) -> Self::IntoIter {
+                        self.iter()
+                    }
+                }
+
+                #[automatically_derived]
+                impl ::core::iter::IntoIterator for &#name {
+                    type Item = #name;
+                    type IntoIter = #krate::iter::Iter<#name>;
+
+                    fn into_iter(self) -> Self::IntoIter {
+                        self.iter()
+                    }
+                }
+            }
+        };
+
+        let generated = quote! {
+            #struct_def
+
+            #[allow(non_upper_case_globals)]
+            impl #name {
+                #[doc(hidden)]
+                #[allow(clippy::unused_unit, non_camel_case_types)]
+                const __OG: () = {
+                    {
+                        // Original enum
+                        // This is a hack to make LSP coloring to still sees the original enum variant as a Enum variant token.
+                        #orig_enum
+                    }
+                    ()
+                };
+
+                #(#flags)*
+            }
+
+            #[allow(non_upper_case_globals)]
+            impl #name {
+                /// Return the underlying bits of this bitflag.
+                #inline
+                pub const fn bits(&self) -> #inner_ty {
+                    self.0
+                }
+
+                #as_bits_mut
+
+                /// Return the underlying bits of this bitflag as the unsigned type with the same
+                /// size, like `0x8000_0000_u32` instead of `i32::MIN` for `i32`.
+                #inline
+                pub const fn bits_unsigned(&self) -> <#inner_ty as #krate::BitsPrimitive>::Unsigned {
+                    self.0 as <#inner_ty as #krate::BitsPrimitive>::Unsigned
+                }
+
+                /// Converts from a `bits` value. Returning [`None`] is any unknown bits are set.
+                #inline
+                pub const fn from_bits(bits: #inner_ty) -> ::core::option::Option<Self> {
+                    let truncated = Self::from_bits_truncate(bits).0;
+
+                    if truncated == bits {
+                        #from_bits_valid
+                    } else {
+                        ::core::option::Option::None
+                    }
+                }
+
+                /// Convert from `bits` value, unsetting any unknown bits.
+                #inline
+                pub const fn from_bits_truncate(bits: #inner_ty) -> Self {
+                    Self(bits & Self::ALL_MASK)
+                }
+
+                #from_bits_retain
+
+                /// Convert from `bits` value without checking for unknown bits.
+                ///
+                /// This skips the masking of [`from_bits_truncate`](Self::from_bits_truncate) for
+                /// values already known to be valid. Debug builds still assert that no unknown bits
+                /// are set.
+                ///
+                /// # Safety
+                ///
+                /// `bits` must not have any unknown bits set.
+                #inline
+                pub const unsafe fn from_bits_unchecked(bits: #inner_ty) -> Self {
+                    ::core::debug_assert!(bits & !Self::ALL_MASK == 0, "unknown bits set");
+                    Self(bits)
+                }
+
+                /// Convert from a flag `name`.
+                #inline
+                pub fn from_flag_name(name: &str) -> ::core::option::Option<Self> {
+                    match name {
+                        #(
+                            #(#all_attrs)*
+                            #all_flags_texts => ::core::option::Option::Some(#all_flags),
+                        )*
+                        #(#alias_arms)*
+                        _ => ::core::option::Option::None
+                    }
+                }
+
+                /// Construct a flags value with all bits unset.
+                #inline
+                pub const fn empty() -> Self {
+                    Self(0)
+                }
+
+                /// Returns `true` if the flag value has all bits unset.
+                #inline
+                pub const fn is_empty(&self) -> bool {
+                    self.0 == 0
+                }
+
+                /// Returns a flag value that contains all value.
+                ///
+                /// This will include bits that do not have any flags/meaning.
+                /// Use [`all`](Self::all) if you want only the specified flags set.
+                #inline
+                pub const fn all_bits() -> Self {
+                    #all_bits_value
+                }
+
+                /// Returns `true` if the flag value contains all value bits set.
+                ///
+                /// This will check for all bits.
+                /// Use [`is_all`](Self::is_all) if you want to check for all specified flags.
+                #inline
+                pub const fn is_all_bits(&self) -> bool {
+                    self.0 == #all_bits_mask
+                }
+
+                /// The bits of all known flags, computed once for [`all`](Self::all) and the
+                /// methods truncating unknown bits.
+                pub const ALL_MASK: #inner_ty = {
+                    let mut all = 0;
+
+                    #(
+                        #(#all_attrs)*{
+                            all |= #all_flags.0;
+                        }
+                    )*
+
+                    #extra_valid_bits;
+
+                    all
+                };
+
+                /// Construct a flag value with all known flags set.
+                ///
+                /// This will only set the flags specified as associated constant.
+                #inline
+                pub const fn all() -> Self {
+                    Self(Self::ALL_MASK)
+                }
+
+                /// Returns `true` if the flag value contais all known flags.
+                #inline
+                pub const fn is_all(&self) -> bool {
+                    Self::ALL_MASK | self.0 == self.0
+                }
+
+                /// Returns `true` if there are any unknown bits set in the flag value.
+                #inline
+                pub const fn contains_unknown_bits(&self) -> bool {
+                    Self::ALL_MASK & self.0 != self.0
+                }
+
+                /// Returns the index of the lowest bit set in the flag value, or [`None`] if it is empty.
+                #inline
+                pub const fn lowest_bit_index(&self) -> ::core::option::Option<u32> {
+                    if self.0 == 0 {
+                        ::core::option::Option::None
+                    } else {
+                        ::core::option::Option::Some(self.0.trailing_zeros())
+                    }
+                }
+
+                /// Returns the index of the highest bit set in the flag value, or [`None`] if it is empty.
+                #inline
+                pub const fn highest_bit_index(&self) -> ::core::option::Option<u32> {
+                    if self.0 == 0 {
+                        ::core::option::Option::None
+                    } else {
+                        ::core::option::Option::Some(#inner_ty::BITS - 1 - self.0.leading_zeros())
+                    }
+                }
+
+                /// Returns a bit flag that only has bits corresponding to the specified flags as associated constant.
+                #inline
+                pub const fn truncated(&self) -> Self {
+                    Self(self.0 & Self::ALL_MASK)
+                }
+
+                /// Removes unknown bits from the flag value.
+                #inline
+                pub #const_mut fn truncate(&mut self) {
+                    *self = Self::from_bits_truncate(self.0);
+                }
+
+                /// Returns `true` if this flag value intersects with any value in `other`.
+                ///
+                /// This is equivalent to `(self & other) != Self::empty()`
+                #inline
+                pub const fn intersects(&self, other: Self) -> bool {
+                    (self.0 & other.0) != Self::empty().0
+                }
+
+                /// Returns `true` if this flag value contains all values of `other`.
+                ///
+                /// This is equivalent to `(self & other) == other`
+                #inline
+                pub const fn contains(&self, other: Self) -> bool {
+                    (self.0 & other.0) == other.0
+                }
+
+                /// Returns the bitwise NOT of the flag.
+                ///
+                /// This function does not truncate unused bits (bits that do not have any flags/meaning).
+                /// Use [`complement`](Self::complement) if you want that the result to be truncated in one call.
+                #inline
+                #[doc(alias = "complement")]
+                pub const fn not(self) -> Self {
+                    #not_value
+                }
+
+                /// Returns the bitwise AND of the flag.
+                #inline
+                #[doc(alias = "intersection")]
+                pub const fn and(self, other: Self) -> Self {
+                    Self(self.0 & other.0)
+                }
+
+                /// Returns the bitwise OR of the flag with `other`.
+                #inline
+                #[doc(alias = "union")]
+                pub const fn or(self, other: Self) -> Self {
+                    Self(self.0 | other.0)
+                }
+
+                /// Returns the bitwise XOR of the flag with `other`.
+                #inline
+                #[doc(alias = "symmetric_difference")]
                 pub const fn xor(self, other: Self) -> Self {
                     Self(self.0 ^ other.0)
                 }
 
-                /// Returns the intersection from this value with `other`.
-                #[inline]
-                #[doc(alias = "and")]
-                pub const fn intersection(self, other: Self) -> Self {
-                    self.and(other)
+                /// Returns the intersection from this value with `other`.
+                #inline
+                #[doc(alias = "and")]
+                pub const fn intersection(self, other: Self) -> Self {
+                    self.and(other)
+                }
+
+                /// Returns the union from this value with `other`.
+                #inline
+                #[doc(alias = "or")]
+                pub const fn union(self, other: Self) -> Self {
+                    self.or(other)
+                }
+
+                /// Returns the difference from this value with `other`.
+                ///
+                /// In other words, returns the intersection of this value with the negation of `other`.
+                ///
+                /// This method is not equivalent to `self & !other` when `other` has unknown bits set.
+                /// `difference` won't truncate `other`, but the `!` operator will.
+                #inline
+                pub const fn difference(self, other: Self) -> Self {
+                    self.and(other.not())
+                }
+
+                /// Returns the symmetric difference from this value with `other`.
+                #inline
+                #[doc(alias = "xor")]
+                pub const fn symmetric_difference(self, other: Self) -> Self {
+                    self.xor(other)
+                }
+
+                /// Returns the complement of the value.
+                ///
+                /// This is very similar to the [`not`](Self::not), but truncates non used bits.
+                #inline
+                #[doc(alias = "not")]
+                pub const fn complement(self) -> Self {
+                    self.not().truncated()
+                }
+
+                #set_methods
+
+                /// Toggle the flags in `other` in the value.
+                #inline
+                pub #const_mut fn toggle(&mut self, other: Self) {
+                    self.0 = self.xor(other).0
+                }
+
+                /// Toggle all known flags in the value.
+                ///
+                /// Unknown bits are left unchanged.
+                #inline
+                pub #const_mut fn toggle_all(&mut self) {
+                    self.0 = self.xor(Self::all()).0
+                }
+
+                /// Returns `true` if the bit at `index` is set, counting from the least significant bit.
+                ///
+                /// # Panics
+                ///
+                /// Panics if `index` is not less than the number of bits of the flags type.
+                #inline
+                pub const fn bit(&self, index: u32) -> bool {
+                    ::core::assert!(index < #bits_count, "bit index out of range");
+                    self.0 & (1 << index) != 0
+                }
+
+                #set_bit
+
+                #bit_permutations
+            }
+
+            #ops_impls
+
+            #from_impls
+
+            #[automatically_derived]
+            impl ::core::convert::AsRef<#inner_ty> for #name {
+                #inline
+                fn as_ref(&self) -> &#inner_ty {
+                    &self.0
+                }
+            }
+
+            #[automatically_derived]
+            impl ::core::borrow::Borrow<#inner_ty> for #name {
+                #inline
+                fn borrow(&self) -> &#inner_ty {
+                    &self.0
+                }
+            }
+
+            #[automatically_derived]
+            impl ::core::fmt::Binary for #name {
+                #inline
+                fn fmt(&self, f: &mut ::core::fmt::Formatter<'_>) -> ::core::fmt::Result {
+                    ::core::fmt::Binary::fmt(&self.0, f)
+                }
+            }
+
+            #[automatically_derived]
+            impl ::core::fmt::LowerHex for #name {
+                #inline
+                fn fmt(&self, f: &mut ::core::fmt::Formatter<'_>) -> ::core::fmt::Result {
+                    ::core::fmt::LowerHex::fmt(&self.0, f)
+                }
+            }
+
+            #[automatically_derived]
+            impl ::core::fmt::UpperHex for #name {
+                #inline
+                fn fmt(&self, f: &mut ::core::fmt::Formatter<'_>) -> ::core::fmt::Result {
+                    ::core::fmt::UpperHex::fmt(&self.0, f)
+                }
+            }
+
+            #[automatically_derived]
+            impl ::core::fmt::Octal for #name {
+                #inline
+                fn fmt(&self, f: &mut ::core::fmt::Formatter<'_>) -> ::core::fmt::Result {
+                    ::core::fmt::Octal::fmt(&self.0, f)
+                }
+            }
+
+            #from_str_impl
+
+            #parse_error_impl
+
+            #debug_impl
+            #display_impl
+            #partial_ord_impl
+
+            impl #krate::Flags for #name {
+                #labels
+                #docs
+                #symbols
+
+                const KNOWN_FLAGS: &'static [(&'static str, #name)] = &[#(
+                    #(#all_attrs)*
+                    (#all_flags_texts , #all_flags) ,
+                )*];
+
+                const ITER_FLAGS: &'static [(&'static str, #name)] = {
+                    #iter_known
+
+                    // Zero flags and exact duplicates of a previous flag are never yielded
+                    const fn yieldable(index: usize) -> bool {
+                        let bits = KNOWN[index].1 .0;
+
+                        if bits == 0 {
+                            return false;
+                        }
+
+                        let mut i = 0;
+                        while i < index {
+                            if KNOWN[i].1 .0 == bits {
+                                return false;
+                            }
+                            i += 1;
+                        }
+
+                        true
+                    }
+
+                    const LEN: usize = {
+                        let mut len = 0;
+                        let mut i = 0;
+                        while i < KNOWN.len() {
+                            if yieldable(i) {
+                                len += 1;
+                            }
+                            i += 1;
+                        }
+                        len
+                    };
+
+                    const TABLE: [(&str, #name); LEN] = {
+                        let mut table = [("", #name(0)); LEN];
+                        let mut len = 0;
+                        let mut i = 0;
+                        while i < KNOWN.len() {
+                            if yieldable(i) {
+                                table[len] = KNOWN[i];
+                                len += 1;
+                            }
+                            i += 1;
+                        }
+
+                        #sort_iter_flags
+
+                        table
+                    };
+
+                    &TABLE
+                };
+
+                const EXTRA_VALID_BITS: #inner_ty = #extra_valid_bits_value;
+
+                type Bits = #inner_ty;
+
+                fn bits(&self) -> Self::Bits {
+                    self.0
+                }
+
+                fn from_bits_retain(bits: Self::Bits) -> Self {
+                    #trait_from_bits_retain
                 }
 
-                /// Returns the union from this value with `other`.
-                #[inline]
-                #[doc(alias = "or")]
-                pub const fn union(self, other: Self) -> Self {
-                    self.or(other)
+                #validate_impl
+
+                #width_flags_impl
+
+                #from_name_impl
+            }
+
+            impl #name {
+                /// Yield a set of contained flags values.
+                ///
+                /// Each yielded flags value will correspond to a defined named flag. Any unknown bits
+                /// will be yielded together as a final flags value.
+                #inline
+                pub const fn iter(&self) -> #krate::iter::Iter<Self> {
+                    #krate::iter::Iter::__private_const_new(<Self as #krate::Flags>::ITER_FLAGS, *self, *self)
                 }
 
-                /// Returns the difference from this value with `other`.
+                /// Yield a set of contained named flags values.
                 ///
-                /// In other words, returns the intersection of this value with the negation of `other`.
+                /// This method is like [`iter`](#method.iter), except only yields bits in contained named flags.
+                /// Any unknown bits, or bits not corresponding to a contained flag will not be yielded.
+                #inline
+                pub const fn iter_names(&self) -> #krate::iter::IterNames<Self> {
+                    #krate::iter::IterNames::__private_const_new(<Self as #krate::Flags>::ITER_FLAGS, *self, *self)
+                }
+
+                /// Call `f` with each contained named flag, like [`iter_names`](#method.iter_names)
+                /// but without the state of an iterator, for a smaller code size.
+                #inline
+                pub fn for_each_set(&self, mut f: impl ::core::ops::FnMut(&'static str, Self)) {
+                    let flags = <Self as #krate::Flags>::ITER_FLAGS;
+                    let mut remaining = self.0;
+
+                    let mut i = 0;
+                    while i < flags.len() && remaining != 0 {
+                        let (name, flag) = flags[i];
+                        if self.0 & flag.0 == flag.0 && remaining & flag.0 != 0 {
+                            remaining &= !flag.0;
+                            f(name, flag);
+                        }
+                        i += 1;
+                    }
+                }
+
+                /// Returns `true` if `pred` returns `true` for any contained named flag, as yielded by
+                /// [`iter_names`](#method.iter_names).
+                #inline
+                pub fn any_set(&self, mut pred: impl ::core::ops::FnMut(Self) -> bool) -> bool {
+                    let flags = <Self as #krate::Flags>::ITER_FLAGS;
+                    let mut remaining = self.0;
+
+                    let mut i = 0;
+                    while i < flags.len() && remaining != 0 {
+                        let (_, flag) = flags[i];
+                        if self.0 & flag.0 == flag.0 && remaining & flag.0 != 0 {
+                            remaining &= !flag.0;
+                            if pred(flag) {
+                                return true;
+                            }
+                        }
+                        i += 1;
+                    }
+
+                    false
+                }
+
+                /// Returns `true` if `pred` returns `true` for all contained named flags, as yielded
+                /// by [`iter_names`](#method.iter_names), which is the case for an empty value.
+                #inline
+                pub fn all_set(&self, mut pred: impl ::core::ops::FnMut(Self) -> bool) -> bool {
+                    !self.any_set(|flag| !pred(flag))
+                }
+
+                /// Yield each unknown bit set in the flags value as its own flags value.
                 ///
-                /// This method is not equivalent to `self & !other` when `other` has unknown bits set.
-                /// `difference` won't truncate `other`, but the `!` operator will.
-                #[inline]
-                pub const fn difference(self, other: Self) -> Self {
-                    self.and(other.not())
+                /// The bits are yielded from the lowest to the highest.
+                #inline
+                pub const fn iter_unknown_bits(&self) -> #krate::iter::IterUnknownBits<Self> {
+                    #krate::iter::IterUnknownBits::__private_const_new(Self(self.0 & !Self::ALL_MASK))
                 }
 
-                /// Returns the symmetric difference from this value with `other`.
-                #[inline]
-                #[doc(alias = "xor")]
-                pub const fn symmetric_difference(self, other: Self) -> Self {
-                    self.xor(other)
+                /// Yield the name of every defined flag, with whether the flag is contained in the
+                /// flags value.
+                #inline
+                pub const fn iter_states(&self) -> #krate::iter::IterStates<Self> {
+                    #krate::iter::IterStates::__private_const_new(<Self as #krate::Flags>::KNOWN_FLAGS, *self)
                 }
 
-                /// Returns the complement of the value.
+                /// Yield the name and value of every defined flag not contained in the flags value.
                 ///
-                /// This is very similar to the [`not`](Self::not), but truncates non used bits.
-                #[inline]
-                #[doc(alias = "not")]
-                pub const fn complement(self) -> Self {
-                    self.not().truncated()
+                /// A multi-bit flag is yielded if any of its bits is unset.
+                #inline
+                pub const fn iter_missing(&self) -> #krate::iter::IterMissing<Self> {
+                    #krate::iter::IterMissing::__private_const_new(<Self as #krate::Flags>::KNOWN_FLAGS, *self)
                 }
 
-                /// Set the flags in `other` in the value.
-                #[inline]
-                #[doc(alias = "insert")]
-                pub #const_mut fn set(&mut self, other: Self) {
-                    self.0 = self.or(other).0
+                /// Explain a bits value, with a line for each contained named flag, with the positions of
+                /// its bits and its documentation, and for each remaining bit.
+                ///
+                #[doc = #explain_bits_doc]
+                #inline
+                pub fn explain_bits(bits: #inner_ty) -> #krate::parser::ExplainBits<Self> {
+                    #krate::parser::explain_bits(bits)
                 }
 
-                /// Unset the flags bits in `other` in the value.
-                #[inline]
-                #[doc(alias = "remove")]
-                pub #const_mut fn unset(&mut self, other: Self) {
-                    self.0 = self.difference(other).0
+                #symbolic_methods
+
+                /// The name of the single-bit flag of each bit, indexed by the bit.
+                ///
+                /// When several flags have the same bit, the first defined one is used.
+                pub const NAME_BY_BIT: [::core::option::Option<&'static str>; #inner_ty::BITS as usize] = {
+                    let known = <Self as #krate::Flags>::KNOWN_FLAGS;
+                    let mut table = [::core::option::Option::None; #inner_ty::BITS as usize];
+
+                    let mut i = 0;
+                    while i < known.len() {
+                        let (name, flag) = known[i];
+                        if flag.0.count_ones() == 1 {
+                            let bit = flag.0.trailing_zeros() as usize;
+                            if table[bit].is_none() {
+                                table[bit] = ::core::option::Option::Some(name);
+                            }
+                        }
+                        i += 1;
+                    }
+
+                    table
+                };
+
+                /// Returns the name of the single-bit flag of the bit at `index`, or [`None`] if
+                /// there is none.
+                #inline
+                pub const fn name_of_bit(index: u32) -> ::core::option::Option<&'static str> {
+                    if index < #inner_ty::BITS {
+                        Self::NAME_BY_BIT[index as usize]
+                    } else {
+                        ::core::option::Option::None
+                    }
+                }
+
+                /// The length in bytes of the longest flag name.
+                pub const MAX_NAME_LEN: usize = {
+                    let known = <Self as #krate::Flags>::KNOWN_FLAGS;
+                    let mut max = 0;
+
+                    let mut i = 0;
+                    while i < known.len() {
+                        if known[i].0.len() > max {
+                            max = known[i].0.len();
+                        }
+                        i += 1;
+                    }
+
+                    max
+                };
+
+                /// The worst-case length in bytes of a flags value written as text by
+                #[doc = #to_writer_doc]
+                /// always fits the text.
+                ///
+                /// It counts every flag name, the ` | ` separators and the hex number of the bits
+                /// that aren't part of a flag.
+                pub const MAX_TEXT_LEN: usize = {
+                    let flags = <Self as #krate::Flags>::ITER_FLAGS;
+                    let mut len = 0;
+
+                    let mut i = 0;
+                    while i < flags.len() {
+                        // The name and the separator after it
+                        len += flags[i].0.len() + 3;
+                        i += 1;
+                    }
+
+                    // `0x` and a hex digit per 4 bits
+                    len + 2 + #inner_ty::BITS as usize / 4
+                };
+            }
+
+            #iter_impls
+
+            #(#convert_impls)*
+
+            #(#widen_impls)*
+
+            #fields_impl
+
+            #nonzero_impl
+
+            #consts_impl
+            #with_methods_impl
+            #accessors_impl
+
+            #deny_gaps_check
+            #width_impl
+
+            #(#zero_check)*
+
+            #(#stray_bits_warnings)*
+
+            #version_impl
+            #decode_bits_impl
+
+            #serialize_impl
+            #deserialize_impl
+            #json_impl
+            #encode_impl
+            #decode_impl
+            #scale_encode_impl
+            #scale_decode_impl
+            #type_info_impl
+            #visualizer_module
+            #from_file_include
+            #zvariant_type_impl
+            #log_kv_impl
+            #godot_impl
+            #sea_orm_impl
+            #bson_impl
+            #juniper_impl
+            #rocket_impl
+            #strum_impl
+            #verify_impl
+        };
+
+        tokens.append_all(generated);
+    }
+}
+
+pub struct Args {
+    ty: Path,
+    subset_ord: bool,
+    strict: bool,
+    not_retains: bool,
+    nonzero: bool,
+    /// The companion constants module, with an optional custom name.
+    consts: Option<Option<Ident>>,
+    auto_derive: Option<AutoDerive>,
+    /// Whether `iter` yields the flags in bit order instead of definition order.
+    iter_bit_order: Option<bool>,
+    /// The number of bits used of the bits type.
+    width: Option<LitInt>,
+    /// Whether the generated methods are marked `#[inline(always)]`.
+    aggressive_inline: bool,
+    /// Whether a `with_<flag>` and a `without_<flag>` method are generated for each flag.
+    with_methods: bool,
+    /// Whether an `is_<flag>` and a `set_<flag>` method are generated for each flag.
+    accessors: bool,
+    /// Whether the operator traits are not implemented.
+    no_ops: bool,
+    /// Whether the conversions from and to the bits are not implemented.
+    no_from: bool,
+    /// How the bits are converted to the type, with the value of the option.
+    from_bits: Option<(Ident, ConvertMode)>,
+    /// Whether `FromStr` is not implemented.
+    no_from_str: bool,
+    /// Whether the iterator traits are not implemented.
+    no_iter: bool,
+    /// Whether `Display` is implemented.
+    display: bool,
+    /// Whether `ConstParamTy` is derived.
+    const_param_ty: bool,
+    /// Whether the operator traits are implemented as `impl const`.
+    const_ops: bool,
+    /// The file to write the generated code to.
+    debug_expand: Option<LitStr>,
+    /// The directory to write the debugger visualizers to.
+    debugger_visualizer: Option<LitStr>,
+    /// The module of the type from the crate root, for the natvis type name.
+    debugger_module: Option<LitStr>,
+    /// How `Deserialize` handles unknown bits.
+    deserialize_unknown_bits: Option<UnknownBitsPolicy>,
+    /// The names of the methods setting and unsetting flags.
+    method_names: Option<MethodNames>,
+    /// The parser of the `FromStr` implementation.
+    from_str: Option<FromStrParser>,
+    /// The file to read flags from.
+    from_file: Option<LitStr>,
+    /// How the flags are converted to BSON, if they are.
+    bson: Option<BsonRepr>,
+    /// The prefix removed from the flag names when formatting and parsing.
+    strip_prefix: Option<LitStr>,
+    /// Whether `strum::VariantNames` and `strum::EnumCount` are implemented.
+    strum: bool,
+    /// Whether `log::kv::ToValue` is implemented.
+    log_kv: bool,
+    /// Whether the `godot` property traits are implemented.
+    godot: bool,
+    /// Whether the `sea-orm` value traits are implemented.
+    sea_orm: bool,
+    /// Whether `juniper::GraphQLScalar` is derived.
+    juniper: bool,
+    /// Whether the `rocket` parsing traits are implemented.
+    rocket: bool,
+    /// The function checking the invariants of a value.
+    validate: Option<Path>,
+    /// The oldest Rust version the generated code must compile with.
+    msrv: Option<LitStr>,
+    /// The type-specific error of `FromStr`, with an optional custom name.
+    parse_error: Option<Option<Ident>>,
+    /// The path of the `bitflag_attr` crate.
+    krate: Option<Path>,
+}
+
+/// How the unknown bits of a value are handled when deserializing it.
+#[derive(Clone, Copy)]
+enum UnknownBitsPolicy {
+    /// Keep the unknown bits.
+    Retain,
+    /// Unset the unknown bits.
+    Truncate,
+    /// Fail with an error.
+    Deny,
+}
+
+/// The parser used by the `FromStr` implementation.
+#[derive(Clone, Copy)]
+enum FromStrParser {
+    /// `parser::from_text`, accepting the flag names and hexadecimal bits.
+    Default,
+    /// `parser::from_text_strict`, only accepting the flag names.
+    Strict,
+    /// `parser::from_text_truncate`, unsetting the unknown bits.
+    Truncate,
+}
+
+/// The BSON value the flags are converted to.
+#[derive(Clone, Copy)]
+enum BsonRepr {
+    /// The bits, as a 32-bit integer if they fit in one, or else as a 64-bit integer.
+    Int,
+    /// The text of the flags, as written by `parser::to_writer`.
+    Text,
+}
+
+/// The names of the methods setting and unsetting flags.
+#[derive(Clone, Copy)]
+enum MethodNames {
+    /// `set`, `unset`, `try_set` and `try_unset`.
+    Default,
+    /// `insert`, `remove`, `set` with a `bool`, `try_insert` and `try_remove`, like the `bitflags`
+    /// crate, set with `method_names = bitflags`.
+    Bitflags,
+}
+
+/// The traits derived by the `auto_derive` option.
+#[derive(Clone, Copy)]
+enum AutoDerive {
+    /// `Clone` and `Copy`, required by the generated code.
+    Required,
+    /// `Clone`, `Copy`, `PartialEq`, `Eq` and `Hash`, set with `auto_derive = full`.
+    Full,
+}
+
+impl AutoDerive {
+    fn traits(self) -> &'static [&'static str] {
+        match self {
+            AutoDerive::Required => &["::core::clone::Clone", "::core::marker::Copy"],
+            AutoDerive::Full => &[
+                "::core::clone::Clone",
+                "::core::marker::Copy",
+                "::core::cmp::PartialEq",
+                "::core::cmp::Eq",
+                "::core::hash::Hash",
+            ],
+        }
+    }
+}
+
+impl Parse for Args {
+    fn parse(input: syn::parse::ParseStream) -> syn::Result<Self> {
+        let ty: Path = input.parse().map_err(|err| {
+            Error::new(err.span(), "unexpected token: expected a `{integer}` type")
+        })?;
+
+        if !cfg!(feature = "custom-types") {
+            if let Some(ident) = ty.get_ident() {
+                if !VALID_TYPES.contains(&ident.to_string().as_str()) {
+                    return Err(Error::new_spanned(ident, "type must be a `{integer}` type"));
+                }
+            }
+        }
+
+        let mut args = Args {
+            ty,
+            subset_ord: false,
+            strict: false,
+            not_retains: false,
+            nonzero: false,
+            consts: None,
+            auto_derive: None,
+            iter_bit_order: None,
+            width: None,
+            aggressive_inline: false,
+            with_methods: false,
+            accessors: false,
+            no_ops: false,
+            no_from: false,
+            from_bits: None,
+            no_from_str: false,
+            no_iter: false,
+            display: false,
+            const_param_ty: false,
+            const_ops: false,
+            debug_expand: None,
+            debugger_visualizer: None,
+            debugger_module: None,
+            deserialize_unknown_bits: None,
+            method_names: None,
+            from_str: None,
+            from_file: None,
+            bson: None,
+            strip_prefix: None,
+            strum: false,
+            log_kv: false,
+            godot: false,
+            sea_orm: false,
+            juniper: false,
+            rocket: false,
+            validate: None,
+            msrv: None,
+            parse_error: None,
+            krate: None,
+        };
+
+        if input.is_empty() {
+            return Ok(args);
+        }
+
+        input.parse::<syn::Token![,]>()?;
+
+        let options: TokenStream = input.parse()?;
+        let parser = syn::meta::parser(|meta| {
+            if meta.path.is_ident("subset_ord") {
+                set_option(&mut args.subset_ord, &meta)
+            } else if meta.path.is_ident("strict") {
+                set_option(&mut args.strict, &meta)
+            } else if meta.path.is_ident("not_retains") {
+                set_option(&mut args.not_retains, &meta)
+            } else if meta.path.is_ident("nonzero") {
+                set_option(&mut args.nonzero, &meta)
+            } else if meta.path.is_ident("aggressive_inline") {
+                set_option(&mut args.aggressive_inline, &meta)
+            } else if meta.path.is_ident("with_methods") {
+                set_option(&mut args.with_methods, &meta)
+            } else if meta.path.is_ident("accessors") {
+                set_option(&mut args.accessors, &meta)
+            } else if meta.path.is_ident("no_ops") {
+                set_option(&mut args.no_ops, &meta)
+            } else if meta.path.is_ident("no_from") {
+                set_option(&mut args.no_from, &meta)
+            } else if meta.path.is_ident("no_from_str") {
+                set_option(&mut args.no_from_str, &meta)
+            } else if meta.path.is_ident("no_iter") {
+                set_option(&mut args.no_iter, &meta)
+            } else if meta.path.is_ident("display") {
+                set_option(&mut args.display, &meta)
+            } else if meta.path.is_ident("const_param_ty") {
+                set_option(&mut args.const_param_ty, &meta)
+            } else if meta.path.is_ident("const_ops") {
+                set_option(&mut args.const_ops, &meta)
+            } else if meta.path.is_ident("debug_expand") {
+                if args.debug_expand.is_some() {
+                    return Err(meta.error("duplicated option `debug_expand`"));
+                }
+
+                args.debug_expand = Some(meta.value()?.parse()?);
+                Ok(())
+            } else if meta.path.is_ident("from_file") {
+                if args.from_file.is_some() {
+                    return Err(meta.error("duplicated option `from_file`"));
+                }
+
+                args.from_file = Some(meta.value()?.parse()?);
+                Ok(())
+            } else if meta.path.is_ident("debugger_visualizer") {
+                if args.debugger_visualizer.is_some() {
+                    return Err(meta.error("duplicated option `debugger_visualizer`"));
+                }
+
+                args.debugger_visualizer = Some(meta.value()?.parse()?);
+                Ok(())
+            } else if meta.path.is_ident("debugger_module") {
+                if args.debugger_module.is_some() {
+                    return Err(meta.error("duplicated option `debugger_module`"));
+                }
+
+                args.debugger_module = Some(meta.value()?.parse()?);
+                Ok(())
+            } else if meta.path.is_ident("strip_prefix") {
+                if args.strip_prefix.is_some() {
+                    return Err(meta.error("duplicated option `strip_prefix`"));
+                }
+
+                args.strip_prefix = Some(meta.value()?.parse()?);
+                Ok(())
+            } else if meta.path.is_ident("strum") {
+                set_feature_option(&mut args.strum, &meta, cfg!(feature = "strum"), "strum")
+            } else if meta.path.is_ident("log_kv") {
+                set_feature_option(&mut args.log_kv, &meta, cfg!(feature = "log-kv"), "log-kv")
+            } else if meta.path.is_ident("godot") {
+                // The property is an `i64`, the bits wider than it would be lost
+                if ty_bits_width(&args.ty).is_some_and(|width| width > 64) {
+                    return Err(meta.error(
+                        "the `godot` option can't be used with bits types wider than 64 bits",
+                    ));
+                }
+
+                set_feature_option(&mut args.godot, &meta, cfg!(feature = "godot"), "godot")
+            } else if meta.path.is_ident("sea_orm") {
+                set_feature_option(
+                    &mut args.sea_orm,
+                    &meta,
+                    cfg!(feature = "sea-orm"),
+                    "sea-orm",
+                )
+            } else if meta.path.is_ident("juniper") {
+                set_feature_option(
+                    &mut args.juniper,
+                    &meta,
+                    cfg!(feature = "juniper"),
+                    "juniper",
+                )
+            } else if meta.path.is_ident("rocket") {
+                set_feature_option(&mut args.rocket, &meta, cfg!(feature = "rocket"), "rocket")
+            } else if meta.path.is_ident("crate") {
+                if args.krate.is_some() {
+                    return Err(meta.error("duplicated option `crate`"));
+                }
+
+                args.krate = Some(meta.value()?.parse()?);
+                Ok(())
+            } else if meta.path.is_ident("validate") {
+                if args.validate.is_some() {
+                    return Err(meta.error("duplicated option `validate`"));
+                }
+
+                args.validate = Some(meta.value()?.parse()?);
+                Ok(())
+            } else if meta.path.is_ident("msrv") {
+                if args.msrv.is_some() {
+                    return Err(meta.error("duplicated option `msrv`"));
+                }
+
+                args.msrv = Some(meta.value()?.parse()?);
+                Ok(())
+            } else if meta.path.is_ident("deserialize_unknown_bits") {
+                if args.deserialize_unknown_bits.is_some() {
+                    return Err(meta.error("duplicated option `deserialize_unknown_bits`"));
+                }
+
+                let value: Ident = meta.value()?.parse()?;
+                args.deserialize_unknown_bits = if value == "retain" {
+                    Some(UnknownBitsPolicy::Retain)
+                } else if value == "truncate" {
+                    Some(UnknownBitsPolicy::Truncate)
+                } else if value == "deny" {
+                    Some(UnknownBitsPolicy::Deny)
+                } else {
+                    return Err(Error::new_spanned(
+                        value,
+                        "unknown `deserialize_unknown_bits` value: expected `retain`, `truncate` or `deny`",
+                    ));
+                };
+                Ok(())
+            } else if meta.path.is_ident("from_str") {
+                if args.from_str.is_some() {
+                    return Err(meta.error("duplicated option `from_str`"));
+                }
+
+                let value: Ident = meta.value()?.parse()?;
+                args.from_str = if value == "default" {
+                    Some(FromStrParser::Default)
+                } else if value == "strict" {
+                    Some(FromStrParser::Strict)
+                } else if value == "truncate" {
+                    Some(FromStrParser::Truncate)
+                } else {
+                    return Err(Error::new_spanned(
+                        value,
+                        "unknown `from_str` value: expected `default`, `strict` or `truncate`",
+                    ));
+                };
+                Ok(())
+            } else if meta.path.is_ident("from_bits") {
+                if args.from_bits.is_some() {
+                    return Err(meta.error("duplicated option `from_bits`"));
+                }
+
+                let value: Ident = meta.value()?.parse()?;
+                let mode = if value == "truncate" {
+                    ConvertMode::Truncate
+                } else if value == "retain" {
+                    ConvertMode::Retain
+                } else if value == "checked" {
+                    ConvertMode::Checked
+                } else {
+                    return Err(Error::new_spanned(
+                        value,
+                        "unknown `from_bits` value: expected `truncate`, `retain` or `checked`",
+                    ));
+                };
+                args.from_bits = Some((value, mode));
+                Ok(())
+            } else if meta.path.is_ident("bson") {
+                if args.bson.is_some() {
+                    return Err(meta.error("duplicated option `bson`"));
+                }
+
+                let repr = if !meta.input.peek(syn::Token![=]) {
+                    BsonRepr::Int
+                } else {
+                    let value: Ident = meta.value()?.parse()?;
+                    if value == "int" {
+                        BsonRepr::Int
+                    } else if value == "text" {
+                        BsonRepr::Text
+                    } else {
+                        return Err(Error::new_spanned(
+                            value,
+                            "unknown `bson` value: expected `int` or `text`",
+                        ));
+                    }
+                };
+
+                // The integers of BSON are at most an `i64`, the bits wider than it would be lost
+                if matches!(repr, BsonRepr::Int)
+                    && ty_bits_width(&args.ty).is_some_and(|width| width > 64)
+                {
+                    return Err(Error::new_spanned(
+                        &meta.path,
+                        "the `bson = int` option can't be used with bits types wider than 64 bits, use `bson = text` instead",
+                    ));
+                }
+
+                if !cfg!(feature = "bson") {
+                    return Err(Error::new_spanned(
+                        &meta.path,
+                        "the `bson` option requires the `bson` feature of `bitflag-attr`",
+                    ));
+                }
+
+                args.bson = Some(repr);
+                Ok(())
+            } else if meta.path.is_ident("method_names") {
+                if args.method_names.is_some() {
+                    return Err(meta.error("duplicated option `method_names`"));
+                }
+
+                let value: Ident = meta.value()?.parse()?;
+                args.method_names = if value == "default" {
+                    Some(MethodNames::Default)
+                } else if value == "bitflags" {
+                    Some(MethodNames::Bitflags)
+                } else {
+                    return Err(Error::new_spanned(
+                        value,
+                        "unknown `method_names` value: expected `default` or `bitflags`",
+                    ));
+                };
+                Ok(())
+            } else if meta.path.is_ident("consts") {
+                if args.consts.is_some() {
+                    return Err(meta.error("duplicated option `consts`"));
+                }
+
+                args.consts = if meta.input.peek(syn::Token![=]) {
+                    Some(Some(meta.value()?.parse()?))
+                } else {
+                    Some(None)
+                };
+                Ok(())
+            } else if meta.path.is_ident("parse_error") {
+                if args.parse_error.is_some() {
+                    return Err(meta.error("duplicated option `parse_error`"));
+                }
+
+                args.parse_error = if meta.input.peek(syn::Token![=]) {
+                    Some(Some(meta.value()?.parse()?))
+                } else {
+                    Some(None)
+                };
+                Ok(())
+            } else if meta.path.is_ident("auto_derive") {
+                if args.auto_derive.is_some() {
+                    return Err(meta.error("duplicated option `auto_derive`"));
+                }
+
+                args.auto_derive = if meta.input.peek(syn::Token![=]) {
+                    let value: Ident = meta.value()?.parse()?;
+                    if value != "full" {
+                        return Err(Error::new_spanned(
+                            value,
+                            "unknown `auto_derive` value: expected `full`",
+                        ));
+                    }
+                    Some(AutoDerive::Full)
+                } else {
+                    Some(AutoDerive::Required)
+                };
+                Ok(())
+            } else if meta.path.is_ident("iter_order") {
+                if args.iter_bit_order.is_some() {
+                    return Err(meta.error("duplicated option `iter_order`"));
+                }
+
+                let value: Ident = meta.value()?.parse()?;
+                args.iter_bit_order = if value == "bits" {
+                    Some(true)
+                } else if value == "definition" {
+                    Some(false)
+                } else {
+                    return Err(Error::new_spanned(
+                        value,
+                        "unknown `iter_order` value: expected `definition` or `bits`",
+                    ));
+                };
+                Ok(())
+            } else if meta.path.is_ident("width") {
+                if args.width.is_some() {
+                    return Err(meta.error("duplicated option `width`"));
+                }
+
+                let value: LitInt = meta.value()?.parse()?;
+                let width: u32 = value.base10_parse()?;
+                let max = args
+                    .ty
+                    .get_ident()
+                    .and_then(|ident| bits_width(&ident.to_string()))
+                    .unwrap_or(128);
+
+                if width == 0 || width > max {
+                    return Err(Error::new_spanned(
+                        value,
+                        format_args!("`width` must be between 1 and {max}"),
+                    ));
+                }
+
+                args.width = Some(LitInt::new(&width.to_string(), value.span()));
+                Ok(())
+            } else {
+                Err(meta.error(format_args!(
+                    "unknown option `{}`",
+                    meta.path.to_token_stream()
+                )))
+            }
+        });
+        syn::parse::Parser::parse2(parser, options)?;
+
+        Ok(args)
+    }
+}
+
+/// Set a flag option, erroring if it was already set.
+fn set_option(option: &mut bool, meta: &syn::meta::ParseNestedMeta) -> syn::Result<()> {
+    if *option {
+        return Err(meta.error(format_args!(
+            "duplicated option `{}`",
+            meta.path.to_token_stream()
+        )));
+    }
+
+    *option = true;
+    Ok(())
+}
+
+/// Set a flag option generating the implementations for another crate, erroring if the feature of
+/// these implementations is disabled.
+///
+/// The implementations name the other crate, so they are only generated for the types asking for
+/// them: the features are unified across the dependency graph, and the other crates using this one
+/// may not depend on that crate.
+fn set_feature_option(
+    option: &mut bool,
+    meta: &syn::meta::ParseNestedMeta,
+    enabled: bool,
+    feature: &str,
+) -> syn::Result<()> {
+    if !enabled {
+        return Err(meta.error(format_args!(
+            "the `{}` option requires the `{feature}` feature of `bitflag-attr`",
+            meta.path.to_token_stream()
+        )));
+    }
+
+    set_option(option, meta)
+}
+
+struct ExtraValidBits(Expr);
+
+impl ExtraValidBits {
+    fn from_meta(meta: &Meta) -> syn::Result<Self> {
+        match meta {
+            Meta::NameValue(m) => {
+                if !m.path.is_ident("extra_valid_bits") {
+                    return Err(Error::new(
+                        m.span(),
+                        "not a valid `extra_valid_bits` attribute",
+                    ));
                 }
 
-                /// Toggle the flags in `other` in the value.
-                #[inline]
-                pub #const_mut fn toggle(&mut self, other: Self) {
-                    self.0 = self.xor(other).0
-                }
+                Ok(Self(m.value.clone()))
             }
+            _ => Err(Error::new(
+                meta.span(),
+                "extra_valid_bits must follow the syntax `extra_valid_bits = <expr>`",
+            )),
+        }
+    }
+}
 
-            #[automatically_derived]
-            impl ::core::ops::Not for #name {
-                type Output = Self;
+impl Parse for ExtraValidBits {
+    fn parse(input: syn::parse::ParseStream) -> syn::Result<Self> {
+        let meta: MetaNameValue = input.parse()?;
 
-                #[inline]
-                fn not(self) -> Self::Output {
-                    self.complement()
-                }
-            }
+        if !meta.path.is_ident("extra_valid_bits") {
+            return Err(Error::new(meta.span(), "not a extra_valid_bits attribute"));
+        }
 
-            #[automatically_derived]
-            impl ::core::ops::BitAnd for #name {
-                type Output = Self;
+        Ok(Self(meta.value))
+    }
+}
 
-                #[inline]
-                fn bitand(self, rhs: Self) -> Self::Output {
-                    self.and(rhs)
-                }
-            }
+/// A `#[bitflag_convert(OtherFlags)]` or `#[bitflag_convert(OtherFlags, <mode>)]` attribute.
+struct BitflagConvert {
+    ty: Path,
+    mode: ConvertMode,
+}
 
-            #[automatically_derived]
-            impl ::core::ops::BitOr for #name {
-                type Output = Self;
+enum ConvertMode {
+    /// Bit-identical `From` conversions.
+    Retain,
+    /// `From` conversions truncating to the target known bits.
+    Truncate,
+    /// `TryFrom` conversions failing if unknown bits of the target are set.
+    Checked,
+}
 
-                #[inline]
-                fn bitor(self, rhs: Self) -> Self::Output {
-                    self.or(rhs)
-                }
-            }
+impl Parse for BitflagConvert {
+    fn parse(input: syn::parse::ParseStream) -> syn::Result<Self> {
+        let ty: Path = input.parse()?;
 
-            #[automatically_derived]
-            impl ::core::ops::BitXor for #name {
-                type Output = Self;
+        let mode = if input.parse::<Option<syn::Token![,]>>()?.is_some() && !input.is_empty() {
+            let mode: Ident = input.parse()?;
 
-                #[inline]
-                fn bitxor(self, rhs: Self) -> Self::Output {
-                    self.xor(rhs)
+            match mode.to_string().as_str() {
+                "retain" => ConvertMode::Retain,
+                "truncate" => ConvertMode::Truncate,
+                "checked" => ConvertMode::Checked,
+                _ => {
+                    return Err(Error::new_spanned(
+                        mode,
+                        "unknown conversion mode: expected `retain`, `truncate` or `checked`",
+                    ))
                 }
             }
+        } else {
+            ConvertMode::Retain
+        };
 
-            #[automatically_derived]
-            impl ::core::ops::BitAndAssign for #name {
-                #[inline]
-                fn bitand_assign(&mut self, rhs: Self) {
-                    ::core::ops::BitAndAssign::bitand_assign(&mut self.0, rhs.0)
-                }
-            }
+        if !input.is_empty() {
+            return Err(input.error("unexpected token"));
+        }
 
-            #[automatically_derived]
-            impl ::core::ops::BitOrAssign for #name {
-                #[inline]
-                fn bitor_assign(&mut self, rhs: Self) {
-                    ::core::ops::BitOrAssign::bitor_assign(&mut self.0, rhs.0)
-                }
-            }
+        Ok(Self { ty, mode })
+    }
+}
 
-            #[automatically_derived]
-            impl ::core::ops::BitXorAssign for #name {
-                #[inline]
-                fn bitxor_assign(&mut self, rhs: Self) {
-                    ::core::ops::BitXorAssign::bitxor_assign(&mut self.0, rhs.0)
-                }
-            }
+/// A `name: Flags @ start..end` entry of the `#[bitflag_fields(...)]` attribute.
+struct BitflagField {
+    name: Ident,
+    ty: Path,
+    range: Range<u32>,
+}
 
-            #[automatically_derived]
-            impl ::core::ops::Sub for #name {
-                type Output = Self;
+impl BitflagField {
+    /// The mask of the bits of the field.
+    fn mask(&self) -> u128 {
+        let width = self.range.end - self.range.start;
+        let ones = u128::MAX.checked_shr(128 - width).unwrap_or(0);
 
-                /// The intersection of a source flag with the complement of a target flags value
-                #[inline]
-                fn sub(self, rhs: Self) -> Self::Output {
-                    self.difference(rhs)
-                }
-            }
+        ones << self.range.start
+    }
+}
 
-            #[automatically_derived]
-            impl ::core::ops::SubAssign for #name {
-                /// The intersection of a source flag with the complement of a target flags value
-                #[inline]
-                fn sub_assign(&mut self, rhs: Self) {
-                    self.unset(rhs)
-                }
-            }
+impl Parse for BitflagField {
+    fn parse(input: syn::parse::ParseStream) -> syn::Result<Self> {
+        let name: Ident = input.parse()?;
+        input.parse::<syn::Token![:]>()?;
+        let ty: Path = input.parse()?;
+        input.parse::<syn::Token![@]>()?;
+
+        let start: LitInt = input.parse()?;
+        input.parse::<syn::Token![..]>()?;
+        let end: LitInt = input.parse()?;
+
+        let range = start.base10_parse()?..end.base10_parse()?;
+        if range.start >= range.end || range.end > 128 {
+            return Err(Error::new(
+                start.span(),
+                "invalid field range: expected `start..end` with `start < end <= 128`",
+            ));
+        }
 
-            #[automatically_derived]
-            impl ::core::convert::From<#inner_ty> for #name {
-                #[inline]
-                fn from(val: #inner_ty) -> Self {
-                    Self::from_bits_truncate(val)
-                }
-            }
+        Ok(Self { name, ty, range })
+    }
+}
 
-            #[automatically_derived]
-            impl ::core::convert::From<#name> for #inner_ty {
-                #[inline]
-                fn from(val: #name) -> Self {
-                    val.0
-                }
-            }
+/// The options of a flag, set with the `#[flag(...)]` helper attribute.
+#[derive(Default)]
+pub struct FlagOptions {
+    /// The version the flag was introduced in.
+    since: Option<[u32; 3]>,
+    /// Whether the flag is allowed to have no bits set.
+    zero: bool,
+    /// Whether the flag is allowed to have bits not covered by any single-bit flag.
+    allow_stray_bits: bool,
+    /// The default display label of the flag.
+    label: Option<LitStr>,
+    /// The character of the flag in the symbolic format.
+    symbol: Option<syn::LitChar>,
+    /// The other names of the flag, with a constant each, accepted when parsing and found by in the
+    /// documentation.
+    aliases: Vec<LitStr>,
+    /// Whether the flag is a convenience mask of other flags, never yielded by the iterators, set
+    /// with the `#[composite]` helper attribute.
+    composite: bool,
+    /// The values of the flag on the targets matching a `cfg` predicate, in order.
+    values: Vec<(Meta, Expr)>,
+}
 
-            #[automatically_derived]
-            impl ::core::fmt::Binary for #name {
-                #[inline]
-                fn fmt(&self, f: &mut ::core::fmt::Formatter<'_>) -> ::core::fmt::Result {
-                    ::core::fmt::Binary::fmt(&self.0, f)
+impl FlagOptions {
+    /// Parse the `#[flag(...)]` and `#[composite]` attributes, returning the options and the
+    /// remaining attributes.
+    fn from_attrs(attrs: &[Attribute]) -> syn::Result<(Self, Vec<Attribute>)> {
+        let mut options = Self::default();
+        let mut remaining = Vec::with_capacity(attrs.len());
+
+        for attr in attrs {
+            if attr.path().is_ident("composite") {
+                attr.meta.require_path_only()?;
+                if options.composite {
+                    return Err(Error::new_spanned(attr, "duplicated attribute `composite`"));
                 }
-            }
 
-            #[automatically_derived]
-            impl ::core::fmt::LowerHex for #name {
-                #[inline]
-                fn fmt(&self, f: &mut ::core::fmt::Formatter<'_>) -> ::core::fmt::Result {
-                    ::core::fmt::LowerHex::fmt(&self.0, f)
-                }
+                options.composite = true;
+                continue;
             }
 
-            #[automatically_derived]
-            impl ::core::fmt::UpperHex for #name {
-                #[inline]
-                fn fmt(&self, f: &mut ::core::fmt::Formatter<'_>) -> ::core::fmt::Result {
-                    ::core::fmt::UpperHex::fmt(&self.0, f)
-                }
+            if !attr.path().is_ident("flag") {
+                remaining.push(attr.clone());
+                continue;
             }
 
-            #[automatically_derived]
-            impl ::core::fmt::Octal for #name {
-                #[inline]
-                fn fmt(&self, f: &mut ::core::fmt::Formatter<'_>) -> ::core::fmt::Result {
-                    ::core::fmt::Octal::fmt(&self.0, f)
-                }
-            }
+            attr.parse_nested_meta(|meta| {
+                if meta.path.is_ident("since") {
+                    if options.since.is_some() {
+                        return Err(meta.error("duplicated option `since`"));
+                    }
 
-            #[automatically_derived]
-            impl ::core::str::FromStr for #name {
-                type Err = ::bitflag_attr::parser::ParseError;
+                    let version: LitStr = meta.value()?.parse()?;
+                    options.since = Some(parse_version(&version)?);
+                    Ok(())
+                } else if meta.path.is_ident("zero") {
+                    if options.zero {
+                        return Err(meta.error("duplicated option `zero`"));
+                    }
 
-                fn from_str(input: &str) -> ::core::result::Result<Self, Self::Err> {
-                    ::bitflag_attr::parser::from_text(input)
-                }
-            }
+                    options.zero = true;
+                    Ok(())
+                } else if meta.path.is_ident("allow_stray_bits") {
+                    if options.allow_stray_bits {
+                        return Err(meta.error("duplicated option `allow_stray_bits`"));
+                    }
 
-            #debug_impl
+                    options.allow_stray_bits = true;
+                    Ok(())
+                } else if meta.path.is_ident("label") {
+                    if options.label.is_some() {
+                        return Err(meta.error("duplicated option `label`"));
+                    }
 
-            impl ::bitflag_attr::Flags for #name {
-                const KNOWN_FLAGS: &'static [(&'static str, #name)] = &[#(
-                    #(#all_attrs)*
-                    (#all_flags_names , #all_flags) ,
-                )*];
+                    options.label = Some(meta.value()?.parse()?);
+                    Ok(())
+                } else if meta.path.is_ident("symbol") {
+                    if options.symbol.is_some() {
+                        return Err(meta.error("duplicated option `symbol`"));
+                    }
 
-                const EXTRA_VALID_BITS: #inner_ty = #extra_valid_bits_value;
+                    let symbol: syn::LitChar = meta.value()?.parse()?;
+                    if symbol.value() == '-' {
+                        return Err(Error::new_spanned(
+                            symbol,
+                            "`-` can't be a symbol, it's the symbol of the unset flags",
+                        ));
+                    }
 
-                type Bits = #inner_ty;
+                    options.symbol = Some(symbol);
+                    Ok(())
+                } else if meta.path.is_ident("alias") {
+                    let alias: LitStr = meta.value()?.parse()?;
+                    if syn::parse_str::<Ident>(&alias.value()).is_err() {
+                        return Err(Error::new_spanned(
+                            &alias,
+                            format_args!("alias `{}` is not an identifier", alias.value()),
+                        ));
+                    }
 
-                fn bits(&self) -> Self::Bits {
-                    self.0
+                    options.aliases.push(alias);
+                    Ok(())
+                } else if meta.path.is_ident("value") {
+                    let content;
+                    syn::parenthesized!(content in meta.input);
+                    let predicate: Meta = content.parse()?;
+                    content.parse::<syn::Token![,]>()?;
+                    let value: Expr = content.parse()?;
+
+                    options.values.push((predicate, value));
+                    Ok(())
+                } else {
+                    Err(meta.error(format_args!(
+                        "unknown flag option `{}`",
+                        meta.path.to_token_stream()
+                    )))
                 }
+            })?;
+        }
 
-                fn from_bits_retain(bits: Self::Bits) -> Self {
-                    Self(bits)
-                }
+        Ok((options, remaining))
+    }
+}
+
+/// The first paragraph of the doc comments of `attrs`, with its lines joined by spaces.
+fn doc_summary(attrs: &[Attribute]) -> Option<String> {
+    let mut summary = String::new();
+
+    for attr in attrs.iter().filter(|attr| attr.path().is_ident("doc")) {
+        let Meta::NameValue(MetaNameValue {
+            value:
+                Expr::Lit(syn::ExprLit {
+                    lit: syn::Lit::Str(doc),
+                    ..
+                }),
+            ..
+        }) = &attr.meta
+        else {
+            continue;
+        };
+
+        let line = doc.value();
+        let line = line.trim();
+        if line.is_empty() {
+            if summary.is_empty() {
+                continue;
             }
+            break;
+        }
 
-            impl #name {
-                const KNOWN_FLAGS: &'static [(&'static str, #name)] = &[#(
-                    #(#all_attrs)*
-                    (#all_flags_names , #all_flags) ,
-                )*];
+        if !summary.is_empty() {
+            summary.push(' ');
+        }
+        summary.push_str(line);
+    }
 
-                /// Yield a set of contained flags values.
-                ///
-                /// Each yielded flags value will correspond to a defined named flag. Any unknown bits
-                /// will be yielded together as a final flags value.
-                #[inline]
-                pub const fn iter(&self) -> ::bitflag_attr::iter::Iter<Self> {
-                    ::bitflag_attr::iter::Iter::__private_const_new(Self::KNOWN_FLAGS, *self, *self)
-                }
+    (!summary.is_empty()).then_some(summary)
+}
 
-                /// Yield a set of contained named flags values.
-                ///
-                /// This method is like [`iter`](#method.iter), except only yields bits in contained named flags.
-                /// Any unknown bits, or bits not corresponding to a contained flag will not be yielded.
-                #[inline]
-                pub const fn iter_names(&self) -> ::bitflag_attr::iter::IterNames<Self> {
-                    ::bitflag_attr::iter::IterNames::__private_const_new(Self::KNOWN_FLAGS, *self, *self)
-                }
-            }
+/// Find the flags referenced by the tokens of a discriminant, as their indices in `variants`.
+fn find_references(tokens: TokenStream, variants: &[Ident], found: &mut Vec<usize>) {
+    for token in tokens {
+        match token {
+            TokenTree::Ident(ident) => found.extend(variants.iter().position(|var| *var == ident)),
+            TokenTree::Group(group) => find_references(group.stream(), variants, found),
+            _ => {}
+        }
+    }
+}
 
-            #[automatically_derived]
-            impl ::core::iter::Extend<#name> for #name {
-                /// Set all flags of `iter` to self
-                fn extend<T: ::core::iter::IntoIterator<Item = Self>>(&mut self, iter: T) {
-                    for item in iter {
-                        self.set(item);
-                    }
-                }
+/// Parse a `major[.minor[.patch]]` version.
+fn parse_version(version: &LitStr) -> syn::Result<[u32; 3]> {
+    let value = version.value();
+    let mut parts = [0; 3];
+
+    for (i, part) in value.split('.').enumerate() {
+        match (parts.get_mut(i), part.parse()) {
+            (Some(slot), Ok(number)) => *slot = number,
+            _ => {
+                return Err(Error::new_spanned(
+                    version,
+                    "invalid version: expected `major[.minor[.patch]]`",
+                ))
             }
+        }
+    }
 
-            #[automatically_derived]
-            impl ::core::iter::FromIterator<#name> for #name {
-                #[doc = #doc_from_iter]
-                fn from_iter<T: ::core::iter::IntoIterator<Item = Self>>(iter: T) -> Self {
-                    use ::core::iter::Extend;
+    Ok(parts)
+}
 
-                    let mut res = Self::empty();
-                    res.extend(iter);
-                    res
-                }
-            }
+/// The minimum supported Rust version of `bitflag-attr`, every construct of the generated code
+/// that is newer has a fallback.
+const MIN_MSRV: [u32; 3] = [1, 75, 0];
 
-            #[automatically_derived]
-            impl ::core::iter::IntoIterator for #name {
-                type Item = Self;
-                type IntoIter = ::bitflag_attr::iter::Iter<Self>;
+/// Parse the version of the `msrv` option. The versions older than [`MIN_MSRV`] use all the
+/// fallbacks, like [`MIN_MSRV`] itself.
+fn parse_msrv(version: &LitStr) -> syn::Result<[u32; 3]> {
+    Ok(parse_version(version)?.max(MIN_MSRV))
+}
 
-                fn into_iter(self) -> Self::IntoIter {
-                    self.iter()
-                }
-            }
+/// The helper attributes of the type.
+const TYPE_HELPER_ATTRS: &[&str] = &[
+    "extra_valid_bits",
+    "non_exhaustive",
+    "bitflag_convert",
+    "bitflag_widen",
+    "bitflag_fields",
+    "bitflag_forward_attr",
+    "deny_gaps",
+];
 
-            #[automatically_derived]
-            impl ::core::iter::IntoIterator for &#name {
-                type Item = #name;
-                type IntoIter = ::bitflag_attr::iter::Iter<#name>;
+/// The generated associated constants a flag can conflict with. `WIDTH` is only generated with the
+/// `width` option, so it is checked separately.
+const RESERVED_CONSTS: &[&str] = &["ALL_MASK", "NAME_BY_BIT", "MAX_NAME_LEN", "MAX_TEXT_LEN"];
 
-                fn into_iter(self) -> Self::IntoIter {
-                    self.iter()
-                }
-            }
+/// The generated methods an accessor of a flag can conflict with.
+const ACCESSOR_CONFLICTS: &[&str] = &["is_empty", "is_all", "is_all_bits", "set_bit"];
 
-            #serialize_impl
-            #deserialize_impl
+/// The helper attributes of the flags.
+const VARIANT_HELPER_ATTRS: &[&str] = &["flag", "composite", "default"];
+
+/// Error on attributes that look like a misspelled helper attribute, as they would otherwise be
+/// passed through to the generated code. Only single identifiers a single typo away from a helper
+/// are flagged, so the attributes of other crates are left alone.
+fn check_helper_attrs(attrs: &[Attribute], helpers: &[&str]) -> syn::Result<()> {
+    for attr in attrs {
+        let Some(ident) = attr.path().get_ident() else {
+            continue;
         };
+        let name = ident.to_string();
+        if helpers.contains(&name.as_str()) {
+            continue;
+        }
 
-        tokens.append_all(generated);
+        let similar = helpers
+            .iter()
+            .find(|helper| edit_distance(&name, helper) == 1);
+
+        if let Some(helper) = similar {
+            return Err(Error::new_spanned(
+                ident,
+                format_args!("unknown helper attribute `{name}`, did you mean `{helper}`?"),
+            ));
+        }
     }
-}
 
-pub struct Args {
-    ty: Path,
+    Ok(())
 }
 
-impl Parse for Args {
-    fn parse(input: syn::parse::ParseStream) -> syn::Result<Self> {
-        let ty: Path = input.parse().map_err(|err| {
-            Error::new(err.span(), "unexpected token: expected a `{integer}` type")
-        })?;
+/// The edit distance between two strings, counting an insertion, a deletion, a substitution or a
+/// swap of two adjacent characters as one edit.
+fn edit_distance(a: &str, b: &str) -> usize {
+    let a: Vec<char> = a.chars().collect();
+    let b: Vec<char> = b.chars().collect();
+    let mut rows = vec![vec![0; b.len() + 1]; a.len() + 1];
 
-        if !cfg!(feature = "custom-types") {
-            if let Some(ident) = ty.get_ident() {
-                if !VALID_TYPES.contains(&ident.to_string().as_str()) {
-                    return Err(Error::new_spanned(ident, "type must be a `{integer}` type"));
-                }
+    for (i, row) in rows.iter_mut().enumerate() {
+        row[0] = i;
+    }
+    for (j, cell) in rows[0].iter_mut().enumerate() {
+        *cell = j;
+    }
+
+    for i in 1..=a.len() {
+        for j in 1..=b.len() {
+            let cost = usize::from(a[i - 1] != b[j - 1]);
+            let mut distance = (rows[i - 1][j - 1] + cost)
+                .min(rows[i - 1][j] + 1)
+                .min(rows[i][j - 1] + 1);
+
+            if i > 1 && j > 1 && a[i - 1] == b[j - 2] && a[i - 2] == b[j - 1] {
+                distance = distance.min(rows[i - 2][j - 2] + 1);
             }
+            rows[i][j] = distance;
         }
+    }
+
+    rows[a.len()][b.len()]
+}
 
-        Ok(Args { ty })
+/// Whether the derive `path` is the derive `name`, either unqualified or under one of the `modules`.
+fn is_derive(path: &Path, modules: &[&str], name: &str) -> bool {
+    let mut segments = path.segments.iter().map(|segment| &segment.ident);
+
+    match (segments.next(), segments.next_back()) {
+        (Some(ident), None) => path.leading_colon.is_none() && ident == name,
+        (Some(first), Some(last)) => last == name && modules.iter().any(|module| first == module),
+        (None, _) => false,
     }
 }
 
-struct ExtraValidBits(Expr);
+/// Whether an `Encode` or `Decode` derive is the one of `musli` or of `parity-scale-codec`, by its
+/// path and the enabled features. An unqualified derive is the one of the only enabled feature, and
+/// is kept when both are enabled, as it can't be told apart.
+fn codec_of_derive(path: &Path) -> (bool, bool) {
+    let (musli, scale) = (cfg!(feature = "musli"), cfg!(feature = "scale"));
+    let first = path
+        .segments
+        .first()
+        .map(|segment| segment.ident.to_string());
+
+    if path.segments.len() == 1 && path.leading_colon.is_none() {
+        return (musli && !scale, scale && !musli);
+    }
 
-impl ExtraValidBits {
-    fn from_meta(meta: &Meta) -> syn::Result<Self> {
-        match meta {
-            Meta::NameValue(m) => {
-                if !m.path.is_ident("extra_valid_bits") {
-                    return Err(Error::new(
-                        m.span(),
-                        "not a valid `extra_valid_bits` attribute",
-                    ));
-                }
+    match first.as_deref() {
+        Some("musli") => (musli, false),
+        Some("parity_scale_codec" | "codec") => (false, scale),
+        _ => (false, false),
+    }
+}
 
-                Ok(Self(m.value.clone()))
+/// Convert a `CamelCase` type name to `snake_case`.
+pub fn to_snake_case(name: &str) -> String {
+    let mut snake = String::with_capacity(name.len() + 4);
+    let mut chars = name.chars().peekable();
+    let mut prev_lower = false;
+
+    while let Some(c) = chars.next() {
+        if c.is_uppercase() {
+            let next_lower = chars.peek().is_some_and(|next| next.is_lowercase());
+
+            // Split on `aB` and on the last capital of an acronym like `ABc`
+            if !snake.is_empty() && !snake.ends_with('_') && (prev_lower || next_lower) {
+                snake.push('_');
             }
-            _ => Err(Error::new(
-                meta.span(),
-                "extra_valid_bits must follow the syntax `extra_valid_bits = <expr>`",
-            )),
+
+            snake.extend(c.to_lowercase());
+            prev_lower = false;
+        } else {
+            snake.push(c);
+            prev_lower = c.is_lowercase() || c.is_ascii_digit();
         }
     }
+
+    snake
 }
 
-impl Parse for ExtraValidBits {
-    fn parse(input: syn::parse::ParseStream) -> syn::Result<Self> {
-        let meta: MetaNameValue = input.parse()?;
+/// Find the composite flags with bits not covered by any single-bit flag.
+///
+/// The check is only done when the value of every flag can be evaluated at macro expansion time,
+/// that is, integer literals and other flags combined with operators. Otherwise a flag that can't
+/// be evaluated may be the single-bit flag covering the bits.
+fn find_stray_bits<'a>(
+    ty: &Path,
+    variants: impl IntoIterator<Item = &'a syn::Variant>,
+    options: &[FlagOptions],
+) -> Vec<(&'a Ident, u128)> {
+    let Some(width) = ty
+        .get_ident()
+        .and_then(|ident| bits_width(&ident.to_string()))
+    else {
+        return Vec::new();
+    };
+    let mask = if width == 128 {
+        u128::MAX
+    } else {
+        (1 << width) - 1
+    };
+
+    // Flags under `cfg` may not exist, so they are not evaluated
+    let variants: Vec<_> = variants
+        .into_iter()
+        .zip(options)
+        .filter(|(variant, _)| !variant.attrs.iter().any(|attr| attr.path().is_ident("cfg")))
+        .collect();
+
+    let values = eval_flags(variants.iter().map(|(variant, _)| *variant), mask);
+    if values.len() != variants.len() {
+        return Vec::new();
+    }
 
-        if !meta.path.is_ident("extra_valid_bits") {
-            return Err(Error::new(meta.span(), "not a extra_valid_bits attribute"));
+    let covered = values
+        .iter()
+        .filter(|(_, value)| value.count_ones() == 1)
+        .fold(0, |covered, (_, value)| covered | value);
+
+    variants
+        .iter()
+        .filter(|(_, options)| !options.allow_stray_bits)
+        .filter_map(|(variant, _)| {
+            let (_, value) = values.iter().find(|(name, _)| *name == &variant.ident)?;
+            let stray = value & !covered;
+
+            (value.count_ones() > 1 && stray != 0).then_some((&variant.ident, stray))
+        })
+        .collect()
+}
+
+/// Evaluate the values of the flags made of integer literals and other flags combined with
+/// operators, in the order of the flags. The other flags are left out.
+fn eval_flags<'a>(
+    variants: impl IntoIterator<Item = &'a syn::Variant>,
+    mask: u128,
+) -> Vec<(&'a Ident, u128)> {
+    let variants: Vec<_> = variants.into_iter().collect();
+
+    // Flags can refer to flags defined after them, so evaluate until no progress is made
+    let mut values: Vec<(&Ident, u128)> = Vec::with_capacity(variants.len());
+    loop {
+        let evaluated = values.len();
+
+        for variant in &variants {
+            if values.iter().any(|(name, _)| *name == &variant.ident) {
+                continue;
+            }
+            let Some((_, expr)) = &variant.discriminant else {
+                continue;
+            };
+            if let Some(value) = eval_flag(expr, &values, mask) {
+                values.push((&variant.ident, value));
+            }
         }
 
-        Ok(Self(meta.value))
+        if values.len() == evaluated {
+            break;
+        }
+    }
+
+    // Keep the order of the flags
+    variants
+        .iter()
+        .filter_map(|variant| values.iter().find(|(name, _)| *name == &variant.ident))
+        .copied()
+        .collect()
+}
+
+/// Evaluate a flag expression made of integer literals, other flags and operators.
+fn eval_flag(expr: &Expr, values: &[(&Ident, u128)], mask: u128) -> Option<u128> {
+    let value = match expr {
+        Expr::Lit(syn::ExprLit {
+            lit: syn::Lit::Int(lit),
+            ..
+        }) => lit.base10_parse::<u128>().ok()?,
+        Expr::Path(path) => {
+            let ident = path.path.get_ident()?;
+            values.iter().find(|(name, _)| *name == ident)?.1
+        }
+        Expr::Paren(paren) => eval_flag(&paren.expr, values, mask)?,
+        Expr::Group(group) => eval_flag(&group.expr, values, mask)?,
+        Expr::Cast(cast) => eval_flag(&cast.expr, values, mask)?,
+        Expr::Unary(unary) => {
+            let value = eval_flag(&unary.expr, values, mask)?;
+            match unary.op {
+                syn::UnOp::Not(_) => !value,
+                syn::UnOp::Neg(_) => value.wrapping_neg(),
+                _ => return None,
+            }
+        }
+        Expr::Binary(binary) => {
+            let left = eval_flag(&binary.left, values, mask)?;
+            let right = eval_flag(&binary.right, values, mask)?;
+            match binary.op {
+                syn::BinOp::BitOr(_) => left | right,
+                syn::BinOp::BitAnd(_) => left & right,
+                syn::BinOp::BitXor(_) => left ^ right,
+                syn::BinOp::Shl(_) => left.checked_shl(u32::try_from(right).ok()?)?,
+                syn::BinOp::Shr(_) => left.checked_shr(u32::try_from(right).ok()?)?,
+                syn::BinOp::Add(_) => left.wrapping_add(right),
+                syn::BinOp::Sub(_) => left.wrapping_sub(right),
+                _ => return None,
+            }
+        }
+        _ => return None,
+    };
+
+    Some(value & mask)
+}
+
+/// The value with all the bits of the `width` set, or all bits of `ty` without a width.
+fn width_mask(ty: &Path, width: Option<&LitInt>) -> TokenStream {
+    match width {
+        // The shift overflows when the width is the whole bits type
+        Some(width) => quote! {
+            !match (!(0 as #ty)).checked_shl(#width) {
+                ::core::option::Option::Some(high) => high,
+                ::core::option::Option::None => 0,
+            }
+        },
+        None => quote!(!0),
+    }
+}
+
+/// The width in bits of a bits type, if it's the same on all platforms.
+fn bits_width(ty: &str) -> Option<u32> {
+    match ty {
+        "i8" | "u8" | "c_char" | "c_schar" | "c_uchar" => Some(8),
+        "i16" | "u16" | "c_short" | "c_ushort" => Some(16),
+        "i32" | "u32" | "c_int" | "c_uint" => Some(32),
+        "i64" | "u64" | "c_longlong" | "c_ulonglong" => Some(64),
+        "i128" | "u128" => Some(128),
+        _ => None,
     }
 }
 
+/// The number of bits of a primitive bits type, also given by a path like `core::primitive::u8`.
+/// The aliases of the `custom-types` feature can't be resolved by the macro.
+fn ty_bits_width(ty: &Path) -> Option<u32> {
+    ty.segments
+        .last()
+        .and_then(|segment| bits_width(&segment.ident.to_string()))
+}
+
 /// Recursively check if a expression can be simplified to a simple wrap of `Self(<expr>)`.
 ///
 /// Logic behind this:
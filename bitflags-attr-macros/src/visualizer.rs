@@ -0,0 +1,250 @@
+use std::{fmt::Write, path::PathBuf};
+
+use proc_macro2::TokenStream;
+use quote::quote;
+use syn::{Error, Ident, LitStr};
+
+use crate::typed::to_snake_case;
+
+/// The debugger visualizers of a flags type, set with the `debugger_visualizer` option.
+pub struct Visualizer {
+    /// The directory the visualizers are written to, relative to the crate root.
+    pub dir: LitStr,
+    /// The module of the type from the crate root, set with the `debugger_module` option.
+    pub module: Option<LitStr>,
+    /// The number of bits of the flags.
+    pub bits: u32,
+    /// The names and values of the flags, in the order they are formatted.
+    pub flags: Vec<(String, u128)>,
+}
+
+impl Visualizer {
+    fn file(&self, name: &Ident, suffix: &str) -> PathBuf {
+        let manifest_dir = std::env::var("CARGO_MANIFEST_DIR").unwrap_or_default();
+        PathBuf::from(manifest_dir)
+            .join(self.dir.value())
+            .join(format!("{name}{suffix}"))
+    }
+
+    /// The module embedding the natvis file and the GDB script in the debug information.
+    pub fn module(&self, name: &Ident) -> TokenStream {
+        let module = Ident::new(
+            &format!("__{}_debugger_visualizer", to_snake_case(&name.to_string())),
+            name.span(),
+        );
+        let natvis = self.file(name, ".natvis").display().to_string();
+        let gdb = self.file(name, "_gdb.py").display().to_string();
+
+        quote! {
+            #[doc(hidden)]
+            #[debugger_visualizer(natvis_file = #natvis)]
+            #[debugger_visualizer(gdb_script_file = #gdb)]
+            mod #module {}
+        }
+    }
+
+    /// Write the natvis file, the GDB script and the LLDB script of the flags type `name`.
+    pub fn write(&self, name: &Ident) -> syn::Result<()> {
+        let files = [
+            (self.file(name, ".natvis"), self.natvis(name)),
+            (self.file(name, "_gdb.py"), self.gdb_script(name)),
+            (self.file(name, "_lldb.py"), self.lldb_script(name)),
+        ];
+
+        for (file, source) in files {
+            file.parent()
+                .map_or(Ok(()), std::fs::create_dir_all)
+                .and_then(|()| std::fs::write(&file, source))
+                .map_err(|err| {
+                    Error::new_spanned(
+                        &self.dir,
+                        format_args!(
+                            "failed to write the debugger visualizer `{}`: {err}",
+                            file.display()
+                        ),
+                    )
+                })?;
+        }
+
+        Ok(())
+    }
+
+    fn mask(&self) -> u128 {
+        if self.bits == 128 {
+            u128::MAX
+        } else {
+            (1 << self.bits) - 1
+        }
+    }
+
+    /// The natvis file, for the debuggers of Visual Studio and WinDbg.
+    ///
+    /// The expressions follow `parser::to_writer`: a flag is written if it's contained and has
+    /// bits not covered by the contained flags before it, which the `covered{i}` intrinsics are.
+    fn natvis(&self, name: &Ident) -> String {
+        let mask = self.mask();
+        let len = self.flags.len();
+        let contained = |flag: u128| format!("(__0 &amp; {flag:#X}) == {flag:#X}");
+
+        let crate_name = std::env::var("CARGO_CRATE_NAME").unwrap_or_default();
+        let type_name = [
+            Some(crate_name),
+            self.module.as_ref().map(LitStr::value),
+            Some(name.to_string()),
+        ]
+        .into_iter()
+        .flatten()
+        .filter(|segment| !segment.is_empty())
+        .collect::<Vec<_>>()
+        .join("::");
+
+        let mut names = String::new();
+        for (i, (text, flag)) in self.flags.iter().enumerate() {
+            let _ = write!(
+                names,
+                r#"{{({} &amp;&amp; ({flag:#X} &amp; ~covered{i}()) != 0) ? (covered{i}() != 0 ? " | {text}" : "{text}") : "",sb}}"#,
+                contained(*flag),
+            );
+        }
+
+        let mut source = String::new();
+        let _ = writeln!(source, r#"<?xml version="1.0" encoding="utf-8"?>"#);
+        let _ = writeln!(
+            source,
+            "<!-- Visualizer generated by `#[bitflag]` for `{name}` -->"
+        );
+        let _ = writeln!(
+            source,
+            r#"<AutoVisualizer xmlns="http://schemas.microsoft.com/vstudio/debugger/natvis/2010">"#
+        );
+        let _ = writeln!(source, r#"  <Type Name="{type_name}">"#);
+        let _ = writeln!(
+            source,
+            r#"    <Intrinsic Name="covered0" Expression="0" />"#
+        );
+        for (i, (_, flag)) in self.flags.iter().enumerate() {
+            let _ = writeln!(
+                source,
+                r#"    <Intrinsic Name="covered{}" Expression="covered{i}() | ({} ? {flag:#X} : 0)" />"#,
+                i + 1,
+                contained(*flag),
+            );
+        }
+        let _ = writeln!(
+            source,
+            r#"    <Intrinsic Name="remaining" Expression="__0 &amp; {mask:#X} &amp; ~covered{len}()" />"#
+        );
+        let _ = writeln!(
+            source,
+            r#"    <DisplayString Condition="(__0 &amp; {mask:#X}) == 0">0x0</DisplayString>"#
+        );
+        let _ = writeln!(
+            source,
+            r#"    <DisplayString Condition="remaining() != 0">{names}{{covered{len}() != 0 ? " | " : "",sb}}{{remaining(),X}}</DisplayString>"#
+        );
+        let _ = writeln!(source, "    <DisplayString>{names}</DisplayString>");
+        let _ = writeln!(source, "    <Expand>");
+        let _ = writeln!(source, r#"      <Item Name="[bits]">__0</Item>"#);
+        for (text, flag) in &self.flags {
+            let _ = writeln!(
+                source,
+                r#"      <Item Name="{text}">{}</Item>"#,
+                contained(*flag)
+            );
+        }
+        let _ = writeln!(source, "    </Expand>");
+        let _ = writeln!(source, "  </Type>");
+        let _ = writeln!(source, "</AutoVisualizer>");
+
+        source
+    }
+
+    /// The Python function formatting the bits like `parser::to_writer`, shared by the scripts.
+    fn python_to_text(&self, name: &Ident, imports: &str) -> String {
+        let mut source = format!("# Pretty-printer generated by `#[bitflag]` for `{name}`\n\n");
+        if !imports.is_empty() {
+            let _ = writeln!(source, "{imports}");
+        }
+        let _ = writeln!(source, "BITS = {}", self.bits);
+        let _ = writeln!(source, "FLAGS = [");
+        for (text, flag) in &self.flags {
+            let _ = writeln!(source, "    ({text:?}, {flag:#X}),");
+        }
+        source.push_str(
+            r#"]
+
+
+def to_text(bits):
+    """Format the bits like `parser::to_writer`, or as `0x0` if empty."""
+    bits &= (1 << BITS) - 1
+    if bits == 0:
+        return "0x0"
+
+    names = []
+    covered = 0
+    for name, flag in FLAGS:
+        if bits & flag == flag:
+            # Flags fully covered by the flags before them are not written
+            if flag & ~covered:
+                names.append(name)
+            covered |= flag
+
+    remaining = bits & ~covered
+    if remaining:
+        names.append("0x%X" % remaining)
+
+    return " | ".join(names)
+"#,
+        );
+
+        source
+    }
+
+    /// The GDB pretty-printer, loaded from the debug information.
+    fn gdb_script(&self, name: &Ident) -> String {
+        let mut source = self.python_to_text(name, "import re\n\nimport gdb\n");
+        let _ = write!(
+            source,
+            r#"
+
+class {name}Printer:
+    def __init__(self, value):
+        self.value = value
+
+    def to_string(self):
+        return to_text(int(self.value["__0"]))
+
+
+def lookup(value):
+    tag = value.type.strip_typedefs().unqualified().tag
+    if tag is not None and re.match(r"^(.*::)?{name}$", tag):
+        return {name}Printer(value)
+    return None
+
+
+gdb.pretty_printers.append(lookup)
+"#
+        );
+
+        source
+    }
+
+    /// The LLDB summary provider, loaded with `command script import`.
+    fn lldb_script(&self, name: &Ident) -> String {
+        let mut source = self.python_to_text(name, "");
+        let _ = write!(
+            source,
+            r#"
+
+def summary(value, internal_dict):
+    return to_text(value.GetChildAtIndex(0).GetValueAsUnsigned())
+
+
+def __lldb_init_module(debugger, internal_dict):
+    debugger.HandleCommand('type summary add -x "^(.*::)?{name}$" -F %s.summary' % __name__)
+"#
+        );
+
+        source
+    }
+}
@@ -14,6 +14,7 @@ pub enum ExampleFlags {
     Flag5 = CONST1 | CONST2 | 3,
     Flag6 = Flag1 | Flag2,
     Flag7 = CONST1 | Flag1,
+    #[flag(allow_stray_bits)]
     Flag8 = (1 << 1) | (1 << 4),
     Flag9 = 1u8 as u32,
 }
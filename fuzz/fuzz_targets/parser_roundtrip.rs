@@ -0,0 +1,20 @@
+#![no_main]
+
+use bitflag_attr::bitflag;
+use libfuzzer_sys::fuzz_target;
+
+#[bitflag(u32)]
+#[derive(Clone, Copy)]
+enum Flags {
+    A = 1 << 0,
+    B = 1 << 1,
+    C = 1 << 2,
+    AB = A | B,
+    High = 1 << 31,
+    #[flag(zero)]
+    None = 0,
+}
+
+fuzz_target!(|data: &[u8]| {
+    bitflag_attr::fuzzing::fuzz_parser_roundtrip::<Flags>(data);
+});
@@ -0,0 +1,17 @@
+#![no_main]
+
+use bitflag_attr::bitflag;
+use libfuzzer_sys::fuzz_target;
+
+#[bitflag(i8)]
+#[non_exhaustive]
+#[derive(Clone, Copy)]
+enum Flags {
+    A = 1 << 0,
+    B = 1 << 1,
+    Sign = i8::MIN,
+}
+
+fuzz_target!(|data: &[u8]| {
+    bitflag_attr::fuzzing::fuzz_parser_roundtrip::<Flags>(data);
+});
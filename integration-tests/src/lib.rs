@@ -0,0 +1,2 @@
+//! Tests of the implementations generated for other crates, in the `tests` directory, each one
+//! enabled by the feature of the same name.
@@ -0,0 +1,103 @@
+#![cfg(feature = "bson")]
+
+use bitflag_attr::bitflag;
+use bson::Bson;
+
+#[bitflag(u8, bson, deserialize_unknown_bits = deny)]
+#[derive(Debug, Clone, Copy, PartialEq)]
+enum Permissions {
+    Read = 1,
+    Write = 1 << 1,
+}
+
+#[bitflag(u64, bson = int)]
+#[derive(Debug, Clone, Copy, PartialEq)]
+enum Wide {
+    Low = 1,
+    High = 1 << 63,
+}
+
+#[bitflag(u8, bson = text)]
+#[derive(Debug, Clone, Copy, PartialEq)]
+enum Named {
+    Read = 1,
+    Write = 1 << 1,
+}
+
+#[test]
+fn int_is_bits() {
+    assert_eq!(Bson::from(Permissions::all()), Bson::Int32(0b11));
+    assert_eq!(
+        Permissions::try_from(Bson::Int32(0b11)).unwrap(),
+        Permissions::all()
+    );
+    assert_eq!(
+        Permissions::try_from(Bson::Int64(0b01)).unwrap(),
+        Permissions::Read
+    );
+    assert!(Permissions::try_from(Bson::Int32(0x100)).is_err());
+    assert!(Permissions::try_from(Bson::Boolean(true)).is_err());
+}
+
+#[test]
+fn wide_bits_wrap_around() {
+    let bson = Bson::from(Wide::all());
+    assert_eq!(bson, Bson::Int64(i64::MIN | 1));
+    assert_eq!(Wide::try_from(bson).unwrap(), Wide::all());
+
+    let value = Wide::from_bits_retain(0xF0E1_D2C3_B4A5_9687);
+    assert!(value.bits() > i64::MAX as u64);
+    let back = Wide::try_from(Bson::from(value)).unwrap();
+    assert_eq!(back.bits().to_le_bytes(), value.bits().to_le_bytes());
+}
+
+#[test]
+fn text_is_flag_names() {
+    assert_eq!(
+        Bson::from(Named::all()),
+        Bson::String(String::from("Read | Write"))
+    );
+    assert_eq!(
+        Named::try_from(Bson::String(String::from("Read | Write"))).unwrap(),
+        Named::all()
+    );
+    assert_eq!(Named::try_from(Bson::Int32(0b10)).unwrap(), Named::Write);
+    assert!(Named::try_from(Bson::String(String::from("Execute"))).is_err());
+}
+
+#[bitflag(u8, bson = text, deserialize_unknown_bits = truncate, validate = requires_read)]
+#[derive(Debug, Clone, Copy, PartialEq)]
+enum Validated {
+    Read = 1,
+    Write = 1 << 1,
+}
+
+// The unknown bits are invalid, so the text with unknown bits only passes once they are truncated
+const fn requires_read(flags: &Validated) -> Result<(), &'static str> {
+    if flags.bits() & !0b11 != 0 {
+        Err("unknown bits set")
+    } else if flags.bits() == 0b10 {
+        Err("`Write` requires `Read`")
+    } else {
+        Ok(())
+    }
+}
+
+#[test]
+fn try_from_validates_after_truncating() {
+    assert_eq!(
+        Validated::try_from(Bson::String(String::from("Read | 0x80"))).unwrap(),
+        Validated::Read
+    );
+    assert_eq!(
+        Validated::try_from(Bson::Int32(0x81)).unwrap(),
+        Validated::Read
+    );
+    assert!(Validated::try_from(Bson::String(String::from("Write"))).is_err());
+}
+
+#[test]
+fn try_from_applies_unknown_bits_policy() {
+    assert!(Permissions::try_from(Bson::Int32(0x81)).is_err());
+    assert!(Permissions::try_from(Bson::String(String::from("Read | 0x80"))).is_err());
+}
@@ -0,0 +1,60 @@
+#![cfg(feature = "godot")]
+
+use bitflag_attr::bitflag;
+use godot::register::property::{Export, Var};
+
+#[bitflag(u32, deserialize_unknown_bits = deny, godot)]
+#[derive(Debug, Clone, Copy, PartialEq)]
+enum Layers {
+    Ground = 1,
+    Water = 1 << 1,
+}
+
+#[test]
+fn property_is_bits() {
+    let mut layers = Layers::Ground;
+    assert_eq!(layers.get_property(), 1);
+
+    layers.set_property(0b11);
+    assert_eq!(layers, Layers::all());
+}
+
+#[test]
+fn property_ignores_denied_bits() {
+    let mut layers = Layers::Ground;
+    layers.set_property(0x81);
+    assert_eq!(layers, Layers::Ground);
+}
+
+#[test]
+fn property_ignores_out_of_range_values() {
+    let mut layers = Layers::Ground;
+    layers.set_property(1 << 32 | 0b11);
+    assert_eq!(layers, Layers::Ground);
+
+    layers.set_property(-1);
+    assert_eq!(layers, Layers::Ground);
+}
+
+#[bitflag(u64, godot)]
+#[derive(Debug, Clone, Copy, PartialEq)]
+enum Wide {
+    Low = 1,
+    High = 1 << 63,
+}
+
+#[test]
+fn wide_property_wraps_around() {
+    let mut wide = Wide::Low;
+    assert_eq!(Wide::all().get_property(), i64::MIN | 1);
+
+    wide.set_property(i64::MIN | 1);
+    assert_eq!(wide, Wide::all());
+}
+
+// The hints are Godot strings, which need a running engine, so they are only compiled
+#[allow(dead_code)]
+fn hints_are_implemented() {
+    let _ = Layers::var_hint();
+    let _ = Layers::export_hint();
+}
@@ -0,0 +1,52 @@
+#![cfg(feature = "juniper")]
+
+use bitflag_attr::bitflag;
+use juniper::{DefaultScalarValue, FromInputValue, InputValue, ToInputValue};
+
+#[bitflag(u8, juniper)]
+#[derive(Debug, Clone, Copy, PartialEq)]
+enum Permissions {
+    Read = 1,
+    Write = 1 << 1,
+}
+
+#[bitflag(u8, juniper, no_from_str, from_str = strict)]
+#[derive(Debug, Clone, Copy, PartialEq)]
+enum StrictPermissions {
+    Read = 1,
+    Write = 1 << 1,
+}
+
+#[test]
+fn to_input_value_is_text() {
+    let input: InputValue<DefaultScalarValue> = Permissions::all().to_input_value();
+    assert_eq!(input.as_string_value(), Some("Read | Write"));
+}
+
+#[test]
+fn from_input_value_parses_text() {
+    let input = InputValue::<DefaultScalarValue>::scalar(String::from("Read | Write"));
+    assert_eq!(
+        Permissions::from_input_value(&input).unwrap(),
+        Permissions::all()
+    );
+
+    let input = InputValue::<DefaultScalarValue>::scalar(String::from("Execute"));
+    assert!(Permissions::from_input_value(&input).is_err());
+
+    let input = InputValue::<DefaultScalarValue>::scalar(1);
+    let error = Permissions::from_input_value(&input).unwrap_err();
+    assert!(error.starts_with("expected a `String` of flags"), "{error}");
+}
+
+#[test]
+fn from_input_value_uses_the_from_str_parser() {
+    let input = InputValue::<DefaultScalarValue>::scalar(String::from("Read | Write"));
+    assert_eq!(
+        StrictPermissions::from_input_value(&input).unwrap(),
+        StrictPermissions::all()
+    );
+
+    let input = InputValue::<DefaultScalarValue>::scalar(String::from("Read | 0x2"));
+    assert!(StrictPermissions::from_input_value(&input).is_err());
+}
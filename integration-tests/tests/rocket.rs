@@ -0,0 +1,49 @@
+#![cfg(feature = "rocket")]
+
+use bitflag_attr::bitflag;
+use rocket::form::{FromFormField, ValueField};
+use rocket::request::FromParam;
+
+#[bitflag(u8, rocket)]
+#[derive(Debug, Clone, Copy, PartialEq)]
+enum Permissions {
+    Read = 1,
+    Write = 1 << 1,
+}
+
+#[bitflag(u8, rocket, from_str = strict)]
+#[derive(Debug, Clone, Copy, PartialEq)]
+enum StrictPermissions {
+    Read = 1,
+    Write = 1 << 1,
+}
+
+#[test]
+fn from_param_parses_text() {
+    assert_eq!(
+        Permissions::from_param("Read | Write").unwrap(),
+        Permissions::all()
+    );
+    assert!(Permissions::from_param("Execute").is_err());
+}
+
+#[test]
+fn from_form_field_parses_text() {
+    let field = ValueField::from_value("Read | Write");
+    assert_eq!(Permissions::from_value(field).unwrap(), Permissions::all());
+
+    let field = ValueField::from_value("Execute");
+    assert!(Permissions::from_value(field).is_err());
+}
+
+#[test]
+fn from_str_parser_is_used() {
+    assert_eq!(
+        StrictPermissions::from_param("Read | Write").unwrap(),
+        StrictPermissions::all()
+    );
+    assert!(StrictPermissions::from_param("Read | 0x80").is_err());
+
+    let field = ValueField::from_value("0x80");
+    assert!(StrictPermissions::from_value(field).is_err());
+}
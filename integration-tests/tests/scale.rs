@@ -0,0 +1,48 @@
+#![cfg(feature = "scale")]
+
+use bitflag_attr::bitflag;
+use parity_scale_codec::{Compact, Decode, Encode};
+use scale_info::TypeInfo;
+
+#[bitflag(u32, deserialize_unknown_bits = deny)]
+#[derive(Debug, Clone, Copy, PartialEq, Encode, Decode, scale_info::TypeInfo)]
+enum Permissions {
+    Read = 1,
+    Write = 1 << 1,
+}
+
+#[bitflag(u8, strict)]
+#[derive(Debug, Clone, Copy, PartialEq, Encode, Decode)]
+enum StrictFlags {
+    A = 1,
+    B = 1 << 1,
+}
+
+#[test]
+fn encodes_compact_bits() {
+    let flags = Permissions::Read | Permissions::Write;
+    let bytes = flags.encode();
+
+    assert_eq!(bytes, Compact(0b11_u32).encode());
+    assert_eq!(Permissions::decode(&mut &bytes[..]).unwrap(), flags);
+}
+
+#[test]
+fn decode_applies_unknown_bits_policy() {
+    let bytes = Compact(0x81_u32).encode();
+    assert!(Permissions::decode(&mut &bytes[..]).is_err());
+
+    let bytes = Compact(0x83_u8).encode();
+    assert_eq!(
+        StrictFlags::decode(&mut &bytes[..]).unwrap(),
+        StrictFlags::all()
+    );
+}
+
+#[test]
+fn type_info_lists_flags() {
+    let info = Permissions::type_info();
+
+    assert_eq!(info.path.segments.last(), Some(&"Permissions"));
+    assert_eq!(info.docs, ["Flag `Read`", "Flag `Write`"]);
+}
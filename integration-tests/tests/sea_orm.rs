@@ -0,0 +1,34 @@
+#![cfg(feature = "sea-orm")]
+
+use bitflag_attr::bitflag;
+use sea_orm::sea_query::{ColumnType, Nullable, ValueType};
+use sea_orm::Value;
+
+#[bitflag(u32, sea_orm, deserialize_unknown_bits = deny)]
+#[derive(Debug, Clone, Copy, PartialEq)]
+enum Permissions {
+    Read = 1,
+    Write = 1 << 1,
+}
+
+#[test]
+fn value_is_bits() {
+    assert_eq!(Value::from(Permissions::all()), Value::Unsigned(Some(0b11)));
+    assert_eq!(Permissions::null(), Value::Unsigned(None));
+}
+
+#[test]
+fn value_type_applies_unknown_bits_policy() {
+    assert_eq!(
+        <Permissions as ValueType>::try_from(Value::Unsigned(Some(0b11))).unwrap(),
+        Permissions::all()
+    );
+    assert!(<Permissions as ValueType>::try_from(Value::Unsigned(Some(0x81))).is_err());
+    assert!(<Permissions as ValueType>::try_from(Value::Unsigned(None)).is_err());
+}
+
+#[test]
+fn column_is_bits_column() {
+    assert_eq!(Permissions::type_name(), "Permissions");
+    assert_eq!(Permissions::column_type(), ColumnType::Unsigned);
+}
@@ -0,0 +1,22 @@
+#![cfg(feature = "strum")]
+
+use bitflag_attr::bitflag;
+use strum::{EnumCount, VariantNames};
+
+#[bitflag(u8, strip_prefix = "PERM_", strum)]
+#[derive(Debug, Clone, Copy, PartialEq)]
+enum Permissions {
+    PERM_Read = 1,
+    PERM_Write = 1 << 1,
+    PERM_ReadWrite = PERM_Read | PERM_Write,
+}
+
+#[test]
+fn variant_names_are_flag_names() {
+    assert_eq!(Permissions::VARIANTS, ["Read", "Write", "ReadWrite"]);
+}
+
+#[test]
+fn count_is_number_of_flags() {
+    assert_eq!(Permissions::COUNT, 3);
+}
@@ -0,0 +1,88 @@
+//! Build flags values from flag names known at runtime.
+
+use crate::{parser::ParseError, Flags};
+
+/// A builder of flags values from flag names.
+///
+/// This is useful to build flags values from dynamic sources like configuration files, where the
+/// flag names are only known at runtime. Every method setting a flag fails with a [`ParseError`]
+/// if the name doesn't correspond to any defined flag.
+///
+/// ```
+/// use bitflag_attr::{bitflag, FlagsBuilder};
+///
+/// #[bitflag(u8)]
+/// #[derive(Debug, Clone, Copy, PartialEq)]
+/// enum Flags {
+///     A = 1,
+///     B = 1 << 1,
+///     C = 1 << 2,
+/// }
+///
+/// # fn main() -> Result<(), bitflag_attr::parser::ParseError> {
+/// let enable_b = true;
+///
+/// let flags = FlagsBuilder::<Flags>::new()
+///     .set("A")?
+///     .set_value("B", enable_b)?
+///     .build();
+/// assert_eq!(flags, Flags::A | Flags::B);
+///
+/// assert!(FlagsBuilder::<Flags>::new().set("D").is_err());
+/// # Ok(())
+/// # }
+/// ```
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub struct FlagsBuilder<F> {
+    flags: F,
+}
+
+impl<F: Flags> FlagsBuilder<F> {
+    /// Start building from an empty flags value.
+    #[inline]
+    pub fn new() -> Self {
+        Self { flags: F::empty() }
+    }
+
+    /// Start building from `flags`.
+    #[inline]
+    pub fn from_flags(flags: F) -> Self {
+        Self { flags }
+    }
+
+    /// Set the flag named `name`.
+    pub fn set(self, name: &str) -> Result<Self, ParseError> {
+        self.set_value(name, true)
+    }
+
+    /// Unset the flag named `name`.
+    pub fn unset(self, name: &str) -> Result<Self, ParseError> {
+        self.set_value(name, false)
+    }
+
+    /// Set the flag named `name` if `value` is `true`, or unset it otherwise.
+    pub fn set_value(mut self, name: &str, value: bool) -> Result<Self, ParseError> {
+        let flag = F::from_name(name).ok_or_else(|| ParseError::invalid_named_flag(name))?;
+
+        if value {
+            self.flags.set(flag);
+        } else {
+            self.flags.unset(flag);
+        }
+
+        Ok(self)
+    }
+
+    /// Return the built flags value.
+    #[inline]
+    pub fn build(self) -> F {
+        self.flags
+    }
+}
+
+impl<F: Flags> Default for FlagsBuilder<F> {
+    #[inline]
+    fn default() -> Self {
+        Self::new()
+    }
+}
@@ -13,7 +13,7 @@ where
 #[allow(non_upper_case_globals)]
 impl ExampleFlags {
     #[doc(hidden)]
-    #[allow(clippy::unused_unit)]
+    #[allow(clippy::unused_unit, non_camel_case_types)]
     const __OG: () = {
         {
             #[doc = " A example bitflag"]
@@ -56,7 +56,7 @@ impl ExampleFlags {
         const Flag8: u32 = (1 << 1) | (1 << 4);
         #[allow(non_upper_case_globals, dead_code, unused)]
         const Flag9: u32 = 1u8 as u32;
-        Self(Flag1 | Flag2)
+        Self(Flag6)
     };
     pub const Flag7: Self = {
         #[allow(non_upper_case_globals, dead_code, unused)]
@@ -77,7 +77,7 @@ impl ExampleFlags {
         const Flag8: u32 = (1 << 1) | (1 << 4);
         #[allow(non_upper_case_globals, dead_code, unused)]
         const Flag9: u32 = 1u8 as u32;
-        Self(CONST1 | Flag1)
+        Self(Flag7)
     };
     pub const Flag8: Self = Self((1 << 1) | (1 << 4));
     pub const Flag9: Self = Self(1u8 as u32);
@@ -89,6 +89,18 @@ impl ExampleFlags {
     pub const fn bits(&self) -> u32 {
         self.0
     }
+    #[doc = r" Return a mutable reference to the underlying bits, so a C function filling a flags"]
+    #[doc = r" out-parameter can write to the flags value directly."]
+    #[inline]
+    pub fn as_bits_mut(&mut self) -> &mut u32 {
+        &mut self.0
+    }
+    #[doc = r" Return the underlying bits of this bitflag as the unsigned type with the same"]
+    #[doc = r" size, like `0x8000_0000_u32` instead of `i32::MIN` for `i32`."]
+    #[inline]
+    pub const fn bits_unsigned(&self) -> <u32 as crate::BitsPrimitive>::Unsigned {
+        self.0 as <u32 as crate::BitsPrimitive>::Unsigned
+    }
     #[doc = r" Converts from a `bits` value. Returning [`None`] is any unknown bits are set."]
     #[inline]
     pub const fn from_bits(bits: u32) -> Option<Self> {
@@ -102,13 +114,27 @@ impl ExampleFlags {
     #[doc = r" Convert from `bits` value, unsetting any unknown bits."]
     #[inline]
     pub const fn from_bits_truncate(bits: u32) -> Self {
-        Self(bits & Self::all().0)
+        Self(bits & Self::ALL_MASK)
     }
     #[doc = r" Convert from `bits` value exactly."]
     #[inline]
     pub const fn from_bits_retain(bits: u32) -> Self {
         Self(bits)
     }
+    #[doc = r" Convert from `bits` value without checking for unknown bits."]
+    #[doc = r""]
+    #[doc = r" This skips the masking of [`from_bits_truncate`](Self::from_bits_truncate) for"]
+    #[doc = r" values already known to be valid. Debug builds still assert that no unknown bits"]
+    #[doc = r" are set."]
+    #[doc = r""]
+    #[doc = r" # Safety"]
+    #[doc = r""]
+    #[doc = r" `bits` must not have any unknown bits set."]
+    #[inline]
+    pub const unsafe fn from_bits_unchecked(bits: u32) -> Self {
+        ::core::debug_assert!(bits & !Self::ALL_MASK == 0, "unknown bits set");
+        Self(bits)
+    }
     #[doc = r" Convert from a flag `name`."]
     #[inline]
     pub fn from_flag_name(name: &str) -> Option<Self> {
@@ -151,11 +177,9 @@ impl ExampleFlags {
     pub const fn is_all_bits(&self) -> bool {
         self.0 == !0
     }
-    #[doc = r" Construct a flag value with all known flags set."]
-    #[doc = r""]
-    #[doc = r" This will only set the flags specified as associated constant."]
-    #[inline]
-    pub const fn all() -> Self {
+    #[doc = r" The bits of all known flags, computed once for [`all`](Self::all) and the"]
+    #[doc = r" methods truncating unknown bits."]
+    pub const ALL_MASK: u32 = {
         let mut all = 0;
         {
             all |= Self::Flag1.0;
@@ -184,22 +208,47 @@ impl ExampleFlags {
         {
             all |= Self::Flag9.0;
         };
-        Self(all)
+        all
+    };
+    #[doc = r" Construct a flag value with all known flags set."]
+    #[doc = r""]
+    #[doc = r" This will only set the flags specified as associated constant."]
+    #[inline]
+    pub const fn all() -> Self {
+        Self(Self::ALL_MASK)
     }
     #[doc = r" Returns `true` if the flag value contais all known flags."]
     #[inline]
     pub const fn is_all(&self) -> bool {
-        Self::all().0 | self.0 == self.0
+        Self::ALL_MASK | self.0 == self.0
     }
     #[doc = r" Returns `true` if there are any unknown bits set in the flag value."]
     #[inline]
     pub const fn contains_unknown_bits(&self) -> bool {
-        Self::all().0 & self.0 != self.0
+        Self::ALL_MASK & self.0 != self.0
+    }
+    #[doc = r" Returns the index of the lowest bit set in the flag value, or [`None`] if it is empty."]
+    #[inline]
+    pub const fn lowest_bit_index(&self) -> ::core::option::Option<u32> {
+        if self.0 == 0 {
+            ::core::option::Option::None
+        } else {
+            ::core::option::Option::Some(self.0.trailing_zeros())
+        }
+    }
+    #[doc = r" Returns the index of the highest bit set in the flag value, or [`None`] if it is empty."]
+    #[inline]
+    pub const fn highest_bit_index(&self) -> ::core::option::Option<u32> {
+        if self.0 == 0 {
+            ::core::option::Option::None
+        } else {
+            ::core::option::Option::Some(u32::BITS - 1 - self.0.leading_zeros())
+        }
     }
     #[doc = r" Returns a bit flag that only has bits corresponding to the specified flags as associated constant."]
     #[inline]
     pub const fn truncated(&self) -> Self {
-        Self(self.0 & Self::all().0)
+        Self(self.0 & Self::ALL_MASK)
     }
     #[doc = r" Removes unknown bits from the flag value."]
     #[inline]
@@ -295,15 +344,108 @@ impl ExampleFlags {
     pub fn unset(&mut self, other: Self) {
         self.0 = self.difference(other).0
     }
+    #[doc = r" Set the flags in `other` in the value, failing if any of them is already set."]
+    #[doc = r""]
+    #[doc = r" The value is left unchanged if it fails."]
+    #[inline]
+    #[doc(alias = "try_insert")]
+    pub fn try_set(
+        &mut self,
+        other: Self,
+    ) -> ::core::result::Result<(), crate::AlreadySetError<u32>> {
+        let already_set = self.0 & other.0;
+        if already_set != 0 {
+            return ::core::result::Result::Err(crate::AlreadySetError::new(already_set));
+        }
+
+        self.0 = self.or(other).0;
+        ::core::result::Result::Ok(())
+    }
+    #[doc = r" Unset the flags in `other` in the value, failing if any of them is not set."]
+    #[doc = r""]
+    #[doc = r" The value is left unchanged if it fails."]
+    #[inline]
+    #[doc(alias = "try_remove")]
+    pub fn try_unset(
+        &mut self,
+        other: Self,
+    ) -> ::core::result::Result<(), crate::NotSetError<u32>> {
+        let not_set = other.0 & !self.0;
+        if not_set != 0 {
+            return ::core::result::Result::Err(crate::NotSetError::new(not_set));
+        }
+
+        self.0 = self.difference(other).0;
+        ::core::result::Result::Ok(())
+    }
     #[doc = r" Toggle the flags in `other` in the value."]
     #[inline]
     pub fn toggle(&mut self, other: Self) {
         self.0 = self.xor(other).0
     }
+    #[doc = r" Toggle all known flags in the value."]
+    #[doc = r""]
+    #[doc = r" Unknown bits are left unchanged."]
+    #[inline]
+    pub fn toggle_all(&mut self) {
+        self.0 = self.xor(Self::all()).0
+    }
+    #[doc = r" Returns `true` if the bit at `index` is set, counting from the least significant bit."]
+    #[doc = r""]
+    #[doc = r" # Panics"]
+    #[doc = r""]
+    #[doc = r" Panics if `index` is not less than the number of bits of the bits type."]
+    #[inline]
+    pub const fn bit(&self, index: u32) -> bool {
+        ::core::assert!(index < <u32>::BITS, "bit index out of range");
+        self.0 & (1 << index) != 0
+    }
+    #[doc = r" Set the bit at `index` to `value`, counting from the least significant bit."]
+    #[doc = r""]
+    #[doc = r" # Panics"]
+    #[doc = r""]
+    #[doc = r" Panics if `index` is not less than the number of bits of the bits type."]
+    #[inline]
+    pub fn set_bit(&mut self, index: u32, value: bool) {
+        ::core::assert!(index < <u32>::BITS, "bit index out of range");
+        if value {
+            self.0 |= 1 << index;
+        } else {
+            self.0 &= !(1 << index);
+        }
+    }
+    #[doc = " Returns the flags value with the bits rotated left by `n`, wrapping the truncated bits to the end."]
+    #[inline]
+    pub const fn rotate_left(self, n: u32) -> Self {
+        let bits = self.0.rotate_left(n);
+        Self(bits)
+    }
+    #[doc = " Returns the flags value with the bits rotated right by `n`, wrapping the truncated bits to the beginning."]
+    #[inline]
+    pub const fn rotate_right(self, n: u32) -> Self {
+        let bits = self.0.rotate_right(n);
+        Self(bits)
+    }
+    #[doc = " Returns the flags value with the byte order of the bits reversed."]
+    #[inline]
+    pub const fn swap_bytes(self) -> Self {
+        let bits = self.0.swap_bytes();
+        Self(bits)
+    }
+    #[doc = " Returns the flags value with the order of the bits reversed."]
+    #[inline]
+    pub const fn reverse_bits(self) -> Self {
+        let bits = self.0.reverse_bits();
+        Self(bits)
+    }
 }
 #[automatically_derived]
 impl ::core::ops::Not for ExampleFlags {
     type Output = Self;
+    #[doc = r" The complement of the flags value, truncating unknown bits."]
+    #[doc = r""]
+    #[doc = r" This is equivalent to [`complement`](Self::complement), use [`not`](Self::not) for"]
+    #[doc = r" a bitwise NOT keeping unknown bits."]
     #[inline]
     fn not(self) -> Self::Output {
         self.complement()
@@ -386,6 +528,20 @@ impl ::core::convert::From<ExampleFlags> for u32 {
     }
 }
 #[automatically_derived]
+impl ::core::convert::AsRef<u32> for ExampleFlags {
+    #[inline]
+    fn as_ref(&self) -> &u32 {
+        &self.0
+    }
+}
+#[automatically_derived]
+impl ::core::borrow::Borrow<u32> for ExampleFlags {
+    #[inline]
+    fn borrow(&self) -> &u32 {
+        &self.0
+    }
+}
+#[automatically_derived]
 impl ::core::fmt::Binary for ExampleFlags {
     #[inline]
     fn fmt(&self, f: &mut ::core::fmt::Formatter<'_>) -> ::core::fmt::Result {
@@ -429,6 +585,17 @@ impl ::core::fmt::Debug for ExampleFlags {
             fn fmt(&self, f: &mut ::core::fmt::Formatter<'_>) -> ::core::fmt::Result {
                 if self.0.is_empty() {
                     f.write_fmt(core::format_args!("{:#X}", self.0 .0))
+                } else if f.alternate() {
+                    let mut list = f.debug_list();
+                    let mut iter = self.0.iter_names();
+                    for (name, _) in &mut iter {
+                        list.entry(&::core::format_args!("{}", name));
+                    }
+                    let remaining = iter.remaining();
+                    if !remaining.is_empty() {
+                        list.entry(&::core::format_args!("{:#X}", remaining.0));
+                    }
+                    list.finish()
                 } else {
                     crate::parser::to_writer(self.0, f)
                 }
@@ -456,6 +623,48 @@ impl crate::Flags for ExampleFlags {
         ("Flag8", Self::Flag8),
         ("Flag9", Self::Flag9),
     ];
+    const ITER_FLAGS: &'static [(&'static str, ExampleFlags)] = {
+        const KNOWN: &[(&str, ExampleFlags)] = <ExampleFlags as crate::Flags>::KNOWN_FLAGS;
+        const fn yieldable(index: usize) -> bool {
+            let bits = KNOWN[index].1 .0;
+            if bits == 0 {
+                return false;
+            }
+            let mut i = 0;
+            while i < index {
+                if KNOWN[i].1 .0 == bits {
+                    return false;
+                }
+                i += 1;
+            }
+            true
+        }
+        const LEN: usize = {
+            let mut len = 0;
+            let mut i = 0;
+            while i < KNOWN.len() {
+                if yieldable(i) {
+                    len += 1;
+                }
+                i += 1;
+            }
+            len
+        };
+        const TABLE: [(&str, ExampleFlags); LEN] = {
+            let mut table = [("", ExampleFlags(0)); LEN];
+            let mut len = 0;
+            let mut i = 0;
+            while i < KNOWN.len() {
+                if yieldable(i) {
+                    table[len] = KNOWN[i];
+                    len += 1;
+                }
+                i += 1;
+            }
+            table
+        };
+        &TABLE
+    };
     const EXTRA_VALID_BITS: u32 = {
         let mut all = 0;
         {
@@ -496,24 +705,13 @@ impl crate::Flags for ExampleFlags {
     }
 }
 impl ExampleFlags {
-    const KNOWN_FLAGS: &'static [(&'static str, ExampleFlags)] = &[
-        ("Flag1", Self::Flag1),
-        ("Flag2", Self::Flag2),
-        ("Flag3", Self::Flag3),
-        ("Flag4", Self::Flag4),
-        ("Flag5", Self::Flag5),
-        ("Flag6", Self::Flag6),
-        ("Flag7", Self::Flag7),
-        ("Flag8", Self::Flag8),
-        ("Flag9", Self::Flag9),
-    ];
     #[doc = r" Yield a set of contained flags values."]
     #[doc = r""]
     #[doc = r" Each yielded flags value will correspond to a defined named flag. Any unknown bits"]
     #[doc = r" will be yielded together as a final flags value."]
     #[inline]
     pub const fn iter(&self) -> crate::iter::Iter<Self> {
-        crate::iter::Iter::__private_const_new(Self::KNOWN_FLAGS, *self, *self)
+        crate::iter::Iter::__private_const_new(<Self as crate::Flags>::ITER_FLAGS, *self, *self)
     }
     #[doc = r" Yield a set of contained named flags values."]
     #[doc = r""]
@@ -521,8 +719,139 @@ impl ExampleFlags {
     #[doc = r" Any unknown bits, or bits not corresponding to a contained flag will not be yielded."]
     #[inline]
     pub const fn iter_names(&self) -> crate::iter::IterNames<Self> {
-        crate::iter::IterNames::__private_const_new(Self::KNOWN_FLAGS, *self, *self)
+        crate::iter::IterNames::__private_const_new(
+            <Self as crate::Flags>::ITER_FLAGS,
+            *self,
+            *self,
+        )
+    }
+    #[doc = r" Call `f` with each contained named flag, like [`iter_names`](#method.iter_names)"]
+    #[doc = r" but without the state of an iterator, for a smaller code size."]
+    #[inline]
+    pub fn for_each_set(&self, mut f: impl ::core::ops::FnMut(&'static str, Self)) {
+        let flags = <Self as crate::Flags>::ITER_FLAGS;
+        let mut remaining = self.0;
+        let mut i = 0;
+        while i < flags.len() && remaining != 0 {
+            let (name, flag) = flags[i];
+            if self.0 & flag.0 == flag.0 && remaining & flag.0 != 0 {
+                remaining &= !flag.0;
+                f(name, flag);
+            }
+            i += 1;
+        }
     }
+    #[doc = r" Returns `true` if `pred` returns `true` for any contained named flag, as yielded by"]
+    #[doc = r" [`iter_names`](#method.iter_names)."]
+    #[inline]
+    pub fn any_set(&self, mut pred: impl ::core::ops::FnMut(Self) -> bool) -> bool {
+        let flags = <Self as crate::Flags>::ITER_FLAGS;
+        let mut remaining = self.0;
+        let mut i = 0;
+        while i < flags.len() && remaining != 0 {
+            let (_, flag) = flags[i];
+            if self.0 & flag.0 == flag.0 && remaining & flag.0 != 0 {
+                remaining &= !flag.0;
+                if pred(flag) {
+                    return true;
+                }
+            }
+            i += 1;
+        }
+        false
+    }
+    #[doc = r" Returns `true` if `pred` returns `true` for all contained named flags, as yielded"]
+    #[doc = r" by [`iter_names`](#method.iter_names), which is the case for an empty value."]
+    #[inline]
+    pub fn all_set(&self, mut pred: impl ::core::ops::FnMut(Self) -> bool) -> bool {
+        !self.any_set(|flag| !pred(flag))
+    }
+    #[doc = r" Yield each unknown bit set in the flags value as its own flags value."]
+    #[doc = r""]
+    #[doc = r" The bits are yielded from the lowest to the highest."]
+    #[inline]
+    pub const fn iter_unknown_bits(&self) -> crate::iter::IterUnknownBits<Self> {
+        crate::iter::IterUnknownBits::__private_const_new(Self(self.0 & !Self::ALL_MASK))
+    }
+    #[doc = r" Yield the name of every defined flag, with whether the flag is contained in the"]
+    #[doc = r" flags value."]
+    #[inline]
+    pub const fn iter_states(&self) -> crate::iter::IterStates<Self> {
+        crate::iter::IterStates::__private_const_new(<Self as crate::Flags>::KNOWN_FLAGS, *self)
+    }
+    #[doc = r" Yield the name and value of every defined flag not contained in the flags value."]
+    #[doc = r""]
+    #[doc = r" A multi-bit flag is yielded if any of its bits is unset."]
+    #[inline]
+    pub const fn iter_missing(&self) -> crate::iter::IterMissing<Self> {
+        crate::iter::IterMissing::__private_const_new(<Self as crate::Flags>::KNOWN_FLAGS, *self)
+    }
+    #[doc = r" Explain a bits value, with a line for each contained named flag, with the positions of"]
+    #[doc = r" its bits and its documentation, and for each remaining bit."]
+    #[doc = r""]
+    #[doc = r" See [`explain_bits`](crate::parser::explain_bits) for the format."]
+    #[inline]
+    pub fn explain_bits(bits: u32) -> crate::parser::ExplainBits<Self> {
+        crate::parser::explain_bits(bits)
+    }
+    #[doc = r" The name of the single-bit flag of each bit, indexed by the bit."]
+    #[doc = r""]
+    #[doc = r" When several flags have the same bit, the first defined one is used."]
+    pub const NAME_BY_BIT: [::core::option::Option<&'static str>; u32::BITS as usize] = {
+        let known = <Self as crate::Flags>::KNOWN_FLAGS;
+        let mut table = [::core::option::Option::None; u32::BITS as usize];
+        let mut i = 0;
+        while i < known.len() {
+            let (name, flag) = known[i];
+            if flag.0.count_ones() == 1 {
+                let bit = flag.0.trailing_zeros() as usize;
+                if table[bit].is_none() {
+                    table[bit] = ::core::option::Option::Some(name);
+                }
+            }
+            i += 1;
+        }
+        table
+    };
+    #[doc = r" Returns the name of the single-bit flag of the bit at `index`, or [`None`] if"]
+    #[doc = r" there is none."]
+    #[inline]
+    pub const fn name_of_bit(index: u32) -> ::core::option::Option<&'static str> {
+        if index < u32::BITS {
+            Self::NAME_BY_BIT[index as usize]
+        } else {
+            ::core::option::Option::None
+        }
+    }
+    #[doc = r" The length in bytes of the longest flag name."]
+    pub const MAX_NAME_LEN: usize = {
+        let known = <Self as crate::Flags>::KNOWN_FLAGS;
+        let mut max = 0;
+        let mut i = 0;
+        while i < known.len() {
+            if known[i].0.len() > max {
+                max = known[i].0.len();
+            }
+            i += 1;
+        }
+        max
+    };
+    #[doc = r" The worst-case length in bytes of a flags value written as text by"]
+    #[doc = r" [`to_writer`](crate::parser::to_writer), so a buffer of this length"]
+    #[doc = r" always fits the text."]
+    #[doc = r""]
+    #[doc = r" It counts every flag name, the ` | ` separators and the hex number of the bits"]
+    #[doc = r" that aren't part of a flag."]
+    pub const MAX_TEXT_LEN: usize = {
+        let flags = <Self as crate::Flags>::ITER_FLAGS;
+        let mut len = 0;
+        let mut i = 0;
+        while i < flags.len() {
+            len += flags[i].0.len() + 3;
+            i += 1;
+        }
+        len + 2 + u32::BITS as usize / 4
+    };
 }
 #[automatically_derived]
 impl ::core::iter::Extend<ExampleFlags> for ExampleFlags {
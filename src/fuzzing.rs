@@ -0,0 +1,102 @@
+//! Entry points to fuzz the [`parser`] of any flags type.
+//!
+//! The functions in this module take the raw input of a fuzzer and panic if the flags type breaks
+//! an invariant of the [`parser`] module, so they can be called directly from a fuzz target:
+//!
+//! ```ignore
+//! #![no_main]
+//!
+//! use bitflag_attr::bitflag;
+//! use libfuzzer_sys::fuzz_target;
+//!
+//! #[bitflag(u8)]
+//! #[derive(Clone, Copy)]
+//! enum Flags {
+//!     A = 1 << 0,
+//!     B = 1 << 1,
+//! }
+//!
+//! fuzz_target!(|data: &[u8]| {
+//!     bitflag_attr::fuzzing::fuzz_parser_roundtrip::<Flags>(data);
+//! });
+//! ```
+//!
+//! The `fuzz` directory of this repository has ready-made [`cargo-fuzz`] targets.
+//!
+//! [`cargo-fuzz`]: https://github.com/rust-fuzz/cargo-fuzz
+
+use crate::{
+    assert_flags_eq,
+    parser::{self, ParseHex},
+    testing, Flags,
+};
+
+/// Check the invariants of the [`parser`] module for the flags type `B` with arbitrary input.
+///
+/// The input is used in two ways:
+///
+/// - The leading bytes, as many as the size of the bits type, are the bits of a flags value that
+///   must roundtrip through all the formatting and parsing functions.
+/// - The whole input, if it is valid UTF-8, is parsed as text. Text accepted by
+///   [`from_text`](parser::from_text) must roundtrip through the formatting functions and be
+///   accepted by [`from_text_truncate`](parser::from_text_truncate) with the unknown bits unset,
+///   unless the truncated value fails [`Flags::validate`], and text accepted by [`from_text_strict`](parser::from_text_strict) must only have named
+///   flags and parse to the same value with [`from_text`](parser::from_text).
+///
+/// # Panics
+///
+/// Panics if any of the invariants is broken.
+pub fn fuzz_parser_roundtrip<B: Flags>(data: &[u8])
+where
+    B::Bits: ParseHex,
+{
+    if let Some(bits) = testing::bits_from_bytes::<B>(data) {
+        testing::check_value_parser_roundtrip(B::from_bits_retain(bits));
+    }
+
+    if let Ok(text) = core::str::from_utf8(data) {
+        check_text::<B>(text);
+    }
+}
+
+fn check_text<B: Flags>(text: &str)
+where
+    B::Bits: ParseHex,
+{
+    // The validation of the value is checked apart, as truncating it can make it valid or invalid
+    match parser::from_text_unvalidated::<B>(text) {
+        Ok(parsed) => {
+            assert_eq!(
+                parser::from_text::<B>(text).is_ok(),
+                parsed.validate().is_ok(),
+                "`{text}` is not validated by `from_text`"
+            );
+            testing::check_value_parser_roundtrip(parsed);
+
+            let expected = parsed.truncated();
+            match parser::from_text_truncate::<B>(text) {
+                Ok(truncated) => assert_flags_eq!(expected, truncated, "parsed from `{}`", text),
+                Err(err) => assert!(
+                    expected.validate().is_err(),
+                    "`{text}` is accepted by `from_text` but not by `from_text_truncate`: {err}"
+                ),
+            }
+        }
+        Err(_) => assert!(
+            parser::from_text_truncate::<B>(text).is_err(),
+            "`{text}` is accepted by `from_text_truncate` but not by `from_text`"
+        ),
+    }
+
+    if let Ok(strict) = parser::from_text_strict::<B>(text) {
+        assert!(
+            !strict.contains_unknown_bits(),
+            "`{text}` parsed with `from_text_strict` has unknown bits"
+        );
+
+        let parsed: B = parser::from_text(text).unwrap_or_else(|err| {
+            panic!("`{text}` is accepted by `from_text_strict` but not by `from_text`: {err}")
+        });
+        assert_flags_eq!(strict, parsed, "parsed from `{}`", text);
+    }
+}
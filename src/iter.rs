@@ -2,7 +2,7 @@
 
 use core::iter::FusedIterator;
 
-use super::Flags;
+use super::{BitsPrimitive, Flags};
 
 /// An iterator over flags values.
 ///
@@ -18,7 +18,7 @@ pub struct IterNames<B: 'static> {
 impl<B: Flags> IterNames<B> {
     pub(crate) fn new(flags: &B) -> Self {
         Self {
-            flags: B::KNOWN_FLAGS,
+            flags: B::ITER_FLAGS,
             index: 0,
             source: B::from_bits_retain(flags.bits()),
             remaining: B::from_bits_retain(flags.bits()),
@@ -141,3 +141,151 @@ impl<B: Flags> Iterator for Iter<B> {
 }
 
 impl<B: Flags> FusedIterator for Iter<B> {}
+
+/// An iterator over the unknown bits of a flags value.
+///
+/// This iterator yields each set bit that doesn't belong to a known flag as its own single-bit
+/// flags value, from the lowest bit to the highest.
+pub struct IterUnknownBits<B: 'static> {
+    remaining: B,
+}
+
+impl<B: Flags> IterUnknownBits<B> {
+    pub fn new(flags: &B) -> Self {
+        Self {
+            remaining: B::from_bits_retain(flags.bits() & !B::all().bits()),
+        }
+    }
+}
+
+impl<B: 'static> IterUnknownBits<B> {
+    // Used by the `bitflags` macro
+    #[doc(hidden)]
+    pub const fn __private_const_new(remaining: B) -> Self {
+        IterUnknownBits { remaining }
+    }
+}
+
+impl<B: Flags> Iterator for IterUnknownBits<B> {
+    type Item = B;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        let bits = self.remaining.bits();
+        if bits == B::Bits::EMPTY {
+            return None;
+        }
+
+        let bit = bits.lowest_set_bit();
+        self.remaining = B::from_bits_retain(bits & !bit);
+
+        Some(B::from_bits_retain(bit))
+    }
+}
+
+impl<B: Flags> FusedIterator for IterUnknownBits<B> {}
+
+/// An iterator over the states of all defined flags.
+///
+/// This iterator yields the name of every defined flag, with whether the flag is contained in the
+/// source flags value.
+pub struct IterStates<B: 'static> {
+    flags: &'static [(&'static str, B)],
+    index: usize,
+    source: B,
+}
+
+impl<B: Flags> IterStates<B> {
+    pub fn new(flags: &B) -> Self {
+        Self {
+            flags: B::KNOWN_FLAGS,
+            index: 0,
+            source: B::from_bits_retain(flags.bits()),
+        }
+    }
+}
+
+impl<B: 'static> IterStates<B> {
+    // Used by the `bitflags` macro
+    #[doc(hidden)]
+    pub const fn __private_const_new(flags: &'static [(&'static str, B)], source: B) -> Self {
+        IterStates {
+            flags,
+            index: 0,
+            source,
+        }
+    }
+}
+
+impl<B: Flags> Iterator for IterStates<B> {
+    type Item = (&'static str, bool);
+
+    fn next(&mut self) -> Option<Self::Item> {
+        let (name, flag) = self.flags.get(self.index)?;
+        self.index += 1;
+
+        Some((name, self.source.contains(*flag)))
+    }
+
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        let len = self.flags.len() - self.index;
+        (len, Some(len))
+    }
+}
+
+impl<B: Flags> ExactSizeIterator for IterStates<B> {}
+
+impl<B: Flags> FusedIterator for IterStates<B> {}
+
+/// An iterator over the defined flags not contained in a flags value.
+///
+/// This iterator yields the name and value of every defined flag that isn't contained in the
+/// source flags value, including the multi-bit flags only partially contained.
+pub struct IterMissing<B: 'static> {
+    flags: &'static [(&'static str, B)],
+    index: usize,
+    source: B,
+}
+
+impl<B: Flags> IterMissing<B> {
+    pub fn new(flags: &B) -> Self {
+        Self {
+            flags: B::KNOWN_FLAGS,
+            index: 0,
+            source: B::from_bits_retain(flags.bits()),
+        }
+    }
+}
+
+impl<B: 'static> IterMissing<B> {
+    // Used by the `bitflags` macro
+    #[doc(hidden)]
+    pub const fn __private_const_new(flags: &'static [(&'static str, B)], source: B) -> Self {
+        IterMissing {
+            flags,
+            index: 0,
+            source,
+        }
+    }
+}
+
+impl<B: Flags> Iterator for IterMissing<B> {
+    type Item = (&'static str, B);
+
+    fn next(&mut self) -> Option<Self::Item> {
+        while let Some((name, flag)) = self.flags.get(self.index) {
+            self.index += 1;
+
+            if !self.source.contains(*flag) {
+                return Some((name, B::from_bits_retain(flag.bits())));
+            }
+        }
+
+        None
+    }
+
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        (0, Some(self.flags.len() - self.index))
+    }
+}
+
+impl<B: Flags> FusedIterator for IterMissing<B> {}
@@ -31,7 +31,8 @@
 //! }
 //! ```
 //!
-//! Deriving [`Clone`] and [`Copy`] for the type is mandatory.
+//! Deriving [`Clone`] and [`Copy`] for the type is mandatory, either explicitly or with the
+//! `auto_derive` option.
 //!
 //! The generated type is a **struct** wrapping the chosen primitive type.
 //!
@@ -102,7 +103,19 @@
 //! libraries are currently supported:
 //!
 //! - `serde`: Support `#[derive(Serialize, Deserialize)]`, using text for human-readable formats,
-//!     and a raw number for binary formats.
+//!   and a raw number for binary formats. `parser::SerializeAsSeq` serializes a single field as
+//!   a sequence of flag names instead.
+//! - `musli`: Support `#[derive(Encode, Decode)]` of `musli`, encoding the flags as the raw bits.
+//! - `log-kv`: Implement `log::kv::ToValue` for the types with the `log_kv` option, recording the
+//!   flags as text in structured logging.
+//! - `godot`: Implement the `godot` `Var` and `Export` traits for the types with the `godot`
+//!   option, exposing the flags as an `int` property listing the flag names in the editor.
+//! - `rocket`: Implement the `rocket` `FromParam` and `FromFormField` traits for the types with
+//!   the `rocket` option, parsing the flags from text in path parameters and forms.
+//! - `verify`: Generate `kani::Arbitrary` implementations and [Kani] proof harnesses for the
+//!   core properties of the generated API.
+//!
+//! [Kani]: https://github.com/model-checking/kani
 //!
 //! ### Adding custom methods
 //!
@@ -159,12 +172,41 @@
 //! See the docs for the [`example_generated`] module and the [`Flags`] trait for more details on
 //! operators and how they behave.
 //!
+//! ## Matching on flags values
+//!
+//! Flags values can't be used as patterns in a `match`, use the [`bitflag_match`] macro instead:
+//!
+//! ```rust
+//! # use bitflag_attr::{bitflag, bitflag_match};
+//! # #[bitflag(u32)]
+//! # #[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+//! # enum Flags {
+//! #     A = 0b00000001,
+//! #     B = 0b00000010,
+//! #     C = 0b00000100
+//! # }
+//! #
+//! let value = Flags::A | Flags::C;
+//!
+//! let msg = bitflag_match!(value, {
+//!     Flags::A | Flags::B => "exactly A and B",
+//!     contains(Flags::C) => "at least C",
+//!     intersects(Flags::A | Flags::B) => "A or B",
+//!     _ => "something else",
+//! });
+//!
+//! assert_eq!(msg, "at least C");
+//! ```
+//!
 //! # Formatting and parsing
 //!
 //! `bitflags` defines a text format that can be used to convert any flags value to and from strings.
 //!
 //! See the [`parser`] module for more details.
 //!
+//! With the `fuzzing` feature, the `fuzzing` module has entry points to fuzz the parser of any
+//! flags type.
+//!
 //! # Terminology
 //!
 //! This crate and its documentation tries to follow the same terminology of the `bitflags` crate
@@ -226,6 +268,20 @@
 //! [`contains`] and [`intersects`]. A zero-bit flag is always contained, but is never intersected. The
 //! names of zero-bit flags can be parsed, but are never formatted.
 //!
+//! Defining a zero-bit flag is a compile error, unless it is marked with `#[flag(zero)]`:
+//!
+//! ```
+//! use bitflag_attr::bitflag;
+//!
+//! #[bitflag(u8)]
+//! #[derive(Debug, Clone, Copy, PartialEq)]
+//! pub enum Mode {
+//!     #[flag(zero)]
+//!     Default = 0,
+//!     Fast = 1,
+//! }
+//! ```
+//!
 //! [`contains`]: Flags::contains
 //! [`intersects`]: Flags::intersects
 //!
@@ -240,6 +296,7 @@
 //! #[derive(Clone, Copy)]
 //! enum Flags {
 //!     A = 1,
+//!     #[flag(allow_stray_bits)]
 //!     B = 1 | (1 << 1),
 //! }
 //! ```
@@ -247,18 +304,47 @@
 //! The result of `Flags::A ^ Flags::B` is `0b0000_0010`, which doesn't correspond to either
 //! `Flags::A` or `Flags::B` even though it's still a known bit.
 //!
+//! The macro warns about multi-bit flags with bits not in any single-bit flag, and the warning is
+//! an error in [strict mode](bitflag#strict-mode). Flags where this is intended can be marked with
+//! `#[flag(allow_stray_bits)]`. Only flags made of integer literals and other flags are checked.
+//!
 //! [`example_generated`]: crate::example_generated::ExampleFlags
 #![cfg_attr(all(not(test), not(feature = "std")), no_std)]
 
+#[cfg(feature = "alloc")]
+extern crate alloc;
+
+// `core::error` is only stable on Rust 1.81, older compilers have the same trait in `std`
+#[cfg(feature = "core-error")]
+pub(crate) use core::error;
+#[cfg(all(feature = "std", not(feature = "core-error")))]
+pub(crate) use std::error;
+
 use core::{
     fmt,
     ops::{BitAnd, BitOr, BitXor, Not},
 };
 
-pub use bitflags_attr_macros::bitflag;
+pub use bitflags_attr_macros::{bitflag, bitflag_from_consts, bitflag_match, Bitflags};
+
+#[cfg(feature = "c-headers")]
+pub use bitflags_attr_macros::bitflag_from_header;
 
+pub mod builder;
+#[cfg(feature = "fuzzing")]
+pub mod fuzzing;
 pub mod iter;
+pub mod non_empty;
+pub mod ops;
 pub mod parser;
+pub mod testing;
+pub mod valid;
+pub mod version;
+
+pub use builder::FlagsBuilder;
+pub use non_empty::NonEmpty;
+pub use valid::Valid;
+pub use version::Version;
 
 /// Primitive types that can be used with [`bitflag`] attribute implement this trait.
 pub trait BitsPrimitive:
@@ -278,6 +364,35 @@ pub trait BitsPrimitive:
 
     /// A value with all bits set.
     const ALL: Self;
+
+    /// The non-zero type with the same layout, like [`NonZeroU32`](core::num::NonZeroU32) for
+    /// `u32`.
+    type NonZero: Copy + Eq + core::hash::Hash + fmt::Debug + 'static;
+
+    /// Convert to the non-zero type, returning [`None`] if the value is zero.
+    fn to_non_zero(self) -> Option<Self::NonZero>;
+
+    /// Convert back from the non-zero type.
+    fn from_non_zero(value: Self::NonZero) -> Self;
+
+    /// The number of trailing unset bits, that is, the position of the lowest set bit.
+    fn trailing_zeros(self) -> u32;
+
+    /// The number of set bits.
+    fn count_ones(self) -> u32;
+
+    /// A value with only the lowest set bit of `self` set.
+    fn lowest_set_bit(self) -> Self;
+
+    /// The unsigned type with the same size, like [`u32`] for `i32`, or the type itself if it is
+    /// unsigned.
+    type Unsigned: BitsPrimitive + fmt::Debug + fmt::Display + fmt::Binary;
+
+    /// Reinterpret the bits as the unsigned type with the same size.
+    fn to_unsigned(self) -> Self::Unsigned;
+
+    /// Reinterpret the bits of the unsigned type with the same size.
+    fn from_unsigned(value: Self::Unsigned) -> Self;
 }
 
 mod private {
@@ -285,27 +400,200 @@ mod private {
 }
 
 macro_rules! impl_primitive {
-    ($($ty:ty),+ $(,)?) => {
+    ($($ty:ty => $non_zero:ty, $unsigned:ty),+ $(,)?) => {
         $(
             impl $crate::private::Sealed for $ty {}
             impl $crate::BitsPrimitive for $ty {
                 const EMPTY: Self = 0;
                 const ALL: Self = !0;
+
+                type NonZero = $non_zero;
+
+                #[inline]
+                fn to_non_zero(self) -> Option<Self::NonZero> {
+                    <$non_zero>::new(self)
+                }
+
+                #[inline]
+                fn from_non_zero(value: Self::NonZero) -> Self {
+                    value.get()
+                }
+
+                #[inline]
+                fn trailing_zeros(self) -> u32 {
+                    <$ty>::trailing_zeros(self)
+                }
+
+                #[inline]
+                fn count_ones(self) -> u32 {
+                    <$ty>::count_ones(self)
+                }
+
+                #[inline]
+                fn lowest_set_bit(self) -> Self {
+                    self & self.wrapping_neg()
+                }
+
+                type Unsigned = $unsigned;
+
+                #[inline]
+                fn to_unsigned(self) -> Self::Unsigned {
+                    self as $unsigned
+                }
+
+                #[inline]
+                fn from_unsigned(value: Self::Unsigned) -> Self {
+                    value as $ty
+                }
             }
             impl $crate::parser::ParseHex for $ty {
                 fn parse_hex(input: &str) -> Result<Self, $crate::parser::ParseError>
                 where
                     Self: Sized
                 {
-                    <$ty>::from_str_radix(input, 16).map_err(|_| $crate::parser::ParseError::invalid_hex_flag(input))
+                    // Signed types are parsed as their bit pattern, like they are formatted
+                    <$unsigned>::from_str_radix(input, 16)
+                        .map(|bits| bits as $ty)
+                        .map_err(|_| $crate::parser::ParseError::invalid_hex_flag(input))
                 }
             }
         )+
     };
 }
 
-impl_primitive!(i8, i16, i32, i64, i128, isize);
-impl_primitive!(u8, u16, u32, u64, u128, usize);
+impl_primitive!(
+    i8 => core::num::NonZeroI8, u8,
+    i16 => core::num::NonZeroI16, u16,
+    i32 => core::num::NonZeroI32, u32,
+    i64 => core::num::NonZeroI64, u64,
+    i128 => core::num::NonZeroI128, u128,
+    isize => core::num::NonZeroIsize, usize,
+);
+impl_primitive!(
+    u8 => core::num::NonZeroU8, u8,
+    u16 => core::num::NonZeroU16, u16,
+    u32 => core::num::NonZeroU32, u32,
+    u64 => core::num::NonZeroU64, u64,
+    u128 => core::num::NonZeroU128, u128,
+    usize => core::num::NonZeroUsize, usize,
+);
+
+/// An error returned when a value has bits set that are unknown to the target flags type.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct UnknownBitsError<B>(B);
+
+impl<B: BitsPrimitive> UnknownBitsError<B> {
+    /// Create a new error from the `unknown_bits` that were set.
+    #[inline]
+    pub const fn new(unknown_bits: B) -> Self {
+        Self(unknown_bits)
+    }
+
+    /// Return the bits that are unknown to the target flags type.
+    #[inline]
+    pub const fn unknown_bits(&self) -> B {
+        self.0
+    }
+}
+
+impl<B: BitsPrimitive> fmt::Display for UnknownBitsError<B> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "unknown bits set: {:#X}", self.0)
+    }
+}
+
+#[cfg(any(feature = "core-error", feature = "std"))]
+impl<B: BitsPrimitive + fmt::Debug> error::Error for UnknownBitsError<B> {}
+
+/// An error returned when setting flags that are already set, with `try_set`.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct AlreadySetError<B>(B);
+
+impl<B: BitsPrimitive> AlreadySetError<B> {
+    /// Create a new error from the `already_set` bits.
+    #[inline]
+    pub const fn new(already_set: B) -> Self {
+        Self(already_set)
+    }
+
+    /// Return the bits that were already set.
+    #[inline]
+    pub const fn already_set(&self) -> B {
+        self.0
+    }
+}
+
+impl<B: BitsPrimitive> fmt::Display for AlreadySetError<B> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "bits already set: {:#X}", self.0)
+    }
+}
+
+#[cfg(any(feature = "core-error", feature = "std"))]
+impl<B: BitsPrimitive + fmt::Debug> error::Error for AlreadySetError<B> {}
+
+/// An error returned when unsetting flags that are not set, with `try_unset`.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct NotSetError<B>(B);
+
+impl<B: BitsPrimitive> NotSetError<B> {
+    /// Create a new error from the `not_set` bits.
+    #[inline]
+    pub const fn new(not_set: B) -> Self {
+        Self(not_set)
+    }
+
+    /// Return the bits that were not set.
+    #[inline]
+    pub const fn not_set(&self) -> B {
+        self.0
+    }
+}
+
+impl<B: BitsPrimitive> fmt::Display for NotSetError<B> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "bits not set: {:#X}", self.0)
+    }
+}
+
+#[cfg(any(feature = "core-error", feature = "std"))]
+impl<B: BitsPrimitive + fmt::Debug> error::Error for NotSetError<B> {}
+
+/// An error returned when decoding a flags value from its bits, by the decoders generated by the
+/// [`bitflag`] macro.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum DecodeError<B> {
+    /// The bits have bits set that are unknown to the target flags type.
+    UnknownBits(UnknownBitsError<B>),
+    /// The value failed [`Flags::validate`] with the reason.
+    Invalid(&'static str),
+}
+
+impl<B: BitsPrimitive> fmt::Display for DecodeError<B> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            DecodeError::UnknownBits(error) => fmt::Display::fmt(error, f),
+            DecodeError::Invalid(reason) => write!(f, "invalid flags value: {reason}"),
+        }
+    }
+}
+
+#[cfg(any(feature = "core-error", feature = "std"))]
+impl<B: BitsPrimitive + fmt::Debug> error::Error for DecodeError<B> {
+    fn source(&self) -> Option<&(dyn error::Error + 'static)> {
+        match self {
+            DecodeError::UnknownBits(error) => Some(error),
+            DecodeError::Invalid(_) => None,
+        }
+    }
+}
+
+impl<B> From<UnknownBitsError<B>> for DecodeError<B> {
+    #[inline]
+    fn from(error: UnknownBitsError<B>) -> Self {
+        DecodeError::UnknownBits(error)
+    }
+}
 
 /// A set of defined flags using a bits type as storage.
 ///
@@ -352,6 +640,14 @@ impl_primitive!(u8, u16, u32, u64, u128, usize);
 /// }
 /// ```
 ///
+/// ## Strict types
+///
+/// [`from_bits_retain`](Flags::from_bits_retain) keeps the unknown bits for most types, but
+/// the types generated with the `strict` option never have unknown bits set, so their
+/// implementation truncates them like [`from_bits_truncate`](Flags::from_bits_truncate).
+/// Generic code that builds values from bits, like [`Flags::cast`] or
+/// [`ops::intersect_all`], must not expect the unknown bits to be kept for every type.
+///
 /// ## Using `Flags`
 ///
 /// The `Flags` trait can be used generically to work with any flags types. In this example,
@@ -378,6 +674,31 @@ pub trait Flags: Sized + Copy + 'static {
     /// The set of named defined flags.
     const KNOWN_FLAGS: &'static [(&'static str, Self)];
 
+    /// The subset of [`KNOWN_FLAGS`](Flags::KNOWN_FLAGS) that iterators may yield.
+    ///
+    /// Flags with no bits set and flags with exactly the same bits as a previously defined flag
+    /// can never be yielded, so the [`bitflag`] macro removes them from this table. Defaults to
+    /// [`KNOWN_FLAGS`](Flags::KNOWN_FLAGS).
+    const ITER_FLAGS: &'static [(&'static str, Self)] = Self::KNOWN_FLAGS;
+
+    /// The default display labels of the named flags, as `(name, label)` pairs.
+    ///
+    /// Used by [`Flags::display_localized`] for the names missing from its table. The [`bitflag`]
+    /// macro fills it from the `#[flag(label = "...")]` attributes. Defaults to no labels.
+    const LABELS: &'static [(&'static str, &'static str)] = &[];
+
+    /// The documentation of the named flags, as `(name, doc)` pairs.
+    ///
+    /// Used by [`parser::explain_bits`] to describe the flags. The [`bitflag`] macro fills it from
+    /// the first paragraph of the doc comments of the flags. Defaults to no documentation.
+    const DOCS: &'static [(&'static str, &'static str)] = &[];
+
+    /// The symbols of the flags, in the order of the symbolic format, like `rwx`.
+    ///
+    /// Used by [`parser::to_symbolic`] and [`parser::from_symbolic`]. The [`bitflag`] macro fills it
+    /// from the `#[flag(symbol = '...')]` attributes. Defaults to no symbols.
+    const SYMBOLS: &'static [(char, Self)] = &[];
+
     /// Extra possible bits values for the flags.
     ///
     /// Useful for externally defined flags
@@ -391,20 +712,42 @@ pub trait Flags: Sized + Copy + 'static {
     /// The returned value is exactly the bits set in this flags value.
     fn bits(&self) -> Self::Bits;
 
+    /// Return the underlying bits of this bitflag as the unsigned type with the same size.
+    ///
+    /// For signed bits types, this is the bit pattern without a sign, like `0x8000_0000_u32`
+    /// instead of `i32::MIN`.
+    fn bits_unsigned(&self) -> <Self::Bits as BitsPrimitive>::Unsigned {
+        self.bits().to_unsigned()
+    }
+
     /// Convert from `bits` value exactly.
+    ///
+    /// Types generated with the `strict` option never have unknown bits set, so their
+    /// implementation unsets the unknown bits like [`from_bits_truncate`](Flags::from_bits_truncate)
+    /// instead. Generic code can't rely on getting the exact `bits` back from these types.
     fn from_bits_retain(bits: Self::Bits) -> Self;
 
-    /// Converts from a `bits` value. Returning [`None`] is any unknown bits are set.
+    /// Converts from a `bits` value. Returning [`None`] is any unknown bits are set, or if the value
+    /// fails [`validate`](Flags::validate).
     fn from_bits(bits: Self::Bits) -> Option<Self> {
         let truncated = Self::from_bits_truncate(bits);
 
-        if truncated.bits() == bits {
+        if truncated.bits() == bits && truncated.validate().is_ok() {
             Some(truncated)
         } else {
             None
         }
     }
 
+    /// Check the invariants of the flags value beyond having only known bits, like a flag that
+    /// requires another one, returning the reason if it is invalid.
+    ///
+    /// It is checked by [`from_bits`](Flags::from_bits), the [`parser`] and the generated
+    /// `Deserialize`, but not by the other constructors. Any value is valid by default.
+    fn validate(&self) -> Result<(), &'static str> {
+        Ok(())
+    }
+
     /// Convert from `bits` value, unsetting any unknown bits.
     fn from_bits_truncate(bits: Self::Bits) -> Self {
         Self::from_bits_retain(bits & Self::all().bits())
@@ -585,6 +928,38 @@ pub trait Flags: Sized + Copy + 'static {
         *self = Self::from_bits_retain(self.bits()).difference(other);
     }
 
+    /// Set the flags in `other` in the value, failing if any of them is already set.
+    ///
+    /// The value is left unchanged if it fails.
+    fn try_set(&mut self, other: Self) -> Result<(), AlreadySetError<Self::Bits>>
+    where
+        Self: Sized,
+    {
+        let already_set = self.bits() & other.bits();
+        if already_set != Self::Bits::EMPTY {
+            return Err(AlreadySetError::new(already_set));
+        }
+
+        self.set(other);
+        Ok(())
+    }
+
+    /// Unset the flags in `other` in the value, failing if any of them is not set.
+    ///
+    /// The value is left unchanged if it fails.
+    fn try_unset(&mut self, other: Self) -> Result<(), NotSetError<Self::Bits>>
+    where
+        Self: Sized,
+    {
+        let not_set = other.bits() & !self.bits();
+        if not_set != Self::Bits::EMPTY {
+            return Err(NotSetError::new(not_set));
+        }
+
+        self.unset(other);
+        Ok(())
+    }
+
     /// Toggle the flags in `other` in the value.
     fn toggle(&mut self, other: Self)
     where
@@ -593,6 +968,38 @@ pub trait Flags: Sized + Copy + 'static {
         *self = Self::from_bits_retain(self.bits()).symmetric_difference(other);
     }
 
+    /// Toggle all known flags in the value.
+    ///
+    /// Unknown bits are left unchanged.
+    fn toggle_all(&mut self)
+    where
+        Self: Sized,
+    {
+        self.toggle(Self::all());
+    }
+
+    /// Reinterpret the value as another flags type with the same bits type.
+    ///
+    /// The bits are kept as they are, including any bits unknown to `T`, unless `T` is a strict
+    /// type whose [`from_bits_retain`](Flags::from_bits_retain) unsets them.
+    #[must_use]
+    fn cast<T: Flags<Bits = Self::Bits>>(self) -> T
+    where
+        Self: Sized,
+    {
+        T::from_bits_retain(self.bits())
+    }
+
+    /// Reinterpret the value as another flags type with the same bits type, unsetting any bits
+    /// unknown to `T`.
+    #[must_use]
+    fn cast_truncate<T: Flags<Bits = Self::Bits>>(self) -> T
+    where
+        Self: Sized,
+    {
+        T::from_bits_truncate(self.bits())
+    }
+
     /// Yield a set of contained flags values.
     ///
     /// Each yielded flags value will correspond to a defined named flag. Any unknown bits
@@ -608,7 +1015,70 @@ pub trait Flags: Sized + Copy + 'static {
     fn iter_names(&self) -> iter::IterNames<Self> {
         iter::IterNames::new(self)
     }
+
+    /// Yield each unknown bit set in the value as its own flags value.
+    ///
+    /// The bits are yielded from the lowest to the highest.
+    fn iter_unknown_bits(&self) -> iter::IterUnknownBits<Self> {
+        iter::IterUnknownBits::new(self)
+    }
+
+    /// Display the value as text, replacing the flag names with labels.
+    ///
+    /// Each name is looked up in `table` as `(name, label)` pairs, then in [`Flags::LABELS`], and
+    /// is written as is if neither has a label for it. Only the displayed text changes, parsing
+    /// still uses the flag names.
+    fn display_localized<'a>(
+        &'a self,
+        table: &'a [(&'a str, &'a str)],
+    ) -> parser::DisplayLocalized<'a, Self> {
+        parser::DisplayLocalized::new(self, table)
+    }
+
+    /// Yield the name of every defined flag, with whether the flag is contained in the value.
+    ///
+    /// The flags are yielded in the order of [`Flags::KNOWN_FLAGS`]. A zero-bit flag is always
+    /// contained.
+    fn iter_states(&self) -> iter::IterStates<Self> {
+        iter::IterStates::new(self)
+    }
+
+    /// Yield the name and value of every defined flag not contained in the value.
+    ///
+    /// The flags are yielded in the order of [`Flags::KNOWN_FLAGS`], and a multi-bit flag is
+    /// yielded if any of its bits is unset.
+    fn iter_missing(&self) -> iter::IterMissing<Self> {
+        iter::IterMissing::new(self)
+    }
+
+    /// Map the name of every defined flag to whether the flag is contained in the value.
+    ///
+    /// This is the collected form of [`Flags::iter_states`], for consumers wanting the flags as
+    /// key/value pairs.
+    #[cfg(feature = "alloc")]
+    fn to_map(&self) -> alloc::collections::BTreeMap<&'static str, bool> {
+        self.iter_states().collect()
+    }
 }
 
 #[cfg(doc)]
 pub mod example_generated;
+
+// The examples of the macros documentation need this crate, so they are tested from here
+#[cfg(doctest)]
+mod macros_docs {
+    #[doc = include_str!("../bitflags-attr-macros/docs/bitflag.md")]
+    struct Bitflag;
+
+    #[doc = include_str!("../bitflags-attr-macros/docs/bitflag_match.md")]
+    struct BitflagMatch;
+
+    #[doc = include_str!("../bitflags-attr-macros/docs/bitflag_from_consts.md")]
+    struct BitflagFromConsts;
+
+    #[doc = include_str!("../bitflags-attr-macros/docs/derive_bitflags.md")]
+    struct DeriveBitflags;
+
+    #[doc = include_str!("../bitflags-attr-macros/docs/bitflag_from_header.md")]
+    struct BitflagFromHeader;
+}
@@ -0,0 +1,107 @@
+//! A flags value with at least one bit set, stored as a non-zero integer.
+
+use core::fmt;
+
+use crate::{BitsPrimitive, Flags};
+
+type NonZeroBits<F> = <<F as Flags>::Bits as BitsPrimitive>::NonZero;
+
+/// A flags value with at least one bit set.
+///
+/// The bits are stored in the non-zero integer type matching the bits type of `F`, so
+/// `Option<NonEmpty<F>>` has the same size and ABI as the bits type, with [`None`] represented by
+/// `0`. This maps cleanly to FFI values where `0` means absent.
+///
+/// Flags types using the `nonzero` option of the [`bitflag`](crate::bitflag) macro also get
+/// `const` conversions from and to this type.
+///
+/// ```
+/// use core::mem::size_of;
+/// use bitflag_attr::{bitflag, NonEmpty};
+///
+/// #[bitflag(u32)]
+/// #[derive(Debug, Clone, Copy, PartialEq)]
+/// enum Flags {
+///     A = 1,
+///     B = 1 << 1,
+/// }
+///
+/// assert_eq!(size_of::<Option<NonEmpty<Flags>>>(), size_of::<u32>());
+///
+/// let flags = NonEmpty::new(Flags::A | Flags::B).unwrap();
+/// assert_eq!(flags.get(), Flags::A | Flags::B);
+/// assert!(NonEmpty::new(Flags::empty()).is_none());
+/// ```
+#[repr(transparent)]
+pub struct NonEmpty<F: Flags>(NonZeroBits<F>);
+
+impl<F: Flags> NonEmpty<F> {
+    /// Wrap `flags`, returning [`None`] if it has no bits set.
+    #[inline]
+    pub fn new(flags: F) -> Option<Self> {
+        flags.bits().to_non_zero().map(Self)
+    }
+
+    /// Converts from a `bits` value. Returning [`None`] if no bits are set.
+    ///
+    /// Like [`Flags::from_bits_retain`], this keeps any unknown bits.
+    #[inline]
+    pub fn from_bits_retain(bits: F::Bits) -> Option<Self> {
+        bits.to_non_zero().map(Self)
+    }
+
+    /// Return the wrapped flags value.
+    #[inline]
+    pub fn get(self) -> F {
+        F::from_bits_retain(self.bits())
+    }
+
+    /// Return the underlying bits of the flags value.
+    #[inline]
+    pub fn bits(self) -> F::Bits {
+        F::Bits::from_non_zero(self.0)
+    }
+
+    /// Return the underlying bits as a non-zero integer.
+    #[inline]
+    pub const fn non_zero_bits(self) -> NonZeroBits<F> {
+        self.0
+    }
+
+    // Used by the `bitflag` macro
+    #[doc(hidden)]
+    pub const fn __private_const_new(bits: NonZeroBits<F>) -> Self {
+        Self(bits)
+    }
+}
+
+impl<F: Flags> Clone for NonEmpty<F> {
+    #[inline]
+    fn clone(&self) -> Self {
+        *self
+    }
+}
+
+impl<F: Flags> Copy for NonEmpty<F> {}
+
+impl<F: Flags> PartialEq for NonEmpty<F> {
+    #[inline]
+    fn eq(&self, other: &Self) -> bool {
+        self.0 == other.0
+    }
+}
+
+impl<F: Flags> Eq for NonEmpty<F> {}
+
+impl<F: Flags> core::hash::Hash for NonEmpty<F> {
+    #[inline]
+    fn hash<H: core::hash::Hasher>(&self, state: &mut H) {
+        self.0.hash(state)
+    }
+}
+
+impl<F: Flags + fmt::Debug> fmt::Debug for NonEmpty<F> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        fmt::Debug::fmt(&self.get(), f)
+    }
+}
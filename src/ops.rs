@@ -0,0 +1,63 @@
+//! Generic helpers over any flags type.
+//!
+//! These are the common folds over flags values, with the right identity for an empty iterator.
+
+use crate::{BitsPrimitive, Flags};
+
+/// Returns the union of all flags values in `iter`.
+///
+/// Returns an empty flags value if `iter` is empty.
+///
+/// ```
+/// use bitflag_attr::{bitflag, ops};
+///
+/// #[bitflag(u8)]
+/// #[derive(Debug, Clone, Copy, PartialEq)]
+/// enum Flags {
+///     A = 1,
+///     B = 1 << 1,
+/// }
+///
+/// assert_eq!(ops::union_all([Flags::A, Flags::B]), Flags::all());
+/// assert_eq!(ops::union_all::<Flags>([]), Flags::empty());
+/// ```
+pub fn union_all<F: Flags>(iter: impl IntoIterator<Item = F>) -> F {
+    iter.into_iter().fold(F::empty(), F::union)
+}
+
+/// Returns the intersection of all flags values in `iter`.
+///
+/// Returns a flags value with all bits set, including unknown bits, if `iter` is empty. For strict
+/// types, which never have unknown bits set, this is [`Flags::all`] instead.
+///
+/// ```
+/// use bitflag_attr::{bitflag, ops};
+///
+/// #[bitflag(u8)]
+/// #[derive(Debug, Clone, Copy, PartialEq)]
+/// enum Flags {
+///     A = 1,
+///     B = 1 << 1,
+/// }
+///
+/// assert_eq!(ops::intersect_all([Flags::all(), Flags::A]), Flags::A);
+/// assert_eq!(ops::intersect_all::<Flags>([]), Flags::all_bits());
+/// ```
+pub fn intersect_all<F: Flags>(iter: impl IntoIterator<Item = F>) -> F {
+    iter.into_iter()
+        .fold(F::from_bits_retain(F::Bits::ALL), F::intersection)
+}
+
+/// Returns `true` if `value` contains all flags values in `iter`.
+///
+/// Returns `true` if `iter` is empty.
+pub fn contains_all<F: Flags>(value: &F, iter: impl IntoIterator<Item = F>) -> bool {
+    iter.into_iter().all(|flags| value.contains(flags))
+}
+
+/// Returns `true` if `value` intersects any of the flags values in `iter`.
+///
+/// Returns `false` if `iter` is empty.
+pub fn intersects_any<F: Flags>(value: &F, iter: impl IntoIterator<Item = F>) -> bool {
+    iter.into_iter().any(|flags| value.intersects(flags))
+}
@@ -29,7 +29,7 @@
 
 use core::fmt::{self, Write};
 
-use crate::{BitsPrimitive, Flags};
+use crate::{BitsPrimitive, DecodeError, Flags};
 
 /// Write a flags value as text.
 ///
@@ -68,11 +68,671 @@ pub fn to_writer<B: Flags>(flags: &B, mut writer: impl Write) -> Result<(), fmt:
     fmt::Result::Ok(())
 }
 
+/// A wrapper that formats a flags value as text through [`to_writer`].
+///
+/// This allows to display flags values where a [`Display`](fmt::Display) implementation is
+/// required, without formatting them to an intermediate string.
+///
+/// ```
+/// use bitflag_attr::{bitflag, parser::AsDisplay};
+///
+/// #[bitflag(u8)]
+/// #[derive(Clone, Copy)]
+/// enum Flags {
+///     A = 1,
+///     B = 1 << 1,
+/// }
+///
+/// let flags = Flags::A | Flags::B;
+/// assert_eq!(format!("{}", AsDisplay::from_ref(&flags)), "A | B");
+/// ```
+#[repr(transparent)]
+pub struct AsDisplay<B>(B);
+
+impl<B: Flags> AsDisplay<B> {
+    /// Wrap a reference to a flags value.
+    #[inline]
+    pub fn from_ref(flags: &B) -> &Self {
+        // SAFETY: `AsDisplay` is `repr(transparent)` over `B`, so both have the same layout
+        unsafe { &*(flags as *const B as *const Self) }
+    }
+}
+
+impl<B: Flags> fmt::Display for AsDisplay<B> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        to_writer(&self.0, f)
+    }
+}
+
+impl<B: Flags> fmt::Debug for AsDisplay<B> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        fmt::Display::fmt(self, f)
+    }
+}
+
+/// A wrapper that serializes a flags value as a sequence of the names of its contained flags.
+///
+/// Any bits that aren't part of a contained flag are serialized as a last element with their hex
+/// number, so each element is a flag that [`from_text`] accepts. The elements are serialized
+/// straight from the flags value, without allocating.
+///
+/// It gives the array of names format to a single field, with
+/// `#[serde(serialize_with = "bitflag_attr::parser::serialize_as_seq")]`:
+///
+/// ```
+/// use bitflag_attr::bitflag;
+/// use serde::Serialize;
+///
+/// #[bitflag(u8)]
+/// #[derive(Clone, Copy, Serialize)]
+/// enum Flags {
+///     A = 1,
+///     B = 1 << 1,
+/// }
+///
+/// #[derive(Serialize)]
+/// struct Config {
+///     #[serde(serialize_with = "bitflag_attr::parser::serialize_as_seq")]
+///     flags: Flags,
+/// }
+///
+/// let config = Config { flags: Flags::A | Flags::B | Flags::from_bits_retain(1 << 7) };
+/// assert_eq!(serde_json::to_string(&config).unwrap(), r#"{"flags":["A","B","0x80"]}"#);
+/// ```
+#[cfg(feature = "serde")]
+pub struct SerializeAsSeq<'a, B>(&'a B);
+
+#[cfg(feature = "serde")]
+impl<'a, B: Flags> SerializeAsSeq<'a, B> {
+    /// Wrap a reference to a flags value.
+    #[inline]
+    pub const fn new(flags: &'a B) -> Self {
+        Self(flags)
+    }
+}
+
+#[cfg(feature = "serde")]
+impl<B: Flags> serde::Serialize for SerializeAsSeq<'_, B> {
+    fn serialize<S>(&self, serializer: S) -> Result<S::Ok, S::Error>
+    where
+        S: serde::Serializer,
+    {
+        use serde::ser::SerializeSeq;
+
+        // The names are counted first, as some formats need the length of the sequence upfront
+        let mut iter = self.0.iter_names();
+        let names = iter.by_ref().count();
+        let remaining = iter.remaining().bits();
+        let has_remaining = remaining != B::Bits::EMPTY;
+
+        let mut seq = serializer.serialize_seq(Some(names + usize::from(has_remaining)))?;
+        for (name, _) in self.0.iter_names() {
+            seq.serialize_element(name)?;
+        }
+        if has_remaining {
+            seq.serialize_element(&format_args!("{remaining:#X}"))?;
+        }
+        seq.end()
+    }
+}
+
+/// Serialize a flags value as a sequence of the names of its contained flags, for
+/// `#[serde(serialize_with = "...")]`.
+///
+/// See [`SerializeAsSeq`] for the format.
+#[cfg(feature = "serde")]
+pub fn serialize_as_seq<B, S>(flags: &B, serializer: S) -> Result<S::Ok, S::Error>
+where
+    B: Flags,
+    S: serde::Serializer,
+{
+    serde::Serialize::serialize(&SerializeAsSeq::new(flags), serializer)
+}
+
+/// Write a flags value as text, replacing the flag names with labels.
+///
+/// Each name is looked up in `table` as `(name, label)` pairs, then in [`Flags::LABELS`], and is
+/// written as is if neither has a label for it. Any bits that aren't part of a contained flag will
+/// be formatted as a hex number.
+///
+/// The text is meant for display only, it can't be parsed back with [`from_text`].
+///
+/// ```
+/// use bitflag_attr::{bitflag, parser};
+///
+/// #[bitflag(u8)]
+/// #[derive(Clone, Copy)]
+/// enum Flags {
+///     #[flag(label = "Read only")]
+///     ReadOnly = 1,
+///     Hidden = 1 << 1,
+/// }
+///
+/// let mut text = String::new();
+/// parser::to_writer_localized(&Flags::all(), &[], &mut text).unwrap();
+/// assert_eq!(text, "Read only | Hidden");
+///
+/// let mut text = String::new();
+/// parser::to_writer_localized(&Flags::all(), &[("Hidden", "Oculto")], &mut text).unwrap();
+/// assert_eq!(text, "Read only | Oculto");
+/// ```
+pub fn to_writer_localized<B: Flags>(
+    flags: &B,
+    table: &[(&str, &str)],
+    mut writer: impl Write,
+) -> Result<(), fmt::Error> {
+    fn lookup<'a>(table: &[(&str, &'a str)], name: &str) -> Option<&'a str> {
+        table
+            .iter()
+            .find(|(flag_name, _)| *flag_name == name)
+            .map(|(_, label)| *label)
+    }
+
+    let mut first = true;
+    let mut iter = flags.iter_names();
+    for (name, _) in &mut iter {
+        if !first {
+            writer.write_str(" | ")?;
+        }
+
+        first = false;
+        let label = lookup(table, name)
+            .or_else(|| lookup(B::LABELS, name))
+            .unwrap_or(name);
+        writer.write_str(label)?;
+    }
+
+    let remaining = iter.remaining().bits();
+    if remaining != B::Bits::EMPTY {
+        if !first {
+            writer.write_str(" | ")?;
+        }
+
+        write!(writer, "{remaining:#X}")?;
+    }
+
+    fmt::Result::Ok(())
+}
+
+/// A flags value displayed with labels, returned by [`Flags::display_localized`].
+///
+/// See [`to_writer_localized`] for how the labels are chosen.
+pub struct DisplayLocalized<'a, B> {
+    flags: &'a B,
+    table: &'a [(&'a str, &'a str)],
+}
+
+impl<'a, B: Flags> DisplayLocalized<'a, B> {
+    /// Display `flags` with the labels of `table`.
+    #[inline]
+    pub fn new(flags: &'a B, table: &'a [(&'a str, &'a str)]) -> Self {
+        Self { flags, table }
+    }
+}
+
+impl<B: Flags> fmt::Display for DisplayLocalized<'_, B> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        to_writer_localized(self.flags, self.table, f)
+    }
+}
+
+impl<B: Flags> fmt::Debug for DisplayLocalized<'_, B> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        fmt::Display::fmt(self, f)
+    }
+}
+
+/// Explain a bits value of the flags type `B`, for debugging values like the ones returned by a C
+/// API.
+///
+/// The returned value displays the bits on a first line, followed by a line for each contained
+/// named flag, as yielded by [`Flags::iter_names`], with the positions of its bits and its
+/// documentation from [`Flags::DOCS`]. Each remaining bit is then written on its own line, as
+/// unknown if it isn't part of any defined flag, or as unnamed otherwise.
+///
+/// ```
+/// use bitflag_attr::{bitflag, parser};
+///
+/// #[bitflag(u8)]
+/// #[derive(Clone, Copy)]
+/// enum Flags {
+///     /// Allows reading.
+///     Read = 1,
+///     Write = 1 << 1,
+///     Exec = 1 << 2,
+///     ReadWrite = Read | Write,
+/// }
+///
+/// assert_eq!(
+///     parser::explain_bits::<Flags>(0b1000_0011).to_string(),
+///     "0x83\n  Read (bit 0): Allows reading.\n  Write (bit 1)\n  unknown bit 7",
+/// );
+/// ```
+#[inline]
+pub fn explain_bits<B: Flags>(bits: B::Bits) -> ExplainBits<B> {
+    ExplainBits(bits)
+}
+
+/// A per-bit breakdown of a bits value, returned by [`explain_bits`].
+///
+/// The raw bits are kept, as creating a flags value from them would lose the unknown bits of the
+/// types generated with the `strict` option.
+pub struct ExplainBits<B: Flags>(B::Bits);
+
+impl<B: Flags> fmt::Display for ExplainBits<B> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        fn write_positions<B: BitsPrimitive>(f: &mut fmt::Formatter<'_>, bits: B) -> fmt::Result {
+            let word = if bits.count_ones() == 1 {
+                "bit"
+            } else {
+                "bits"
+            };
+            write!(f, " ({word} ")?;
+
+            let mut remaining = bits;
+            let mut first = true;
+            while remaining != B::EMPTY {
+                if !first {
+                    f.write_str(", ")?;
+                }
+
+                first = false;
+                write!(f, "{}", remaining.trailing_zeros())?;
+                remaining = remaining & !remaining.lowest_set_bit();
+            }
+
+            f.write_str(")")
+        }
+
+        write!(f, "{:#X}", self.0)?;
+
+        let known = B::from_bits_truncate(self.0);
+        let mut iter = known.iter_names();
+        for (name, flag) in &mut iter {
+            write!(f, "\n  {name}")?;
+            if flag.bits() != B::Bits::EMPTY {
+                write_positions(f, flag.bits())?;
+            }
+
+            if let Some((_, doc)) = B::DOCS.iter().find(|(flag_name, _)| *flag_name == name) {
+                write!(f, ": {doc}")?;
+            }
+        }
+
+        let all = B::all().bits();
+        let mut remaining = iter.remaining().bits() | (self.0 & !all);
+        while remaining != B::Bits::EMPTY {
+            let bit = remaining.lowest_set_bit();
+            let kind = if bit & all == B::Bits::EMPTY {
+                "unknown"
+            } else {
+                "unnamed"
+            };
+            write!(f, "\n  {kind} bit {}", bit.trailing_zeros())?;
+            remaining = remaining & !bit;
+        }
+
+        fmt::Result::Ok(())
+    }
+}
+
+impl<B: Flags> fmt::Debug for ExplainBits<B> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        fmt::Display::fmt(self, f)
+    }
+}
+
+/// Display a flags value in the symbolic format, like `rw-`.
+///
+/// Each flag of [`Flags::SYMBOLS`] is written as its symbol if it's contained, or as `-` otherwise,
+/// in order. The bits of the flags without a symbol and the unknown bits aren't written.
+///
+/// ```
+/// use bitflag_attr::{bitflag, parser};
+///
+/// #[bitflag(u8)]
+/// #[derive(Clone, Copy)]
+/// enum Mode {
+///     #[flag(symbol = 'r')]
+///     Read = 1 << 2,
+///     #[flag(symbol = 'w')]
+///     Write = 1 << 1,
+///     #[flag(symbol = 'x')]
+///     Exec = 1,
+/// }
+///
+/// assert_eq!(parser::to_symbolic(&(Mode::Read | Mode::Exec)).to_string(), "r-x");
+/// ```
+#[inline]
+pub fn to_symbolic<B: Flags>(flags: &B) -> Symbolic<B> {
+    Symbolic(B::from_bits_retain(flags.bits()))
+}
+
+/// Parse a flags value from the symbolic format, like `rw-`.
+///
+/// The input must have a character for each flag of [`Flags::SYMBOLS`], in order: its symbol to set
+/// it, or `-` to leave it unset.
+///
+/// ```
+/// use bitflag_attr::{bitflag, parser};
+///
+/// #[bitflag(u8)]
+/// #[derive(Debug, Clone, Copy, PartialEq)]
+/// enum Mode {
+///     #[flag(symbol = 'r')]
+///     Read = 1 << 2,
+///     #[flag(symbol = 'w')]
+///     Write = 1 << 1,
+///     #[flag(symbol = 'x')]
+///     Exec = 1,
+/// }
+///
+/// assert_eq!(parser::from_symbolic::<Mode>("rw-").unwrap(), Mode::Read | Mode::Write);
+/// assert!(parser::from_symbolic::<Mode>("w--").is_err());
+/// assert!(parser::from_symbolic::<Mode>("rw").is_err());
+/// ```
+pub fn from_symbolic<B: Flags>(input: &str) -> Result<B, ParseError> {
+    const EXPECTED: &str = "a symbol or `-` for each flag with a symbol";
+
+    let mut flags = B::empty();
+    let mut chars = input.chars();
+
+    for (symbol, flag) in B::SYMBOLS {
+        match chars.next() {
+            Some(ch) if ch == *symbol => flags.set(*flag),
+            Some('-') => {}
+            Some(ch) => return Err(ParseError::invalid_symbol(ch)),
+            None => return Err(ParseError::unexpected_value(EXPECTED)),
+        }
+    }
+
+    if chars.next().is_some() {
+        return Err(ParseError::unexpected_value(EXPECTED));
+    }
+
+    Ok(flags)
+}
+
+/// A flags value in the symbolic format, returned by [`to_symbolic`].
+pub struct Symbolic<B>(B);
+
+impl<B: Flags> fmt::Display for Symbolic<B> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        for (symbol, flag) in B::SYMBOLS {
+            f.write_char(if self.0.contains(*flag) { *symbol } else { '-' })?;
+        }
+
+        fmt::Result::Ok(())
+    }
+}
+
+impl<B: Flags> fmt::Debug for Symbolic<B> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        fmt::Display::fmt(self, f)
+    }
+}
+
+/// The order names are written in by [`to_writer_sorted`].
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum NameOrder {
+    /// Ascending bit order: by the lowest set bit of the flags, then by their number of set bits.
+    Bits,
+    /// Alphabetical order of the names.
+    Alphabetical,
+}
+
+/// Write a flags value as text, with the names sorted in `order`.
+///
+/// The text is the same as [`to_writer`] except for the order of the names, so it doesn't change
+/// when the flags are reordered in their definition. Any bits that aren't part of a contained flag
+/// will be formatted as a hex number.
+///
+/// ```
+/// use bitflag_attr::{bitflag, parser::{self, NameOrder}};
+///
+/// #[bitflag(u8)]
+/// #[derive(Clone, Copy)]
+/// enum Flags {
+///     Write = 1 << 1,
+///     Read = 1,
+///     Append = 1 << 2,
+/// }
+///
+/// let mut text = String::new();
+/// parser::to_writer_sorted(&Flags::all(), NameOrder::Bits, &mut text).unwrap();
+/// assert_eq!(text, "Read | Write | Append");
+///
+/// let mut text = String::new();
+/// parser::to_writer_sorted(&Flags::all(), NameOrder::Alphabetical, &mut text).unwrap();
+/// assert_eq!(text, "Append | Read | Write");
+/// ```
+pub fn to_writer_sorted<B: Flags>(
+    flags: &B,
+    order: NameOrder,
+    mut writer: impl Write,
+) -> Result<(), fmt::Error> {
+    // Names are unique, so they break the ties between flags with the same bits order
+    let key = |name: &'static str, flag: &B| match order {
+        NameOrder::Bits => (flag.bits().trailing_zeros(), flag.bits().count_ones(), name),
+        NameOrder::Alphabetical => (0, 0, name),
+    };
+
+    // Select the names one at a time in order, as there is no buffer to sort them in
+    let mut first = true;
+    let mut last = None;
+    loop {
+        let mut next = None;
+        for (name, flag) in flags.iter_names() {
+            let current = key(name, &flag);
+
+            let after_last = match last {
+                Some(last) => current > last,
+                None => true,
+            };
+            let before_next = match next {
+                Some(next) => current < next,
+                None => true,
+            };
+
+            if after_last && before_next {
+                next = Some(current);
+            }
+        }
+
+        let Some(next @ (_, _, name)) = next else {
+            break;
+        };
+
+        if !first {
+            writer.write_str(" | ")?;
+        }
+
+        first = false;
+        writer.write_str(name)?;
+        last = Some(next);
+    }
+
+    // Append any extra bits that correspond to flags to the end of the format
+    let mut iter = flags.iter_names();
+    for _ in &mut iter {}
+
+    let remaining = iter.remaining().bits();
+    if remaining != B::Bits::EMPTY {
+        if !first {
+            writer.write_str(" | ")?;
+        }
+
+        write!(writer, "{remaining:#X}")?;
+    }
+
+    fmt::Result::Ok(())
+}
+
+/// The number format [`to_writer_styled`] writes unknown bits in.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Default)]
+pub enum BitsFormat {
+    /// A hex number, like `0xC0`, as written by [`to_writer`].
+    #[default]
+    Hex,
+    /// A binary number zero-padded to the size of the bits type, with the digits grouped by four,
+    /// like `0b1100_0000`.
+    Binary,
+    /// A decimal number, like `192`.
+    Decimal,
+}
+
+/// How [`to_writer_styled`] writes the bits that aren't part of a contained flag.
+///
+/// The default style writes them like [`to_writer`], as a single hex number.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Default)]
+pub struct UnknownBitsStyle {
+    format: BitsFormat,
+    split: bool,
+}
+
+impl UnknownBitsStyle {
+    /// Write the unknown bits as a single number in `format`.
+    pub const fn new(format: BitsFormat) -> Self {
+        Self {
+            format,
+            split: false,
+        }
+    }
+
+    /// Write each unknown bit as its own number, from the lowest bit to the highest.
+    pub const fn split(self) -> Self {
+        Self {
+            split: true,
+            ..self
+        }
+    }
+}
+
+/// Write a flags value as text, with the unknown bits written in `style`.
+///
+/// The names are written like [`to_writer`]. Only the hex numbers written by the default style
+/// can be parsed back, so the other styles are meant for logs and diagnostics.
+///
+/// ```
+/// use bitflag_attr::{bitflag, parser::{self, BitsFormat, UnknownBitsStyle}};
+///
+/// #[bitflag(u8)]
+/// #[derive(Clone, Copy)]
+/// enum Flags {
+///     Ready = 1,
+/// }
+///
+/// let flags = Flags::Ready | Flags::from_bits_retain(0b1100_0000);
+///
+/// let mut text = String::new();
+/// parser::to_writer_styled(&flags, UnknownBitsStyle::new(BitsFormat::Binary), &mut text).unwrap();
+/// assert_eq!(text, "Ready | 0b1100_0000");
+///
+/// let mut text = String::new();
+/// let style = UnknownBitsStyle::new(BitsFormat::Binary).split();
+/// parser::to_writer_styled(&flags, style, &mut text).unwrap();
+/// assert_eq!(text, "Ready | 0b0100_0000 | 0b1000_0000");
+///
+/// let mut text = String::new();
+/// let style = UnknownBitsStyle::new(BitsFormat::Decimal).split();
+/// parser::to_writer_styled(&flags, style, &mut text).unwrap();
+/// assert_eq!(text, "Ready | 64 | 128");
+/// ```
+pub fn to_writer_styled<B: Flags>(
+    flags: &B,
+    style: UnknownBitsStyle,
+    mut writer: impl Write,
+) -> Result<(), fmt::Error> {
+    let mut first = true;
+    let mut iter = flags.iter_names();
+    for (name, _) in &mut iter {
+        if !first {
+            writer.write_str(" | ")?;
+        }
+
+        first = false;
+        writer.write_str(name)?;
+    }
+
+    let mut remaining = iter.remaining().bits();
+    while remaining != B::Bits::EMPTY {
+        let bits = if style.split {
+            remaining.lowest_set_bit()
+        } else {
+            remaining
+        };
+        remaining = remaining ^ bits;
+
+        if !first {
+            writer.write_str(" | ")?;
+        }
+
+        first = false;
+        write_bits(bits, style.format, &mut writer)?;
+    }
+
+    fmt::Result::Ok(())
+}
+
+fn write_bits<T: BitsPrimitive>(
+    bits: T,
+    format: BitsFormat,
+    mut writer: impl Write,
+) -> Result<(), fmt::Error> {
+    match format {
+        BitsFormat::Hex => write!(writer, "{bits:#X}"),
+        BitsFormat::Decimal => write!(writer, "{}", bits.to_unsigned()),
+        BitsFormat::Binary => {
+            let digits = core::mem::size_of::<T>() * 8;
+            writer.write_str("0b")?;
+
+            let mut grouped = GroupDigits {
+                writer,
+                remaining: digits,
+            };
+            write!(grouped, "{:0digits$b}", bits.to_unsigned())
+        }
+    }
+}
+
+/// Writes the digits of a number separated by `_` in groups of four, counting from the end.
+struct GroupDigits<W> {
+    writer: W,
+    /// The number of digits left to write.
+    remaining: usize,
+}
+
+impl<W: Write> Write for GroupDigits<W> {
+    fn write_str(&mut self, s: &str) -> fmt::Result {
+        for digit in s.chars() {
+            self.writer.write_char(digit)?;
+            self.remaining -= 1;
+
+            if self.remaining > 0 && self.remaining % 4 == 0 {
+                self.writer.write_char('_')?;
+            }
+        }
+
+        Ok(())
+    }
+}
+
 /// Parse a flags value from text.
 ///
-/// This function will fail on any names that don't correspond to defined flags.
-/// Unknown bits will be retained.
+/// This function will fail on any names that don't correspond to defined flags, or if the value
+/// fails [`Flags::validate`]. Unknown bits will be retained.
 pub fn from_text<B: Flags>(input: &str) -> Result<B, ParseError>
+where
+    B::Bits: ParseHex,
+{
+    validated(from_text_unvalidated(input)?)
+}
+
+/// Parse a flags value from text like [`from_text`], without calling [`Flags::validate`].
+///
+/// Used by the generated decoders, which validate the value after handling its unknown bits.
+#[doc(hidden)]
+pub fn from_text_unvalidated<B: Flags>(input: &str) -> Result<B, ParseError>
 where
     B::Bits: ParseHex,
 {
@@ -119,13 +779,26 @@ pub fn to_writer_truncate<B: Flags>(flags: &B, writer: impl Write) -> Result<(),
 
 /// Parse a flags value from text.
 ///
-/// This function will fail on any names that don't correspond to defined flags.
-/// Unknown bits will be ignored.
+/// This function will fail on any names that don't correspond to defined flags, or if the
+/// truncated value fails [`Flags::validate`]. Unknown bits will be ignored.
 pub fn from_text_truncate<B: Flags>(input: &str) -> Result<B, ParseError>
 where
     B::Bits: ParseHex,
 {
-    Ok(B::from_bits_truncate(from_text::<B>(input)?.bits()))
+    validated(from_text_truncate_unvalidated(input)?)
+}
+
+/// Parse a flags value from text like [`from_text_truncate`], without calling [`Flags::validate`].
+///
+/// Used by the generated decoders, which validate the value after handling its unknown bits.
+#[doc(hidden)]
+pub fn from_text_truncate_unvalidated<B: Flags>(input: &str) -> Result<B, ParseError>
+where
+    B::Bits: ParseHex,
+{
+    Ok(B::from_bits_truncate(
+        from_text_unvalidated::<B>(input)?.bits(),
+    ))
 }
 
 /// Write only the contained, defined, named flags in a flags value as text.
@@ -149,9 +822,17 @@ pub fn to_writer_strict<B: Flags>(flags: &B, mut writer: impl Write) -> Result<(
 
 /// Parse a flags value from text.
 ///
-/// This function will fail on any names that don't correspond to defined flags.
-/// This function will fail to parse hex values.
+/// This function will fail on any names that don't correspond to defined flags, or if the value
+/// fails [`Flags::validate`]. This function will fail to parse hex values.
 pub fn from_text_strict<B: Flags>(input: &str) -> Result<B, ParseError> {
+    validated(from_text_strict_unvalidated(input)?)
+}
+
+/// Parse a flags value from text like [`from_text_strict`], without calling [`Flags::validate`].
+///
+/// Used by the generated decoders, which validate the value after handling its unknown bits.
+#[doc(hidden)]
+pub fn from_text_strict_unvalidated<B: Flags>(input: &str) -> Result<B, ParseError> {
     // This is a simplified version of `from_str` that ignores
     // any bits not corresponding to a named flag
 
@@ -192,7 +873,19 @@ pub trait ParseHex {
         Self: Sized;
 }
 
+/// Check the parsed value with [`Flags::validate`].
+fn validated<B: Flags>(flags: B) -> Result<B, ParseError> {
+    match flags.validate() {
+        Ok(()) => Ok(flags),
+        Err(reason) => Err(ParseError::invalid_value(reason)),
+    }
+}
+
 /// An error encountered while parsing flags from text.
+///
+/// With the `core-error` feature, it implements `core::error::Error`, so it can be boxed and
+/// propagated through error types without enabling the `std` feature. Otherwise, it implements
+/// `std::error::Error` with the `std` feature.
 #[derive(Debug)]
 pub struct ParseError(ParseErrorKind);
 
@@ -212,6 +905,15 @@ enum ParseErrorKind {
         #[cfg(feature = "std")]
         got: String,
     },
+    InvalidValue {
+        reason: &'static str,
+    },
+    UnexpectedValue {
+        expected: &'static str,
+    },
+    InvalidSymbol {
+        got: char,
+    },
 }
 
 impl ParseError {
@@ -247,6 +949,21 @@ impl ParseError {
     pub const fn empty_flag() -> Self {
         ParseError(ParseErrorKind::EmptyFlag)
     }
+
+    /// The parsed flags value failed [`Flags::validate`] with `reason`.
+    pub const fn invalid_value(reason: &'static str) -> Self {
+        ParseError(ParseErrorKind::InvalidValue { reason })
+    }
+
+    /// A symbol that isn't the one of the flag at its position or `-` was encountered.
+    pub const fn invalid_symbol(symbol: char) -> Self {
+        ParseError(ParseErrorKind::InvalidSymbol { got: symbol })
+    }
+
+    /// A value that isn't a representation of the flags was encountered, instead of `expected`.
+    pub const fn unexpected_value(expected: &'static str) -> Self {
+        ParseError(ParseErrorKind::UnexpectedValue { expected })
+    }
 }
 
 impl fmt::Display for ParseError {
@@ -275,10 +992,31 @@ impl fmt::Display for ParseError {
             ParseErrorKind::EmptyFlag => {
                 write!(f, "encountered empty flag")?;
             }
+            ParseErrorKind::InvalidValue { reason } => {
+                write!(f, "invalid flags value: {reason}")?;
+            }
+            ParseErrorKind::UnexpectedValue { expected } => {
+                write!(f, "unexpected value, expected {expected}")?;
+            }
+            ParseErrorKind::InvalidSymbol { got } => {
+                write!(f, "unrecognized symbol `{got}`")?;
+            }
         }
 
         Ok(())
     }
 }
 
-impl core::error::Error for ParseError {}
+#[cfg(any(feature = "core-error", feature = "std"))]
+impl crate::error::Error for ParseError {}
+
+impl<B: BitsPrimitive> From<DecodeError<B>> for ParseError {
+    fn from(error: DecodeError<B>) -> Self {
+        match error {
+            DecodeError::UnknownBits(error) => {
+                ParseError::invalid_hex_flag(format_args!("{:#X}", error.unknown_bits()))
+            }
+            DecodeError::Invalid(reason) => ParseError::invalid_value(reason),
+        }
+    }
+}
@@ -0,0 +1,491 @@
+//! Helpers for testing code that uses flags types.
+//!
+//! With the `differential` feature, the `differential` module compares a flags type with an
+//! equivalent `bitflags!` type, to check that migrating from the `bitflags` crate keeps the same
+//! behavior.
+
+use core::fmt;
+
+use crate::{parser, BitsPrimitive, Flags};
+
+#[cfg(feature = "differential")]
+pub mod differential;
+
+/// Assert that two flags values are equal.
+///
+/// On failure, instead of the [`Debug`](core::fmt::Debug) output of both values, the panic message
+/// lists the named flags that are only set in the left or in the right value, and the unknown bits
+/// that differ between them.
+///
+/// Like [`assert_eq!`], a custom message can be given after the values.
+///
+/// # Example
+///
+/// ```should_panic
+/// use bitflag_attr::{assert_flags_eq, bitflag};
+///
+/// #[bitflag(u8)]
+/// #[derive(Clone, Copy)]
+/// enum Flags {
+///     A = 1 << 0,
+///     B = 1 << 1,
+///     C = 1 << 2,
+/// }
+///
+/// // Panics with:
+/// // assertion `left == right` failed
+/// //   left: A | B
+/// //  right: A | C | 0x80
+/// //  only in left: B
+/// //  only in right: C
+/// //  unknown bits only in right: 0x80
+/// assert_flags_eq!(Flags::A | Flags::B, Flags::A | Flags::C | Flags::from_bits_retain(0x80));
+/// ```
+#[macro_export]
+macro_rules! assert_flags_eq {
+    ($left:expr, $right:expr $(,)?) => {
+        match (&$left, &$right) {
+            (left, right) => {
+                if !$crate::testing::flags_eq(left, right) {
+                    $crate::testing::assert_flags_eq_failed(left, right, ::core::option::Option::None);
+                }
+            }
+        }
+    };
+    ($left:expr, $right:expr, $($arg:tt)+) => {
+        match (&$left, &$right) {
+            (left, right) => {
+                if !$crate::testing::flags_eq(left, right) {
+                    $crate::testing::assert_flags_eq_failed(
+                        left,
+                        right,
+                        ::core::option::Option::Some(::core::format_args!($($arg)+)),
+                    );
+                }
+            }
+        }
+    };
+}
+
+#[doc(hidden)]
+pub fn flags_eq<B: Flags>(left: &B, right: &B) -> bool {
+    left.bits() == right.bits()
+}
+
+#[doc(hidden)]
+#[cold]
+#[track_caller]
+pub fn assert_flags_eq_failed<B: Flags>(left: &B, right: &B, args: Option<fmt::Arguments>) -> ! {
+    let diff = FlagsDiff::new(left, right);
+
+    match args {
+        Some(args) => panic!("assertion `left == right` failed: {args}\n{diff}"),
+        None => panic!("assertion `left == right` failed\n{diff}"),
+    }
+}
+
+/// The per-flag difference between two flags values.
+///
+/// Its [`Display`](fmt::Display) implementation writes both values as text, followed by the named
+/// flags and unknown bits that are set in only one of them.
+pub struct FlagsDiff<'a, B> {
+    left: &'a B,
+    right: &'a B,
+}
+
+impl<'a, B: Flags> FlagsDiff<'a, B> {
+    /// Create the difference between `left` and `right`.
+    pub fn new(left: &'a B, right: &'a B) -> Self {
+        Self { left, right }
+    }
+}
+
+impl<B: Flags> fmt::Display for FlagsDiff<'_, B> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let left = self.left.bits();
+        let right = self.right.bits();
+
+        write!(f, "  left: ")?;
+        write_flags(&B::from_bits_retain(left), f)?;
+        write!(f, "\n right: ")?;
+        write_flags(&B::from_bits_retain(right), f)?;
+
+        for (side, only) in [("left", left & !right), ("right", right & !left)] {
+            let only = B::from_bits_retain(only);
+            let named = only.truncated();
+
+            if !named.is_empty() {
+                write!(f, "\n only in {side}: ")?;
+                parser::to_writer(&named, &mut *f)?;
+            }
+
+            let unknown = only.bits() & !B::all().bits();
+            if unknown != B::Bits::EMPTY {
+                write!(f, "\n unknown bits only in {side}: {unknown:#X}")?;
+            }
+        }
+
+        Ok(())
+    }
+}
+
+fn write_flags<B: Flags>(flags: &B, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+    if flags.is_empty() {
+        write!(f, "{:#X}", flags.bits())
+    } else {
+        parser::to_writer(flags, f)
+    }
+}
+
+/// Generate a module of tests checking that a flags type follows the laws expected from every
+/// [`Flags`] implementation.
+///
+/// This is useful to check manual implementations of the [`Flags`] trait, but works with any
+/// flags type. The generated tests are:
+///
+/// - `parser_roundtrip`: formatting a flags value as text and parsing it back gives the same value
+///   (see [`check_parser_roundtrip`]).
+/// - `truncate_laws`: truncating operations only remove unknown bits (see [`check_truncate_laws`]).
+/// - `complement_laws`: the complement only contains known bits and is an involution on them (see
+///   [`check_complement_laws`]).
+/// - `iter_roundtrip`: the union of the iterated flags values gives the same value (see
+///   [`check_iter_roundtrip`]).
+///
+/// Passing `serde` after the type also generates a `serde_roundtrip` test, that serializes and
+/// deserializes the flags values with `serde_json`, so your crate must have `serde_json` as a
+/// dev-dependency.
+///
+/// The generated module is only compiled when testing.
+///
+/// # Example
+///
+/// ```
+/// use bitflag_attr::{bitflag, bitflag_test_suite};
+///
+/// #[bitflag(u8)]
+/// #[derive(Clone, Copy)]
+/// enum Flags {
+///     A = 1 << 0,
+///     B = 1 << 1,
+///     AB = A | B,
+/// }
+///
+/// bitflag_test_suite!(flags_conformance, Flags);
+/// ```
+#[macro_export]
+macro_rules! bitflag_test_suite {
+    ($name:ident, $ty:ty $(,)?) => {
+        $crate::bitflag_test_suite!(@suite $name, $ty, {});
+    };
+    ($name:ident, $ty:ty, serde $(,)?) => {
+        $crate::bitflag_test_suite!(@suite $name, $ty, {
+            #[test]
+            fn serde_roundtrip() {
+                for value in $crate::testing::SampleValues::<$ty>::new() {
+                    let text = ::serde_json::to_string(&value).unwrap();
+                    let parsed: $ty = ::serde_json::from_str(&text).unwrap();
+
+                    $crate::assert_flags_eq!(value, parsed, "serialized as {}", text);
+                }
+            }
+        });
+    };
+    (@suite $name:ident, $ty:ty, { $($extra:tt)* }) => {
+        #[cfg(test)]
+        mod $name {
+            #[allow(unused_imports)]
+            use super::*;
+
+            #[test]
+            fn parser_roundtrip() {
+                $crate::testing::check_parser_roundtrip::<$ty>();
+            }
+
+            #[test]
+            fn truncate_laws() {
+                $crate::testing::check_truncate_laws::<$ty>();
+            }
+
+            #[test]
+            fn complement_laws() {
+                $crate::testing::check_complement_laws::<$ty>();
+            }
+
+            #[test]
+            fn iter_roundtrip() {
+                $crate::testing::check_iter_roundtrip::<$ty>();
+            }
+
+            $($extra)*
+        }
+    };
+}
+
+/// Check that formatting any flags value as text and parsing it back gives the same value, for
+/// all the formatting and parsing functions in the [`parser`] module.
+///
+/// # Panics
+///
+/// Panics if any of the sample values doesn't roundtrip.
+pub fn check_parser_roundtrip<B: Flags>()
+where
+    B::Bits: parser::ParseHex,
+{
+    for value in SampleValues::<B>::new() {
+        check_value_parser_roundtrip(value);
+    }
+}
+
+/// Check that `value` roundtrips through all the formatting and parsing functions in the
+/// [`parser`] module.
+pub(crate) fn check_value_parser_roundtrip<B: Flags>(value: B)
+where
+    B::Bits: parser::ParseHex,
+{
+    let mut text = Buffer::new();
+    parser::to_writer(&value, &mut text).expect("the formatted text is too long");
+    check_parsed(value, parser::from_text(text.as_str()), &text);
+
+    let mut text = Buffer::new();
+    parser::to_writer_truncate(&value, &mut text).expect("the formatted text is too long");
+    check_parsed(
+        value.truncated(),
+        parser::from_text_truncate(text.as_str()),
+        &text,
+    );
+
+    let mut text = Buffer::new();
+    parser::to_writer_strict(&value, &mut text).expect("the formatted text is too long");
+    let named = value
+        .iter_names()
+        .fold(B::empty(), |acc, (_, flag)| acc.union(flag));
+    check_parsed(named, parser::from_text_strict(text.as_str()), &text);
+}
+
+/// Check that the text parsed to `expected`, or failed to parse if `expected` is rejected by
+/// [`Flags::validate`].
+fn check_parsed<B: Flags>(expected: B, parsed: Result<B, parser::ParseError>, text: &Buffer) {
+    match (expected.validate(), parsed) {
+        (Ok(()), Ok(parsed)) => {
+            assert_flags_eq!(expected, parsed, "formatted as `{}`", text.as_str())
+        }
+        (Ok(()), Err(err)) => panic!("failed to parse `{}`: {err}", text.as_str()),
+        (Err(_), Ok(_)) => panic!(
+            "`{}` parsed to a value rejected by `validate`",
+            text.as_str()
+        ),
+        (Err(_), Err(_)) => {}
+    }
+}
+
+/// Check that the truncating operations only unset unknown bits.
+///
+/// # Panics
+///
+/// Panics if any of the sample values breaks the laws.
+pub fn check_truncate_laws<B: Flags>() {
+    let all = B::all();
+
+    for value in SampleValues::<B>::new() {
+        let truncated = value.truncated();
+
+        assert_flags_eq!(truncated, B::from_bits_retain(value.bits() & all.bits()));
+        assert_flags_eq!(truncated, B::from_bits_truncate(value.bits()));
+        assert_flags_eq!(
+            truncated,
+            truncated.truncated(),
+            "truncated is not idempotent"
+        );
+        assert!(!truncated.contains_unknown_bits());
+        assert!(value.contains(truncated));
+
+        let mut truncate = value;
+        truncate.truncate();
+        assert_flags_eq!(truncated, truncate);
+
+        // The values failing `Flags::validate` are rejected too, even without unknown bits
+        match B::from_bits(value.bits()) {
+            Some(from_bits) => assert_flags_eq!(value, from_bits),
+            None => assert!(value.contains_unknown_bits() || value.validate().is_err()),
+        }
+    }
+}
+
+/// Check that the complement only contains known bits and that applying it twice gives back the
+/// known bits of the value.
+///
+/// # Panics
+///
+/// Panics if any of the sample values breaks the laws.
+pub fn check_complement_laws<B: Flags>() {
+    let all = B::all();
+
+    for value in SampleValues::<B>::new() {
+        let complement = value.complement();
+
+        assert!(!complement.contains_unknown_bits());
+        assert_flags_eq!(complement.complement(), value.truncated());
+        assert_flags_eq!(complement.intersection(value), B::empty());
+        assert_flags_eq!(complement.union(value.truncated()), all);
+    }
+}
+
+/// Check that the union of the flags values yielded while iterating a flags value gives back the
+/// same value.
+///
+/// # Panics
+///
+/// Panics if any of the sample values doesn't roundtrip.
+pub fn check_iter_roundtrip<B: Flags>() {
+    for value in SampleValues::<B>::new() {
+        let from_iter = value.iter().fold(B::empty(), |acc, flag| acc.union(flag));
+        assert_flags_eq!(value, from_iter);
+
+        let mut iter = value.iter_names();
+        let from_names = (&mut iter).fold(B::empty(), |acc, (_, flag)| acc.union(flag));
+        assert_flags_eq!(value, from_names.union(*iter.remaining()));
+
+        for (name, flag) in value.iter_names() {
+            let from_name = B::from_name(name).expect("a yielded name must be a known flag");
+            assert_flags_eq!(flag, from_name);
+        }
+    }
+}
+
+/// A set of flags values that exercise most interesting cases of a flags type: the empty value,
+/// each known flag, all pairs of known flags, all known flags, and values with unknown bits.
+#[doc(hidden)]
+pub struct SampleValues<B: 'static> {
+    index: usize,
+    pair: usize,
+    stage: u8,
+    _marker: core::marker::PhantomData<B>,
+}
+
+impl<B: Flags> SampleValues<B> {
+    pub fn new() -> Self {
+        Self {
+            index: 0,
+            pair: 0,
+            stage: 0,
+            _marker: core::marker::PhantomData,
+        }
+    }
+}
+
+impl<B: Flags> Default for SampleValues<B> {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl<B: Flags> Iterator for SampleValues<B> {
+    type Item = B;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        let known = B::KNOWN_FLAGS;
+
+        loop {
+            match self.stage {
+                // Special values
+                0 => {
+                    let value = match self.index {
+                        0 => B::empty(),
+                        1 => B::all(),
+                        2 => B::all_bits(),
+                        3 => B::all_bits().difference(B::all()),
+                        _ => {
+                            self.stage += 1;
+                            self.index = 0;
+                            continue;
+                        }
+                    };
+
+                    self.index += 1;
+                    return Some(value);
+                }
+                // Pairs of known flags, including each flag with itself
+                1 => {
+                    if self.index >= known.len() {
+                        self.stage += 1;
+                        self.index = 0;
+                        continue;
+                    }
+
+                    if self.pair >= known.len() {
+                        self.index += 1;
+                        self.pair = self.index;
+                        continue;
+                    }
+
+                    let value = known[self.index].1.union(known[self.pair].1);
+                    self.pair += 1;
+                    return Some(value);
+                }
+                // Each known flag with all unknown bits
+                2 => {
+                    let (_, flag) = known.get(self.index)?;
+                    self.index += 1;
+
+                    return Some(flag.union(B::all_bits().difference(B::all())));
+                }
+                _ => return None,
+            }
+        }
+    }
+}
+
+/// Build the bits of a flags value from the leading bytes of `data`, in big-endian order.
+#[cfg(any(feature = "fuzzing", feature = "differential"))]
+pub(crate) fn bits_from_bytes<B: Flags>(data: &[u8]) -> Option<B::Bits>
+where
+    B::Bits: parser::ParseHex,
+{
+    use fmt::Write;
+
+    let bytes = data.get(..core::mem::size_of::<B::Bits>())?;
+
+    // The sign bit is cleared so the hex number also fits in signed bits types, and set back with
+    // the complement
+    let negate = bytes[0] & 0x80 != 0;
+    let mut hex = Buffer::new();
+    write!(hex, "{:02X}", bytes[0] & 0x7F).ok()?;
+    for byte in &bytes[1..] {
+        write!(hex, "{byte:02X}").ok()?;
+    }
+
+    let bits = <B::Bits as parser::ParseHex>::parse_hex(hex.as_str()).ok()?;
+    Some(if negate { !bits } else { bits })
+}
+
+/// A fixed size buffer to format flags values as text without allocating.
+pub(crate) struct Buffer {
+    buf: [u8; 4096],
+    len: usize,
+}
+
+impl Buffer {
+    pub(crate) const fn new() -> Self {
+        Self {
+            buf: [0; 4096],
+            len: 0,
+        }
+    }
+
+    pub(crate) fn as_str(&self) -> &str {
+        // Only complete `str`s are written to the buffer
+        core::str::from_utf8(&self.buf[..self.len]).unwrap_or_default()
+    }
+}
+
+impl fmt::Write for Buffer {
+    fn write_str(&mut self, s: &str) -> fmt::Result {
+        let end = self.len + s.len();
+        let dst = self.buf.get_mut(self.len..end).ok_or(fmt::Error)?;
+
+        dst.copy_from_slice(s.as_bytes());
+        self.len = end;
+
+        Ok(())
+    }
+}
@@ -0,0 +1,599 @@
+//! Differential checks between a flags type and an equivalent reference implementation.
+//!
+//! Teams migrating from the [`bitflags`] crate can check that a type generated by the
+//! [`bitflag`](crate::bitflag) macro behaves exactly like their former `bitflags!` type with the
+//! [`bitflag_differential_test!`](crate::bitflag_differential_test) macro. The checks compare the
+//! results of the operators, the truncating operations, the formatting and the parsing of both
+//! types for their notable values and a set of pseudo-random values.
+//!
+//! Other reference implementations can be checked by implementing [`Reference`] and calling
+//! [`check_differential`].
+//!
+//! [`bitflags`]: https://docs.rs/bitflags
+
+use core::{
+    fmt,
+    ops::{BitAnd, BitOr, BitXor, Not, Sub},
+};
+
+use super::{bits_from_bytes, Buffer, SampleValues};
+use crate::{parser, Flags};
+
+/// A reference flags implementation to compare a flags type against.
+///
+/// The methods follow the semantics of the methods with the same name of the [`Flags`] trait.
+pub trait Reference: Copy {
+    /// The underlying bits type.
+    type Bits;
+
+    /// Convert from `bits` value exactly.
+    fn from_bits_retain(bits: Self::Bits) -> Self;
+
+    /// Get the underlying bits value.
+    fn bits(&self) -> Self::Bits;
+
+    /// Convert from `bits` value, returning [`None`] if any unknown bits are set.
+    fn from_bits(bits: Self::Bits) -> Option<Self>;
+
+    /// Convert from `bits` value, unsetting any unknown bits.
+    fn from_bits_truncate(bits: Self::Bits) -> Self;
+
+    /// Whether all bits in this flags value are unset.
+    fn is_empty(&self) -> bool;
+
+    /// Whether all known bits in this flags value are set.
+    fn is_all(&self) -> bool;
+
+    /// Whether all set bits in a source flags value are also set in a target flags value.
+    fn contains(&self, other: Self) -> bool;
+
+    /// Whether any set bits in a source flags value are also set in a target flags value.
+    fn intersects(&self, other: Self) -> bool;
+
+    /// The bitwise or (`|`) of the bits in two flags values.
+    fn union(self, other: Self) -> Self;
+
+    /// The bitwise and (`&`) of the bits in two flags values.
+    fn intersection(self, other: Self) -> Self;
+
+    /// The intersection of a source flags value with the complement of a target flags value.
+    fn difference(self, other: Self) -> Self;
+
+    /// The bitwise exclusive-or (`^`) of the bits in two flags values.
+    fn symmetric_difference(self, other: Self) -> Self;
+
+    /// The bitwise negation (`!`) of the bits in a flags value, truncating the result.
+    fn complement(self) -> Self;
+
+    /// Call `f` with the name of each named flag yielded while iterating the flags value.
+    fn for_each_name(&self, f: &mut dyn FnMut(&str));
+
+    /// Write the flags value as text.
+    fn to_writer(&self, writer: &mut dyn fmt::Write) -> fmt::Result;
+
+    /// Write the flags value as text, ignoring any unknown bits.
+    fn to_writer_truncate(&self, writer: &mut dyn fmt::Write) -> fmt::Result;
+
+    /// Write only the named flags of the flags value as text.
+    fn to_writer_strict(&self, writer: &mut dyn fmt::Write) -> fmt::Result;
+
+    /// Parse a flags value from text, retaining unknown bits.
+    fn from_text(input: &str) -> Option<Self>;
+
+    /// Parse a flags value from text, ignoring unknown bits.
+    fn from_text_truncate(input: &str) -> Option<Self>;
+
+    /// Parse a flags value from text made only of flag names.
+    fn from_text_strict(input: &str) -> Option<Self>;
+}
+
+/// Generate a test comparing a flags type with an equivalent `bitflags!` type.
+///
+/// The test calls [`check_differential`] with a fixed seed and `1000` pseudo-random values, or the
+/// number of values given after the types. Your crate must have [`bitflags`] 2.5 or later as a
+/// dev-dependency, and the `bitflags!` type must implement [`Copy`].
+///
+/// The generated test is only compiled when testing.
+///
+/// # Example
+///
+/// ```ignore
+/// use bitflag_attr::{bitflag, bitflag_differential_test};
+///
+/// #[bitflag(u8)]
+/// #[derive(Clone, Copy)]
+/// enum Flags {
+///     A = 1 << 0,
+///     B = 1 << 1,
+///     AB = A | B,
+/// }
+///
+/// bitflags::bitflags! {
+///     #[derive(Clone, Copy)]
+///     struct OldFlags: u8 {
+///         const A = 1 << 0;
+///         const B = 1 << 1;
+///         const AB = Self::A.bits() | Self::B.bits();
+///     }
+/// }
+///
+/// bitflag_differential_test!(same_as_bitflags, Flags, OldFlags);
+/// ```
+///
+/// [`bitflags`]: https://docs.rs/bitflags
+#[macro_export]
+macro_rules! bitflag_differential_test {
+    ($name:ident, $ty:ty, $reference:ty $(,)?) => {
+        $crate::bitflag_differential_test!($name, $ty, $reference, 1000);
+    };
+    ($name:ident, $ty:ty, $reference:ty, $count:expr $(,)?) => {
+        #[cfg(test)]
+        mod $name {
+            #[allow(unused_imports)]
+            use super::*;
+
+            #[derive(Clone, Copy)]
+            struct Reference($reference);
+
+            impl $crate::testing::differential::Reference for Reference {
+                type Bits = <$reference as ::bitflags::Flags>::Bits;
+
+                fn from_bits_retain(bits: Self::Bits) -> Self {
+                    Self(<$reference as ::bitflags::Flags>::from_bits_retain(bits))
+                }
+
+                fn bits(&self) -> Self::Bits {
+                    ::bitflags::Flags::bits(&self.0)
+                }
+
+                fn from_bits(bits: Self::Bits) -> ::core::option::Option<Self> {
+                    <$reference as ::bitflags::Flags>::from_bits(bits).map(Self)
+                }
+
+                fn from_bits_truncate(bits: Self::Bits) -> Self {
+                    Self(<$reference as ::bitflags::Flags>::from_bits_truncate(bits))
+                }
+
+                fn is_empty(&self) -> bool {
+                    ::bitflags::Flags::is_empty(&self.0)
+                }
+
+                fn is_all(&self) -> bool {
+                    ::bitflags::Flags::is_all(&self.0)
+                }
+
+                fn contains(&self, other: Self) -> bool {
+                    ::bitflags::Flags::contains(&self.0, other.0)
+                }
+
+                fn intersects(&self, other: Self) -> bool {
+                    ::bitflags::Flags::intersects(&self.0, other.0)
+                }
+
+                fn union(self, other: Self) -> Self {
+                    Self(::bitflags::Flags::union(self.0, other.0))
+                }
+
+                fn intersection(self, other: Self) -> Self {
+                    Self(::bitflags::Flags::intersection(self.0, other.0))
+                }
+
+                fn difference(self, other: Self) -> Self {
+                    Self(::bitflags::Flags::difference(self.0, other.0))
+                }
+
+                fn symmetric_difference(self, other: Self) -> Self {
+                    Self(::bitflags::Flags::symmetric_difference(self.0, other.0))
+                }
+
+                fn complement(self) -> Self {
+                    Self(::bitflags::Flags::complement(self.0))
+                }
+
+                fn for_each_name(&self, f: &mut dyn ::core::ops::FnMut(&str)) {
+                    for (name, _) in ::bitflags::Flags::iter_names(&self.0) {
+                        f(name);
+                    }
+                }
+
+                fn to_writer(&self, writer: &mut dyn ::core::fmt::Write) -> ::core::fmt::Result {
+                    ::bitflags::parser::to_writer(&self.0, writer)
+                }
+
+                fn to_writer_truncate(
+                    &self,
+                    writer: &mut dyn ::core::fmt::Write,
+                ) -> ::core::fmt::Result {
+                    ::bitflags::parser::to_writer_truncate(&self.0, writer)
+                }
+
+                fn to_writer_strict(
+                    &self,
+                    writer: &mut dyn ::core::fmt::Write,
+                ) -> ::core::fmt::Result {
+                    ::bitflags::parser::to_writer_strict(&self.0, writer)
+                }
+
+                fn from_text(input: &str) -> ::core::option::Option<Self> {
+                    ::bitflags::parser::from_str(input).ok().map(Self)
+                }
+
+                fn from_text_truncate(input: &str) -> ::core::option::Option<Self> {
+                    ::bitflags::parser::from_str_truncate(input).ok().map(Self)
+                }
+
+                fn from_text_strict(input: &str) -> ::core::option::Option<Self> {
+                    ::bitflags::parser::from_str_strict(input).ok().map(Self)
+                }
+            }
+
+            #[test]
+            fn differential() {
+                $crate::testing::differential::check_differential::<$ty, Reference>(0x5EED, $count);
+            }
+        }
+    };
+}
+
+/// Inputs that the parsers must reject or accept in the same way, besides the formatted values.
+const TEXT_INPUTS: &[&str] = &[
+    "",
+    " ",
+    "|",
+    " | ",
+    "0x",
+    "0x0",
+    "0xFF",
+    "0xff",
+    "0x1FFFF",
+    "0x-1",
+    "-0x1",
+    "0xG",
+    "x0",
+    "|0x1",
+    "0x1|",
+    "0x1 || 0x2",
+    "0x1 | 0x2",
+];
+
+/// Check that the flags type `B` behaves like the reference implementation `R`.
+///
+/// The checks run over the notable values of `B`, like the empty value, each known flag and all
+/// bits, and `count` pseudo-random values generated from `seed`. Each value and each pair of
+/// values is compared through:
+///
+/// - the conversions from bits, the truncation and the complement, including the `!` operator;
+/// - the set operations and the `|`, `&`, `^` and `-` operators;
+/// - the flag names yielded while iterating;
+/// - the text written by the formatting functions of the [`parser`] module, ignoring the case of
+///   hex numbers, and the results of the parsing functions on any text written by either type.
+///
+/// # Panics
+///
+/// Panics on the first difference between `B` and `R`.
+pub fn check_differential<B, R>(seed: u64, count: usize)
+where
+    B: Flags
+        + Not<Output = B>
+        + BitOr<Output = B>
+        + BitAnd<Output = B>
+        + BitXor<Output = B>
+        + Sub<Output = B>,
+    B::Bits: parser::ParseHex,
+    R: Reference<Bits = B::Bits>,
+{
+    let mut rng = Rng::new(seed);
+
+    for text in TEXT_INPUTS {
+        check_parse::<B, R>(text);
+    }
+
+    for (name, _) in B::KNOWN_FLAGS {
+        check_parse::<B, R>(name);
+    }
+
+    for value in SampleValues::<B>::new() {
+        check_values::<B, R>(value, random_value(&mut rng));
+    }
+
+    for _ in 0..count {
+        check_values::<B, R>(random_value(&mut rng), random_value(&mut rng));
+    }
+}
+
+fn random_value<B: Flags>(rng: &mut Rng) -> B
+where
+    B::Bits: parser::ParseHex,
+{
+    loop {
+        if let Some(bits) = bits_from_bytes::<B>(&rng.next_bytes()) {
+            return B::from_bits_retain(bits);
+        }
+    }
+}
+
+fn check_values<B, R>(value: B, other: B)
+where
+    B: Flags
+        + Not<Output = B>
+        + BitOr<Output = B>
+        + BitAnd<Output = B>
+        + BitXor<Output = B>
+        + Sub<Output = B>,
+    B::Bits: parser::ParseHex,
+    R: Reference<Bits = B::Bits>,
+{
+    check_value::<B, R>(value);
+    check_pair::<B, R>(value, other);
+    check_pair::<B, R>(value, value.truncated());
+    check_pair::<B, R>(value, value.complement());
+}
+
+/// Panic with both bits values if they differ.
+macro_rules! check_bits {
+    ($what:expr, $ours:expr, $reference:expr) => {{
+        let (ours, reference) = ($ours, $reference);
+        if ours != reference {
+            panic!(
+                "`{}` differs: {:#X} is not the reference {:#X}",
+                $what, ours, reference
+            );
+        }
+    }};
+}
+
+/// Panic with both results if they differ.
+macro_rules! check_eq {
+    ($what:expr, $ours:expr, $reference:expr) => {{
+        let (ours, reference) = ($ours, $reference);
+        if ours != reference {
+            panic!(
+                "`{}` differs: {:?} is not the reference {:?}",
+                $what, ours, reference
+            );
+        }
+    }};
+}
+
+fn check_value<B, R>(value: B)
+where
+    B: Flags + Not<Output = B>,
+    B::Bits: parser::ParseHex,
+    R: Reference<Bits = B::Bits>,
+{
+    let bits = value.bits();
+    let reference = R::from_bits_retain(bits);
+    let hex = Hex(bits);
+
+    check_bits!(
+        format_args!("from_bits_retain({hex})"),
+        bits,
+        reference.bits()
+    );
+    check_eq!(
+        format_args!("from_bits({hex}).is_some()"),
+        B::from_bits(bits).is_some(),
+        R::from_bits(bits).is_some()
+    );
+    check_bits!(
+        format_args!("from_bits_truncate({hex})"),
+        B::from_bits_truncate(bits).bits(),
+        R::from_bits_truncate(bits).bits()
+    );
+    check_bits!(
+        format_args!("{hex}.truncated()"),
+        value.truncated().bits(),
+        R::from_bits_truncate(bits).bits()
+    );
+    check_bits!(
+        format_args!("{hex}.complement()"),
+        value.complement().bits(),
+        reference.complement().bits()
+    );
+    check_bits!(
+        format_args!("!{hex}"),
+        (!value).bits(),
+        reference.complement().bits()
+    );
+    check_eq!(
+        format_args!("{hex}.is_empty()"),
+        value.is_empty(),
+        reference.is_empty()
+    );
+    check_eq!(
+        format_args!("{hex}.is_all()"),
+        value.is_all(),
+        reference.is_all()
+    );
+
+    let mut names = value.iter_names().map(|(name, _)| name);
+    let mut same = true;
+    reference.for_each_name(&mut |name| same &= names.next() == Some(name));
+    if !same || names.next().is_some() {
+        panic!("`{hex}.iter_names()` differs from the reference");
+    }
+
+    let writers: [(&str, Writer<B>, Writer<R>); 3] = [
+        (
+            "to_writer",
+            |flags, buf| parser::to_writer(flags, buf),
+            |flags, buf| flags.to_writer(buf),
+        ),
+        (
+            "to_writer_truncate",
+            |flags, buf| parser::to_writer_truncate(flags, buf),
+            |flags, buf| flags.to_writer_truncate(buf),
+        ),
+        (
+            "to_writer_strict",
+            |flags, buf| parser::to_writer_strict(flags, buf),
+            |flags, buf| flags.to_writer_strict(buf),
+        ),
+    ];
+
+    for (what, write, write_reference) in writers {
+        let mut text = Buffer::new();
+        let mut reference_text = Buffer::new();
+        write(&value, &mut text).expect("the formatted text is too long");
+        write_reference(&reference, &mut reference_text).expect("the formatted text is too long");
+
+        if !text.as_str().eq_ignore_ascii_case(reference_text.as_str()) {
+            panic!(
+                "`{what}({hex})` differs: `{}` is not the reference `{}`",
+                text.as_str(),
+                reference_text.as_str()
+            );
+        }
+
+        check_parse::<B, R>(text.as_str());
+        check_parse::<B, R>(reference_text.as_str());
+    }
+}
+
+fn check_pair<B, R>(left: B, right: B)
+where
+    B: Flags + BitOr<Output = B> + BitAnd<Output = B> + BitXor<Output = B> + Sub<Output = B>,
+    R: Reference<Bits = B::Bits>,
+{
+    let reference_left = R::from_bits_retain(left.bits());
+    let reference_right = R::from_bits_retain(right.bits());
+    let (l, r) = (Hex(left.bits()), Hex(right.bits()));
+
+    check_eq!(
+        format_args!("{l}.contains({r})"),
+        left.contains(right),
+        reference_left.contains(reference_right)
+    );
+    check_eq!(
+        format_args!("{l}.intersects({r})"),
+        left.intersects(right),
+        reference_left.intersects(reference_right)
+    );
+
+    let union = reference_left.union(reference_right).bits();
+    check_bits!(
+        format_args!("{l}.union({r})"),
+        left.union(right).bits(),
+        union
+    );
+    check_bits!(format_args!("{l} | {r}"), (left | right).bits(), union);
+
+    let intersection = reference_left.intersection(reference_right).bits();
+    check_bits!(
+        format_args!("{l}.intersection({r})"),
+        left.intersection(right).bits(),
+        intersection
+    );
+    check_bits!(
+        format_args!("{l} & {r}"),
+        (left & right).bits(),
+        intersection
+    );
+
+    let difference = reference_left.difference(reference_right).bits();
+    check_bits!(
+        format_args!("{l}.difference({r})"),
+        left.difference(right).bits(),
+        difference
+    );
+    check_bits!(format_args!("{l} - {r}"), (left - right).bits(), difference);
+
+    let symmetric_difference = reference_left.symmetric_difference(reference_right).bits();
+    check_bits!(
+        format_args!("{l}.symmetric_difference({r})"),
+        left.symmetric_difference(right).bits(),
+        symmetric_difference
+    );
+    check_bits!(
+        format_args!("{l} ^ {r}"),
+        (left ^ right).bits(),
+        symmetric_difference
+    );
+}
+
+fn check_parse<B, R>(text: &str)
+where
+    B: Flags,
+    B::Bits: parser::ParseHex,
+    R: Reference<Bits = B::Bits>,
+{
+    let parsers: [(&str, Parser<B>, Parser<R>); 3] = [
+        (
+            "from_text",
+            |text| parser::from_text(text).ok(),
+            R::from_text,
+        ),
+        (
+            "from_text_truncate",
+            |text| parser::from_text_truncate(text).ok(),
+            R::from_text_truncate,
+        ),
+        (
+            "from_text_strict",
+            |text| parser::from_text_strict(text).ok(),
+            R::from_text_strict,
+        ),
+    ];
+
+    for (what, parse, parse_reference) in parsers {
+        match (parse(text), parse_reference(text)) {
+            (Some(parsed), Some(reference)) => check_bits!(
+                format_args!("{what}({text:?})"),
+                parsed.bits(),
+                reference.bits()
+            ),
+            (None, None) => {}
+            (parsed, reference) => panic!(
+                "`{what}({text:?})` differs: {} but the reference {}",
+                if parsed.is_some() {
+                    "succeeds"
+                } else {
+                    "fails"
+                },
+                if reference.is_some() {
+                    "succeeds"
+                } else {
+                    "fails"
+                },
+            ),
+        }
+    }
+}
+
+/// A formatting function of a flags type.
+type Writer<T> = fn(&T, &mut Buffer) -> fmt::Result;
+
+/// A parsing function of a flags type.
+type Parser<T> = fn(&str) -> Option<T>;
+
+/// Formats bits as a hex number in messages.
+struct Hex<T>(T);
+
+impl<T: fmt::UpperHex> fmt::Display for Hex<T> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{:#X}", self.0)
+    }
+}
+
+/// A xorshift pseudo-random number generator, so the checks are reproducible and don't need any
+/// dependency.
+struct Rng(u64);
+
+impl Rng {
+    fn new(seed: u64) -> Self {
+        // The state must never be zero
+        Self(seed | 1)
+    }
+
+    fn next_bytes(&mut self) -> [u8; 16] {
+        let mut bytes = [0; 16];
+        for chunk in bytes.chunks_mut(8) {
+            self.0 ^= self.0 << 13;
+            self.0 ^= self.0 >> 7;
+            self.0 ^= self.0 << 17;
+            chunk.copy_from_slice(&self.0.to_be_bytes());
+        }
+
+        bytes
+    }
+}
@@ -0,0 +1,203 @@
+//! A wrapper guaranteeing that a flags value has no unknown bits set.
+
+use core::{
+    fmt,
+    ops::{BitAnd, BitOr, BitXor, Deref, Not, Sub},
+};
+
+use crate::{BitsPrimitive, Flags, UnknownBitsError};
+
+/// A flags value without any unknown bits set.
+///
+/// This allows function signatures to demand values with only known bits, without the flags type
+/// itself being `strict`. Every way of creating a `Valid` either validates or truncates the value,
+/// and it dereferences to the wrapped flags value.
+///
+/// ```
+/// use bitflag_attr::{bitflag, Valid};
+///
+/// #[bitflag(u8)]
+/// #[derive(Debug, Clone, Copy, PartialEq)]
+/// enum Flags {
+///     A = 1,
+///     B = 1 << 1,
+/// }
+///
+/// fn only_known(flags: Valid<Flags>) -> u8 {
+///     flags.bits()
+/// }
+///
+/// assert_eq!(only_known(Valid::new_truncate(Flags::from_bits_retain(0xFF))), 0b11);
+/// assert!(Valid::new(Flags::from_bits_retain(0x80)).is_none());
+/// ```
+#[repr(transparent)]
+#[derive(Clone, Copy, PartialEq, Eq, Hash)]
+pub struct Valid<F>(F);
+
+impl<F: Flags> Valid<F> {
+    /// Wrap `flags`, returning [`None`] if any unknown bits are set.
+    #[inline]
+    pub fn new(flags: F) -> Option<Self> {
+        if flags.contains_unknown_bits() {
+            None
+        } else {
+            Some(Self(flags))
+        }
+    }
+
+    /// Wrap `flags`, unsetting any unknown bits.
+    #[inline]
+    pub fn new_truncate(flags: F) -> Self {
+        Self(flags.truncated())
+    }
+
+    /// Wrap `flags` without checking for unknown bits.
+    ///
+    /// # Safety
+    ///
+    /// `flags` must not have any unknown bits set.
+    #[inline]
+    pub const unsafe fn new_unchecked(flags: F) -> Self {
+        Self(flags)
+    }
+
+    /// Converts from a `bits` value. Returning [`None`] is any unknown bits are set.
+    #[inline]
+    pub fn from_bits(bits: F::Bits) -> Option<Self> {
+        F::from_bits(bits).map(Self)
+    }
+
+    /// Convert from `bits` value, unsetting any unknown bits.
+    #[inline]
+    pub fn from_bits_truncate(bits: F::Bits) -> Self {
+        Self(F::from_bits_truncate(bits))
+    }
+
+    /// Wrap `flags`, returning an error with the unknown bits if any are set.
+    #[inline]
+    pub fn try_new(flags: F) -> Result<Self, UnknownBitsError<F::Bits>> {
+        let unknown = flags.bits() & !F::all().bits();
+
+        if unknown == F::Bits::EMPTY {
+            Ok(Self(flags))
+        } else {
+            Err(UnknownBitsError::new(unknown))
+        }
+    }
+
+    /// Construct a flags value with all bits unset.
+    #[inline]
+    pub fn empty() -> Self {
+        Self(F::empty())
+    }
+
+    /// Construct a flag value with all known flags set.
+    #[inline]
+    pub fn all() -> Self {
+        Self(F::all())
+    }
+
+    /// Return the wrapped flags value.
+    #[inline]
+    pub fn into_inner(self) -> F {
+        self.0
+    }
+}
+
+impl<F> Deref for Valid<F> {
+    type Target = F;
+
+    #[inline]
+    fn deref(&self) -> &Self::Target {
+        &self.0
+    }
+}
+
+impl<F> AsRef<F> for Valid<F> {
+    #[inline]
+    fn as_ref(&self) -> &F {
+        &self.0
+    }
+}
+
+impl<F: fmt::Debug> fmt::Debug for Valid<F> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        fmt::Debug::fmt(&self.0, f)
+    }
+}
+
+impl<F: fmt::Display> fmt::Display for Valid<F> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        fmt::Display::fmt(&self.0, f)
+    }
+}
+
+impl<F: Flags> Not for Valid<F> {
+    type Output = Self;
+
+    #[inline]
+    fn not(self) -> Self::Output {
+        Self(self.0.complement())
+    }
+}
+
+impl<F: Flags> BitAnd for Valid<F> {
+    type Output = Self;
+
+    #[inline]
+    fn bitand(self, rhs: Self) -> Self::Output {
+        Self(self.0.intersection(rhs.0))
+    }
+}
+
+impl<F: Flags> BitOr for Valid<F> {
+    type Output = Self;
+
+    #[inline]
+    fn bitor(self, rhs: Self) -> Self::Output {
+        Self(self.0.union(rhs.0))
+    }
+}
+
+impl<F: Flags> BitXor for Valid<F> {
+    type Output = Self;
+
+    #[inline]
+    fn bitxor(self, rhs: Self) -> Self::Output {
+        Self(self.0.symmetric_difference(rhs.0))
+    }
+}
+
+impl<F: Flags> Sub for Valid<F> {
+    type Output = Self;
+
+    #[inline]
+    fn sub(self, rhs: Self) -> Self::Output {
+        Self(self.0.difference(rhs.0))
+    }
+}
+
+#[cfg(feature = "serde")]
+impl<F: serde::Serialize> serde::Serialize for Valid<F> {
+    fn serialize<S>(&self, serializer: S) -> Result<S::Ok, S::Error>
+    where
+        S: serde::Serializer,
+    {
+        self.0.serialize(serializer)
+    }
+}
+
+#[cfg(feature = "serde")]
+impl<'de, F> serde::Deserialize<'de> for Valid<F>
+where
+    F: Flags + serde::Deserialize<'de>,
+{
+    fn deserialize<D>(deserializer: D) -> Result<Self, D::Error>
+    where
+        D: serde::Deserializer<'de>,
+    {
+        let flags = F::deserialize(deserializer)?;
+
+        Self::try_new(flags).map_err(serde::de::Error::custom)
+    }
+}
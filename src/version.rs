@@ -0,0 +1,84 @@
+//! Version numbers for the introduction version metadata of flags.
+
+use core::{cmp::Ordering, fmt};
+
+/// A `major.minor.patch` version number.
+///
+/// Flags can record the version they were introduced in with `#[flag(since = "...")]`, and the
+/// generated `valid_for_version` constructor uses this type to select the available flags.
+///
+/// ```
+/// use bitflag_attr::Version;
+///
+/// assert!(Version::new(3, 11, 0) < Version::from((3, 12)));
+/// assert_eq!(Version::new(3, 11, 2).to_string(), "3.11.2");
+/// ```
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub struct Version {
+    /// The major version number.
+    pub major: u32,
+    /// The minor version number.
+    pub minor: u32,
+    /// The patch version number.
+    pub patch: u32,
+}
+
+impl Version {
+    /// Construct a version from its components.
+    #[inline]
+    pub const fn new(major: u32, minor: u32, patch: u32) -> Self {
+        Self {
+            major,
+            minor,
+            patch,
+        }
+    }
+
+    /// Whether this version is the same or more recent than `other`.
+    ///
+    /// This is the same as `self >= other`, usable in `const` contexts.
+    #[inline]
+    pub const fn at_least(&self, other: Version) -> bool {
+        if self.major != other.major {
+            return self.major > other.major;
+        }
+        if self.minor != other.minor {
+            return self.minor > other.minor;
+        }
+        self.patch >= other.patch
+    }
+}
+
+impl PartialOrd for Version {
+    #[inline]
+    fn partial_cmp(&self, other: &Self) -> Option<Ordering> {
+        Some(self.cmp(other))
+    }
+}
+
+impl Ord for Version {
+    #[inline]
+    fn cmp(&self, other: &Self) -> Ordering {
+        (self.major, self.minor, self.patch).cmp(&(other.major, other.minor, other.patch))
+    }
+}
+
+impl From<(u32, u32)> for Version {
+    #[inline]
+    fn from((major, minor): (u32, u32)) -> Self {
+        Self::new(major, minor, 0)
+    }
+}
+
+impl From<(u32, u32, u32)> for Version {
+    #[inline]
+    fn from((major, minor, patch): (u32, u32, u32)) -> Self {
+        Self::new(major, minor, patch)
+    }
+}
+
+impl fmt::Display for Version {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}.{}.{}", self.major, self.minor, self.patch)
+    }
+}
@@ -1,4 +1,4 @@
-use bitflag_attr::bitflag;
+use bitflag_attr::{bitflag, AlreadySetError, Bitflags, Flags, NotSetError};
 
 #[bitflag(u32)]
 #[derive(Debug, Copy, Clone, PartialEq, Eq, PartialOrd, Ord)]
@@ -17,6 +17,8 @@ fn constructors_works() {
 
     let all_known = TestFlags::all();
     assert_eq!(all_known.bits(), (1 << 0) | (1 << 1) | (1 << 3) | (1 << 4));
+    assert_eq!(TestFlags::ALL_MASK, all_known.bits());
+    assert_eq!(Register24::ALL_MASK, (1 << 0) | (1 << 8) | (1 << 23));
 
     let all_bits_set = TestFlags::all_bits();
     assert_eq!(all_bits_set.bits(), !0);
@@ -219,3 +221,1506 @@ fn symmetric_difference_works() {
     assert_eq!(g2.symmetric_difference(g3), TestFlags::F1 | TestFlags::F3);
     assert_eq!(g3.symmetric_difference(g2), TestFlags::F1 | TestFlags::F3);
 }
+
+#[test]
+fn assert_flags_eq_works() {
+    bitflag_attr::assert_flags_eq!(TestFlags::F1 | TestFlags::F3, TestFlags::F1_3);
+    bitflag_attr::assert_flags_eq!(
+        TestFlags::empty(),
+        TestFlags::empty(),
+        "empty is {}",
+        "empty"
+    );
+}
+
+#[test]
+#[should_panic(
+    expected = "only in left: F2\n only in right: F3\n unknown bits only in right: 0x80"
+)]
+fn assert_flags_eq_shows_diff() {
+    bitflag_attr::assert_flags_eq!(
+        TestFlags::F1 | TestFlags::F2,
+        TestFlags::F1 | TestFlags::F3 | TestFlags::from_bits_retain(0x80)
+    );
+}
+
+#[test]
+#[should_panic(
+    expected = "assertion `left == right` failed: custom message\n  left: F1\n right: F4"
+)]
+fn assert_flags_eq_custom_message() {
+    bitflag_attr::assert_flags_eq!(TestFlags::F1, TestFlags::F4, "custom {}", "message");
+}
+
+#[bitflag(u32)]
+#[bitflag_convert(TestFlags)]
+#[derive(Debug, Copy, Clone, PartialEq, Eq)]
+enum RetainFlags {
+    F1 = 1 << 0,
+    F2 = 1 << 1,
+}
+
+#[bitflag(u32)]
+#[bitflag_convert(TestFlags, truncate)]
+#[derive(Debug, Copy, Clone, PartialEq, Eq)]
+enum TruncateFlags {
+    F1 = 1 << 0,
+    F2 = 1 << 1,
+}
+
+#[bitflag(u32)]
+#[bitflag_convert(TestFlags, checked)]
+#[derive(Debug, Copy, Clone, PartialEq, Eq)]
+enum CheckedFlags {
+    F1 = 1 << 0,
+    F2 = 1 << 1,
+}
+
+#[test]
+fn bitflag_convert_works() {
+    let value = TestFlags::F1 | TestFlags::F3;
+
+    assert_eq!(RetainFlags::from(value).bits(), value.bits());
+    assert_eq!(TestFlags::from(RetainFlags::F2), TestFlags::F2);
+
+    assert_eq!(TruncateFlags::from(value), TruncateFlags::F1);
+    assert_eq!(
+        TestFlags::from(TruncateFlags::from_bits_retain(1 << 2 | 1)),
+        TestFlags::F1
+    );
+
+    assert_eq!(
+        CheckedFlags::try_from(TestFlags::F1 | TestFlags::F2),
+        Ok(CheckedFlags::F1 | CheckedFlags::F2)
+    );
+    let err = CheckedFlags::try_from(value).unwrap_err();
+    assert_eq!(err.unknown_bits(), 1 << 3);
+    assert_eq!(err.to_string(), "unknown bits set: 0x8");
+    assert_eq!(TestFlags::try_from(CheckedFlags::F1), Ok(TestFlags::F1));
+}
+
+#[bitflag(u8)]
+#[derive(Debug, Copy, Clone, PartialEq, Eq)]
+enum FilePerm {
+    Exec = 1 << 0,
+    Write = 1 << 1,
+    Read = 1 << 2,
+}
+
+#[bitflag(u16)]
+#[bitflag_fields(user: FilePerm @ 6..9, group: FilePerm @ 3..6)]
+#[bitflag_fields(other: FilePerm @ 0..3)]
+#[derive(Debug, Copy, Clone, PartialEq, Eq)]
+enum Permissions {
+    Sticky = 1 << 9,
+    SetGid = 1 << 10,
+    SetUid = 1 << 11,
+}
+
+#[test]
+fn bitflag_fields_works() {
+    let mut mode = Permissions::from_bits(0o4750).unwrap();
+    assert_eq!(
+        mode.user(),
+        FilePerm::Read | FilePerm::Write | FilePerm::Exec
+    );
+    assert_eq!(mode.group(), FilePerm::Read | FilePerm::Exec);
+    assert_eq!(mode.other(), FilePerm::empty());
+    assert!(mode.contains(Permissions::SetUid));
+
+    mode.set_group(FilePerm::Read);
+    mode.set_other(FilePerm::Read | FilePerm::from_bits_retain(1 << 3));
+    assert_eq!(mode.bits(), 0o4744);
+    assert_eq!(Permissions::all().bits(), 0o7777);
+    assert!(!Permissions::from_bits_retain(0o7777).contains_unknown_bits());
+    assert!(Permissions::from_bits(0o17777).is_none());
+}
+
+#[bitflag(u32, strip_prefix = "O_")]
+#[derive(Debug, Copy, Clone, PartialEq, Eq)]
+enum OpenFlags {
+    O_RDONLY = 1 << 0,
+    O_CREAT = 1 << 6,
+    #[flag(label = "Exclusive")]
+    O_EXCL = 1 << 7,
+    CLOEXEC = 1 << 19,
+}
+
+#[test]
+fn strip_prefix_works() {
+    use bitflag_attr::{parser, Flags};
+
+    let flags = OpenFlags::O_CREAT | OpenFlags::O_EXCL | OpenFlags::CLOEXEC;
+    let mut text = String::new();
+    parser::to_writer(&flags, &mut text).unwrap();
+    assert_eq!(text, "CREAT | EXCL | CLOEXEC");
+    assert_eq!(text.parse::<OpenFlags>().unwrap(), flags);
+    assert!("O_CREAT".parse::<OpenFlags>().is_err());
+
+    assert_eq!(
+        OpenFlags::from_flag_name("RDONLY"),
+        Some(OpenFlags::O_RDONLY)
+    );
+    assert_eq!(
+        <OpenFlags as Flags>::from_name("EXCL"),
+        Some(OpenFlags::O_EXCL)
+    );
+    assert_eq!(OpenFlags::LABELS, &[("EXCL", "Exclusive")]);
+}
+
+#[bitflag(u8, validate = check_lock)]
+#[derive(Debug, Copy, Clone, PartialEq, Eq)]
+enum LockFlags {
+    Shared = 1 << 0,
+    Exclusive = 1 << 1,
+    NonBlocking = 1 << 2,
+}
+
+const fn check_lock(flags: &LockFlags) -> Result<(), &'static str> {
+    if flags.contains(LockFlags::Shared.union(LockFlags::Exclusive)) {
+        Err("a lock can't be both shared and exclusive")
+    } else {
+        Ok(())
+    }
+}
+
+#[test]
+fn validate_works() {
+    use bitflag_attr::{parser, Flags};
+
+    assert_eq!(
+        LockFlags::from_bits(0b101),
+        Some(LockFlags::Shared | LockFlags::NonBlocking)
+    );
+    assert_eq!(LockFlags::from_bits(0b011), None);
+    assert_eq!(<LockFlags as Flags>::from_bits(0b011), None);
+    assert_eq!(
+        <LockFlags as Flags>::from_bits(0b010),
+        Some(LockFlags::Exclusive)
+    );
+
+    let both = LockFlags::Shared | LockFlags::Exclusive;
+    assert_eq!(
+        both.validate(),
+        Err("a lock can't be both shared and exclusive")
+    );
+    assert_eq!(TestFlags::F1.validate(), Ok(()));
+
+    let err = "Shared | Exclusive".parse::<LockFlags>().unwrap_err();
+    assert_eq!(
+        err.to_string(),
+        "invalid flags value: a lock can't be both shared and exclusive"
+    );
+    assert!(parser::from_text_strict::<LockFlags>("Exclusive | Shared").is_err());
+    assert!(parser::from_text_truncate::<LockFlags>("Shared | 0x8").is_ok());
+    assert!(parser::from_text_truncate::<LockFlags>("0x3").is_err());
+}
+
+#[bitflag(u8, msrv = "1.65")]
+#[derive(Debug, Copy, Clone, PartialEq, Eq)]
+enum MsrvFlags {
+    F1 = 1 << 0,
+    F2 = 1 << 1,
+}
+
+#[test]
+fn msrv_works() {
+    let mut value = MsrvFlags::F1;
+    value.set(MsrvFlags::F2);
+    value ^= MsrvFlags::F1;
+    assert_eq!(value, MsrvFlags::F2);
+
+    value.toggle_all();
+    value -= MsrvFlags::F2;
+    assert_eq!(value, MsrvFlags::F1);
+}
+
+#[bitflag(u8, parse_error = ModeError)]
+#[derive(Debug, Copy, Clone, PartialEq, Eq)]
+enum ModeFlags {
+    Read = 1 << 0,
+    Write = 1 << 1,
+    #[cfg(any())]
+    Exec = 1 << 2,
+}
+
+#[test]
+fn parse_error_works() {
+    assert_eq!(
+        "Read | Write".parse::<ModeFlags>().unwrap(),
+        ModeFlags::Read | ModeFlags::Write
+    );
+
+    let err: ModeError = "Read | | Write".parse::<ModeFlags>().unwrap_err();
+    assert_eq!(
+        err.to_string(),
+        "invalid value for ModeFlags (valid: Read, Write): encountered empty flag"
+    );
+    assert_eq!(err.parse_error().to_string(), "encountered empty flag");
+    #[cfg(feature = "core-error")]
+    assert_eq!(
+        std::error::Error::source(&err)
+            .map(ToString::to_string)
+            .as_deref(),
+        Some("encountered empty flag")
+    );
+
+    let err = ModeError::from(bitflag_attr::parser::ParseError::empty_flag());
+    assert!(err.to_string().starts_with("invalid value for ModeFlags"));
+}
+
+#[bitflag(u16, with_methods, strip_prefix = "MS_")]
+#[derive(Debug, Copy, Clone, PartialEq, Eq)]
+enum MountFlags {
+    MS_RDONLY = 1 << 0,
+    MS_NOSUID = 1 << 1,
+    MS_NO_EXEC = 1 << 3,
+    #[cfg(any())]
+    MS_SYNC = 1 << 4,
+}
+
+#[test]
+fn with_methods_works() {
+    const FLAGS: MountFlags = MountFlags::empty().with_rdonly().with_no_exec();
+
+    assert_eq!(FLAGS, MountFlags::MS_RDONLY | MountFlags::MS_NO_EXEC);
+    assert_eq!(FLAGS.with_rdonly(), FLAGS);
+    assert_eq!(FLAGS.without_rdonly(), MountFlags::MS_NO_EXEC);
+    assert_eq!(
+        MountFlags::all().without_nosuid().without_no_exec(),
+        MountFlags::MS_RDONLY
+    );
+}
+
+#[bitflag(u8, accessors)]
+#[derive(Debug, Copy, Clone, PartialEq, Eq)]
+enum EditorSettings {
+    DarkMode = 1 << 0,
+    AutoSave = 1 << 1,
+    #[flag(zero)]
+    Nothing = 0,
+}
+
+#[test]
+fn accessors_works() {
+    let mut settings = EditorSettings::DarkMode;
+    assert!(settings.is_dark_mode());
+    assert!(!settings.is_auto_save());
+    assert!(settings.is_nothing());
+
+    settings.set_auto_save(true);
+    settings.set_dark_mode(false);
+    assert_eq!(settings, EditorSettings::AutoSave);
+
+    settings.set_auto_save(false);
+    assert!(settings.is_empty());
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Bitflags)]
+#[bitflags(bits = u16, accessors)]
+#[flags {
+    Read = 1 << 0,
+    Write = 1 << 1,
+    /// Read and write.
+    ReadWrite = Read | Write,
+}]
+#[repr(transparent)]
+struct Access(u16);
+
+#[test]
+fn derive_works() {
+    let access = Access::Read | Access::Write;
+    assert_eq!(access, Access::ReadWrite);
+    assert_eq!(access.0, 0b11);
+    assert_eq!(Access::all().bits(), 0b11);
+    assert!(access.is_write());
+    assert_eq!(Access::from_bits(1 << 2), None);
+    assert_eq!("Read".parse::<Access>().unwrap(), Access::Read);
+}
+
+#[test]
+fn try_set_works() {
+    let mut flags = TestFlags::F1;
+    assert_eq!(flags.try_set(TestFlags::F2), Ok(()));
+    assert_eq!(flags, TestFlags::F1 | TestFlags::F2);
+
+    let err = flags.try_set(TestFlags::F1_3).unwrap_err();
+    assert_eq!(err, AlreadySetError::new(TestFlags::F1.bits()));
+    assert_eq!(err.to_string(), "bits already set: 0x1");
+    assert_eq!(flags, TestFlags::F1 | TestFlags::F2);
+
+    let err = flags.try_unset(TestFlags::F1_3).unwrap_err();
+    assert_eq!(err.not_set(), TestFlags::F3.bits());
+    assert_eq!(flags, TestFlags::F1 | TestFlags::F2);
+    assert_eq!(flags.try_unset(TestFlags::F1), Ok(()));
+    assert_eq!(flags, TestFlags::F2);
+
+    assert_eq!(Flags::try_set(&mut flags, TestFlags::F4), Ok(()));
+    assert_eq!(
+        Flags::try_unset(&mut flags, TestFlags::F1),
+        Err(NotSetError::new(TestFlags::F1.bits()))
+    );
+}
+
+#[bitflag(u32)]
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum TargetFlags {
+    #[flag(value(all(), 1 << 2), value(all(), 1 << 3))]
+    First = 1 << 0,
+    #[flag(value(any(), 1 << 2))]
+    Fallback = 1 << 1,
+    #[flag(value(not(any()), First | Fallback))]
+    Referencing,
+    #[flag(value(any(), self::missing::VALUE), value(any(), "not bits"))]
+    OtherTarget = 1 << 4,
+}
+
+#[test]
+fn per_target_values_works() {
+    assert_eq!(TargetFlags::First.bits(), 1 << 2);
+    assert_eq!(TargetFlags::Fallback.bits(), 1 << 1);
+    assert_eq!(TargetFlags::Referencing.bits(), (1 << 2) | (1 << 1));
+    assert_eq!(TargetFlags::OtherTarget.bits(), 1 << 4);
+    assert_eq!(TargetFlags::all().bits(), (1 << 4) | (1 << 2) | (1 << 1));
+}
+
+#[bitflag(u8, method_names = bitflags, accessors)]
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum BitflagsNames {
+    A = 1 << 0,
+    B = 1 << 1,
+}
+
+#[test]
+fn bitflags_method_names_works() {
+    let mut flags = BitflagsNames::empty();
+    flags.insert(BitflagsNames::A);
+    flags.set(BitflagsNames::B, true);
+    assert_eq!(flags, BitflagsNames::all());
+
+    flags.set(BitflagsNames::A, false);
+    assert_eq!(flags, BitflagsNames::B);
+    flags.remove(BitflagsNames::B);
+    assert!(flags.is_empty());
+
+    assert_eq!(flags.try_insert(BitflagsNames::A), Ok(()));
+    assert!(flags.try_insert(BitflagsNames::A).is_err());
+    assert!(flags.try_remove(BitflagsNames::B).is_err());
+
+    flags.set_b(true);
+    flags -= BitflagsNames::A;
+    flags.extend([BitflagsNames::A]);
+    assert_eq!(flags, BitflagsNames::all());
+
+    Flags::set(&mut flags, BitflagsNames::A);
+}
+
+#[bitflag(u8, from_str = strict)]
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum StrictParse {
+    A = 1 << 0,
+    B = 1 << 1,
+}
+
+#[bitflag(u8, from_str = truncate)]
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum TruncateParse {
+    A = 1 << 0,
+    B = 1 << 1,
+}
+
+#[test]
+fn from_str_mode_works() {
+    assert_eq!("A | B".parse::<StrictParse>().unwrap(), StrictParse::all());
+    assert!("A | 0x2".parse::<StrictParse>().is_err());
+    assert!("0x80".parse::<StrictParse>().is_err());
+
+    assert_eq!(
+        "A | 0x82".parse::<TruncateParse>().unwrap(),
+        TruncateParse::all()
+    );
+    assert_eq!(
+        "0x80".parse::<TruncateParse>().unwrap(),
+        TruncateParse::empty()
+    );
+}
+
+#[bitflag(u8)]
+#[derive(Debug, Clone, Copy)]
+enum Explained {
+    /// Allows reading.
+    ///
+    /// Only the first paragraph is kept.
+    Read = 1 << 0,
+    /// Allows writing
+    /// to the file.
+    Write = 1 << 1,
+    Both = Read | Write,
+    Other = 1 << 4,
+    #[flag(allow_stray_bits)]
+    Partial = (1 << 5) | (1 << 6),
+}
+
+#[test]
+fn explain_bits_works() {
+    assert_eq!(
+        <Explained as Flags>::DOCS,
+        &[
+            ("Read", "Allows reading."),
+            ("Write", "Allows writing to the file.")
+        ]
+    );
+
+    assert_eq!(
+        Explained::explain_bits(0b1010_0011).to_string(),
+        "0xA3\n  Read (bit 0): Allows reading.\n  Write (bit 1): Allows writing to the file.\n  unnamed bit 5\n  unknown bit 7"
+    );
+    assert_eq!(
+        Explained::explain_bits(0b0111_0000).to_string(),
+        "0x70\n  Other (bit 4)\n  Partial (bits 5, 6)"
+    );
+    assert_eq!(Explained::explain_bits(0).to_string(), "0x0");
+
+    assert_eq!(
+        StrictFlags::explain_bits(0b1001).to_string(),
+        "0x9\n  F1 (bit 0)\n  unknown bit 3"
+    );
+}
+
+#[bitflag(u16)]
+#[derive(Debug, Clone, Copy, PartialEq)]
+enum FileMode {
+    #[flag(symbol = 'r')]
+    Read = 1 << 2,
+    #[flag(symbol = 'w')]
+    Write = 1 << 1,
+    #[flag(symbol = 'x')]
+    Exec = 1 << 0,
+    Sticky = 1 << 9,
+}
+
+#[test]
+fn symbolic_format_works() {
+    assert_eq!(FileMode::all().to_symbolic().to_string(), "rwx");
+    assert_eq!(
+        (FileMode::Read | FileMode::Exec | FileMode::Sticky)
+            .to_symbolic()
+            .to_string(),
+        "r-x"
+    );
+    assert_eq!(FileMode::empty().to_symbolic().to_string(), "---");
+
+    assert_eq!(
+        FileMode::from_symbolic("rw-").unwrap(),
+        FileMode::Read | FileMode::Write
+    );
+    assert_eq!(FileMode::from_symbolic("---").unwrap(), FileMode::empty());
+    assert_eq!(
+        FileMode::from_symbolic("r-w").unwrap_err().to_string(),
+        "unrecognized symbol `w`"
+    );
+    assert!(FileMode::from_symbolic("rw").is_err());
+    assert!(FileMode::from_symbolic("rwx-").is_err());
+}
+
+// `Default` can't be derived for the enum, which has no `#[default]` variant
+#[bitflag(u8)]
+#[bitflag_forward_attr(derive(Default), must_use)]
+#[derive(Debug, Clone, Copy, PartialEq)]
+enum Forwarded {
+    A = 1,
+    B = 1 << 1,
+}
+
+#[test]
+fn forward_attr_works() {
+    assert_eq!(Forwarded::default(), Forwarded::empty());
+    assert_eq!(Forwarded::A | Forwarded::B, Forwarded::all());
+}
+
+#[bitflag(u32, strip_prefix = "O_")]
+#[derive(Debug, Clone, Copy, PartialEq)]
+enum LegacyOpenFlags {
+    #[flag(alias = "RDONLY", alias = "READ_ONLY")]
+    O_READ = 1 << 0,
+    #[flag(alias = "WRONLY")]
+    O_WRITE = 1 << 1,
+}
+
+#[test]
+fn alias_works() {
+    use bitflag_attr::{parser, Flags};
+
+    assert_eq!(LegacyOpenFlags::RDONLY, LegacyOpenFlags::O_READ);
+    assert_eq!(LegacyOpenFlags::READ_ONLY, LegacyOpenFlags::O_READ);
+    assert_eq!(LegacyOpenFlags::WRONLY, LegacyOpenFlags::O_WRITE);
+
+    assert_eq!(
+        LegacyOpenFlags::from_flag_name("RDONLY"),
+        Some(LegacyOpenFlags::O_READ)
+    );
+    assert_eq!(
+        <LegacyOpenFlags as Flags>::from_name("WRONLY"),
+        Some(LegacyOpenFlags::O_WRITE)
+    );
+    assert_eq!(
+        LegacyOpenFlags::from_flag_name("READ"),
+        Some(LegacyOpenFlags::O_READ)
+    );
+    assert_eq!(LegacyOpenFlags::from_flag_name("O_READ"), None);
+
+    let flags: LegacyOpenFlags = "READ_ONLY | WRONLY".parse().unwrap();
+    assert_eq!(flags, LegacyOpenFlags::all());
+    assert_eq!(
+        parser::from_text_strict::<LegacyOpenFlags>("RDONLY").unwrap(),
+        LegacyOpenFlags::O_READ
+    );
+
+    // Only the flag names are formatted and iterated
+    let mut text = String::new();
+    parser::to_writer(&flags, &mut text).unwrap();
+    assert_eq!(text, "READ | WRITE");
+    assert_eq!(LegacyOpenFlags::KNOWN_FLAGS.len(), 2);
+}
+
+#[bitflag(u8)]
+#[derive(Debug, Clone, Copy, PartialEq)]
+enum Channels {
+    #[composite]
+    Rgb = Red | Green | Blue,
+    Red = 1 << 0,
+    Green = 1 << 1,
+    Blue = 1 << 2,
+    Alpha = 1 << 3,
+}
+
+#[test]
+fn composite_works() {
+    use bitflag_attr::{parser, Flags};
+
+    let flags = Channels::Rgb | Channels::Alpha;
+    let names: Vec<_> = flags.iter_names().map(|(name, _)| name).collect();
+    assert_eq!(names, ["Red", "Green", "Blue", "Alpha"]);
+    assert_eq!(
+        format!("{flags:?}"),
+        "Channels { flags: Red | Green | Blue | Alpha, bits: 0b00001111 }"
+    );
+
+    let mut text = String::new();
+    parser::to_writer(&flags, &mut text).unwrap();
+    assert_eq!(text, "Red | Green | Blue | Alpha");
+
+    // The composite flag is still known and parsed
+    assert_eq!(Channels::KNOWN_FLAGS.len(), 5);
+    assert_eq!(Channels::ITER_FLAGS.len(), 4);
+    assert_eq!("Rgb".parse::<Channels>().unwrap(), Channels::Rgb);
+    assert_eq!(Channels::all(), flags);
+}
+
+mod facade {
+    pub use ::bitflag_attr as flags;
+}
+
+#[bitflag(u16, crate = facade::flags)]
+#[derive(Debug, Clone, Copy, PartialEq)]
+enum FacadeFlags {
+    A = 1 << 0,
+    B = 1 << 1,
+}
+
+#[test]
+fn crate_path_works() {
+    use facade::flags::Flags;
+
+    assert_eq!(FacadeFlags::KNOWN_FLAGS.len(), 2);
+    assert_eq!("A | B".parse::<FacadeFlags>().unwrap(), FacadeFlags::all());
+}
+
+#[bitflag(u8, no_ops, no_from, no_from_str, no_iter)]
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, PartialOrd, Ord)]
+enum HandWrittenImpls {
+    A = 1 << 0,
+    B = 1 << 1,
+}
+
+// Conflicting with the generated implementations without the opt-outs
+impl From<u8> for HandWrittenImpls {
+    fn from(bits: u8) -> Self {
+        Self::from_bits_retain(bits)
+    }
+}
+
+impl std::str::FromStr for HandWrittenImpls {
+    type Err = ();
+
+    fn from_str(input: &str) -> Result<Self, Self::Err> {
+        input.parse::<u8>().map(Self::from).map_err(|_| ())
+    }
+}
+
+#[test]
+fn no_impls_works() {
+    assert_eq!(HandWrittenImpls::from(0x83).bits(), 0x83);
+    assert_eq!(
+        "3".parse::<HandWrittenImpls>().unwrap(),
+        HandWrittenImpls::all()
+    );
+
+    let flags = HandWrittenImpls::A.or(HandWrittenImpls::B);
+    assert_eq!(flags.iter().count(), 2);
+    assert_eq!(
+        format!("{flags:?}"),
+        "HandWrittenImpls { flags: A | B, bits: 0b00000011 }"
+    );
+}
+
+#[bitflag(u8, from_bits = retain)]
+#[derive(Debug, Clone, Copy, PartialEq)]
+enum RetainFromBits {
+    A = 1 << 0,
+    B = 1 << 1,
+}
+
+const fn check_a_with_b(flags: &CheckedFromBits) -> Result<(), &'static str> {
+    if flags.contains(CheckedFromBits::B) && !flags.contains(CheckedFromBits::A) {
+        Err("`B` requires `A`")
+    } else {
+        Ok(())
+    }
+}
+
+#[bitflag(u8, from_bits = checked, validate = check_a_with_b)]
+#[derive(Debug, Clone, Copy, PartialEq)]
+enum CheckedFromBits {
+    A = 1 << 0,
+    B = 1 << 1,
+}
+
+#[test]
+fn from_bits_option_works() {
+    use bitflag_attr::{DecodeError, UnknownBitsError};
+
+    assert_eq!(RetainFromBits::from(0x83).bits(), 0x83);
+    assert_eq!(u8::from(RetainFromBits::from(0x83)), 0x83);
+
+    assert_eq!(CheckedFromBits::try_from(0x03), Ok(CheckedFromBits::all()));
+    assert_eq!(
+        CheckedFromBits::try_from(0x83),
+        Err(DecodeError::UnknownBits(UnknownBitsError::new(0x80)))
+    );
+    assert_eq!(
+        CheckedFromBits::try_from(0x02),
+        Err(DecodeError::Invalid("`B` requires `A`"))
+    );
+    assert_eq!(u8::from(CheckedFromBits::A), 0x01);
+}
+
+#[bitflag(u8, display)]
+#[derive(Debug, Clone, Copy, PartialEq)]
+enum DisplayFlags {
+    A = 1 << 0,
+    B = 1 << 1,
+}
+
+#[bitflag(u8, display, from_str = strict)]
+#[derive(Debug, Clone, Copy, PartialEq)]
+enum StrictDisplayFlags {
+    A = 1 << 0,
+    B = 1 << 1,
+}
+
+#[test]
+fn display_works() {
+    assert_eq!(DisplayFlags::empty().to_string(), "");
+    assert_eq!(DisplayFlags::all().to_string(), "A | B");
+    assert_eq!(DisplayFlags::from_bits_retain(0x81).to_string(), "A | 0x80");
+
+    let flags = StrictDisplayFlags::from_bits_retain(0x82);
+    assert_eq!(flags.to_string(), "B");
+    assert_eq!(
+        flags.to_string().parse::<StrictDisplayFlags>().unwrap(),
+        StrictDisplayFlags::B
+    );
+}
+
+#[bitflag(u32, subset_ord)]
+#[derive(Debug, Copy, Clone, PartialEq, Eq, PartialOrd)]
+enum SubsetFlags {
+    F1 = 1 << 0,
+    F2 = 1 << 1,
+    F3 = 1 << 2,
+}
+
+#[test]
+fn subset_ord_works() {
+    use std::cmp::Ordering;
+
+    let f1_f2 = SubsetFlags::F1 | SubsetFlags::F2;
+
+    assert_eq!(f1_f2.partial_cmp(&f1_f2), Some(Ordering::Equal));
+    assert!(SubsetFlags::F1 < f1_f2);
+    assert!(f1_f2 > SubsetFlags::F2);
+    assert!(SubsetFlags::empty() <= SubsetFlags::F3);
+    assert!(SubsetFlags::all() >= f1_f2);
+    assert_eq!(SubsetFlags::F1.partial_cmp(&SubsetFlags::F2), None);
+    assert_eq!(f1_f2.partial_cmp(&SubsetFlags::F3), None);
+    assert!(SubsetFlags::F3.partial_cmp(&f1_f2).is_none());
+}
+
+#[bitflag(u32, strict)]
+#[derive(Debug, Copy, Clone, PartialEq, Eq)]
+enum StrictFlags {
+    F1 = 1 << 0,
+    F2 = 1 << 1,
+}
+
+#[bitflag(u8, strict, width = 4)]
+#[derive(Debug, Copy, Clone, PartialEq, Eq)]
+enum StrictNarrowFlags {
+    A = 1 << 0,
+}
+
+#[test]
+fn strict_works() {
+    use bitflag_attr::Flags;
+
+    assert_eq!(StrictFlags::all_bits(), StrictFlags::all());
+    assert_eq!(StrictFlags::F1.not(), StrictFlags::F2);
+    assert_eq!(!StrictFlags::empty(), StrictFlags::all());
+    assert_eq!(StrictFlags::from(0xFF), StrictFlags::all());
+    assert_eq!(
+        <StrictFlags as Flags>::from_bits_retain(0xFF),
+        StrictFlags::all()
+    );
+    assert_eq!("F1 | 0x80".parse::<StrictFlags>().unwrap(), StrictFlags::F1);
+    assert_eq!(StrictFlags::from_bits(0x80), None);
+
+    // SAFETY: the bits are all known
+    let retained = unsafe { StrictFlags::from_bits_retain(0b11) };
+    assert_eq!(retained, StrictFlags::all());
+
+    let mut value = StrictFlags::F1;
+    value.toggle(StrictFlags::all());
+    assert!(!value.contains_unknown_bits());
+
+    let mut bits = StrictFlags::F1;
+    bits.set_bit(7, true);
+    bits.set_bit(1, true);
+    assert_eq!(bits, StrictFlags::all());
+    assert!(!bits.contains_unknown_bits());
+
+    let mut narrow = StrictNarrowFlags::A;
+    narrow.set_bit(3, true);
+    assert_eq!(narrow.bits(), 0b1);
+    assert_eq!(
+        format!("{:?}", !value),
+        "StrictFlags { flags: F1, bits: 0b00000000000000000000000000000001 }"
+    );
+}
+
+#[test]
+fn valid_works() {
+    use bitflag_attr::Valid;
+
+    let unknown = TestFlags::F1 | TestFlags::from_bits_retain(1 << 7);
+
+    assert!(Valid::new(unknown).is_none());
+    assert_eq!(
+        Valid::new(TestFlags::F1).map(Valid::into_inner),
+        Some(TestFlags::F1)
+    );
+    assert_eq!(*Valid::new_truncate(unknown), TestFlags::F1);
+    assert_eq!(Valid::<TestFlags>::from_bits(1 << 7), None);
+    assert_eq!(
+        *Valid::<TestFlags>::from_bits_truncate(!0),
+        TestFlags::all()
+    );
+    assert_eq!(Valid::try_new(unknown).unwrap_err().unknown_bits(), 1 << 7);
+
+    let f1 = Valid::new_truncate(TestFlags::F1);
+    let f2 = Valid::new_truncate(TestFlags::F2);
+    assert_eq!(*(f1 | f2), TestFlags::F1 | TestFlags::F2);
+    assert_eq!(*(f1 & f2), TestFlags::empty());
+    assert_eq!(*((f1 | f2) ^ f2), TestFlags::F1);
+    assert_eq!(*((f1 | f2) - f1), TestFlags::F2);
+    assert!(!(!f1).contains_unknown_bits());
+    assert!((f1 | f2).contains(TestFlags::F2));
+    assert_eq!(format!("{f1:?}"), format!("{:?}", TestFlags::F1));
+}
+
+#[bitflag(u32, not_retains)]
+#[derive(Debug, Copy, Clone, PartialEq, Eq)]
+enum NotRetainsFlags {
+    F1 = 1 << 0,
+    F2 = 1 << 1,
+}
+
+#[test]
+fn not_retains_works() {
+    assert_eq!((!NotRetainsFlags::F1).bits(), !1);
+    assert_eq!(
+        !!NotRetainsFlags::from_bits_retain(1 << 7),
+        NotRetainsFlags::from_bits_retain(1 << 7)
+    );
+    assert_eq!(NotRetainsFlags::F1.complement(), NotRetainsFlags::F2);
+    assert_eq!((!TestFlags::F1).bits(), (1 << 1) | (1 << 3) | (1 << 4));
+}
+
+#[bitflag(u32, nonzero)]
+#[derive(Debug, Copy, Clone, PartialEq, Eq)]
+enum NonZeroFlags {
+    F1 = 1 << 0,
+    F2 = 1 << 1,
+}
+
+#[test]
+fn nonzero_works() {
+    use bitflag_attr::NonEmpty;
+
+    const F1: Option<NonEmpty<NonZeroFlags>> = NonZeroFlags::F1.to_non_empty();
+
+    assert_eq!(
+        std::mem::size_of::<Option<NonEmpty<NonZeroFlags>>>(),
+        std::mem::size_of::<u32>()
+    );
+    assert_eq!(NonZeroFlags::empty().to_non_empty(), None);
+    assert_eq!(F1.unwrap().get(), NonZeroFlags::F1);
+    assert_eq!(NonZeroFlags::from(F1.unwrap()), NonZeroFlags::F1);
+
+    let unknown = NonEmpty::<NonZeroFlags>::from_bits_retain(1 << 7).unwrap();
+    assert_eq!(unknown.bits(), 1 << 7);
+    assert_eq!(unknown.non_zero_bits().get(), 1 << 7);
+    assert_eq!(NonEmpty::<NonZeroFlags>::from_bits_retain(0), None);
+    assert_eq!(
+        NonEmpty::new(NonZeroFlags::F2),
+        NonZeroFlags::F2.to_non_empty()
+    );
+    assert_eq!(
+        format!("{:?}", F1.unwrap()),
+        format!("{:?}", NonZeroFlags::F1)
+    );
+}
+
+#[bitflag(u32, consts)]
+#[derive(Debug, Copy, Clone, PartialEq, Eq)]
+enum HTTPConstsFlags {
+    Get = 1 << 0,
+    Post = 1 << 1,
+    GetOrPost = Get | Post,
+}
+
+#[bitflag(u32, consts = custom_consts)]
+#[derive(Debug, Copy, Clone, PartialEq, Eq)]
+enum CustomConstsFlags {
+    F1 = 1 << 0,
+    #[cfg(any())]
+    F2 = 1 << 1,
+}
+
+#[test]
+fn consts_works() {
+    use custom_consts::*;
+    use http_consts_flags_consts::*;
+
+    assert_eq!(Get | Post, HTTPConstsFlags::GetOrPost);
+    assert_eq!(GetOrPost, HTTPConstsFlags::all());
+    assert_eq!(F1, CustomConstsFlags::all());
+}
+
+mod sys {
+    pub const FLAG_READ: u32 = 1 << 0;
+    pub const FLAG_WRITE: u32 = 1 << 1;
+    pub const FLAG_EXEC: u32 = 1 << 2;
+}
+
+bitflag_attr::bitflag_from_consts! {
+    #[bitflag(u32)]
+    #[derive(Debug, Copy, Clone, PartialEq, Eq)]
+    enum FromConstsFlags {
+        sys::FLAG_READ,
+        sys::FLAG_WRITE,
+        /// Renamed flag
+        Exec = sys::FLAG_EXEC,
+        ReadWrite = sys::FLAG_READ | sys::FLAG_WRITE,
+    }
+}
+
+#[test]
+fn bitflag_from_consts_works() {
+    assert_eq!(FromConstsFlags::FLAG_READ.bits(), sys::FLAG_READ);
+    assert_eq!(FromConstsFlags::FLAG_WRITE.bits(), sys::FLAG_WRITE);
+    assert_eq!(FromConstsFlags::Exec.bits(), sys::FLAG_EXEC);
+    assert_eq!(
+        FromConstsFlags::ReadWrite,
+        FromConstsFlags::FLAG_READ | FromConstsFlags::FLAG_WRITE
+    );
+    assert_eq!(
+        FromConstsFlags::from_flag_name("FLAG_READ"),
+        Some(FromConstsFlags::FLAG_READ)
+    );
+    assert_eq!(FromConstsFlags::all().bits(), 0b111);
+}
+
+#[cfg(feature = "c-headers")]
+bitflag_attr::bitflag_from_header! {
+    #[bitflag(u32)]
+    #[header("tests/headers/epoll.h", prefix = "EPOLL")]
+    #[derive(Debug, Copy, Clone, PartialEq, Eq)]
+    enum EpollFlags {
+        EPOLL_READ_WRITE = EPOLLIN | EPOLLOUT,
+    }
+}
+
+#[cfg(feature = "c-headers")]
+#[test]
+fn bitflag_from_header_works() {
+    assert_eq!(EpollFlags::EPOLL_CLOEXEC.bits(), 0o2000000);
+    assert_eq!(EpollFlags::EPOLLIN.bits(), 0x001);
+    assert_eq!(EpollFlags::EPOLLOUT.bits(), 0x004);
+    assert_eq!(EpollFlags::EPOLLET.bits(), 1 << 31);
+    assert_eq!(
+        EpollFlags::EPOLL_READ_WRITE,
+        EpollFlags::EPOLLIN | EpollFlags::EPOLLOUT
+    );
+    assert_eq!(EpollFlags::from_flag_name("OTHER_FLAG"), None);
+    assert_eq!(<EpollFlags as bitflag_attr::Flags>::KNOWN_FLAGS.len(), 7);
+}
+
+#[bitflag(u32)]
+#[derive(Debug, Clone, Copy, PartialEq)]
+enum VersionedFlags {
+    Base = 1,
+    #[flag(since = "3")]
+    Major = 1 << 1,
+    #[flag(since = "3.11")]
+    Minor = 1 << 2,
+    #[cfg(any())]
+    #[flag(since = "4.0")]
+    Disabled = 1 << 3,
+    #[flag(since = "3.11.2")]
+    Patch = 1 << 4,
+}
+
+#[test]
+fn valid_for_version_works() {
+    use bitflag_attr::Version;
+
+    const AT_3_11: VersionedFlags = VersionedFlags::valid_for_version(Version::new(3, 11, 0));
+
+    assert_eq!(
+        VersionedFlags::valid_for_version(Version::new(2, 0, 0)),
+        VersionedFlags::Base
+    );
+    assert_eq!(
+        AT_3_11,
+        VersionedFlags::Base | VersionedFlags::Major | VersionedFlags::Minor
+    );
+    assert_eq!(
+        VersionedFlags::valid_for_version((4, 0).into()),
+        VersionedFlags::all()
+    );
+
+    assert_eq!(VersionedFlags::Base.since(), None);
+    assert_eq!(VersionedFlags::Major.since(), Some(Version::new(3, 0, 0)));
+    assert_eq!(VersionedFlags::Patch.since(), Some(Version::new(3, 11, 2)));
+    assert_eq!(
+        (VersionedFlags::Major | VersionedFlags::Minor).since(),
+        None
+    );
+}
+
+#[bitflag(u16)]
+#[deny_gaps]
+#[extra_valid_bits = 1 << 2]
+#[derive(Debug, Clone, Copy, PartialEq)]
+enum NoGaps {
+    A = 1,
+    B = 1 << 1,
+    D = 1 << 3,
+    #[cfg(any())]
+    Disabled = 1 << 8,
+    AB = A | B,
+}
+
+#[test]
+fn deny_gaps_works() {
+    assert_eq!(NoGaps::all().bits(), 0b1111);
+}
+
+#[bitflag(u8, auto_derive)]
+enum AutoDerived {
+    A = 1,
+    B = 1 << 1,
+}
+
+#[bitflag(u8, auto_derive = full)]
+#[derive(Debug, Clone)]
+enum AutoDerivedFull {
+    A = 1,
+    B = 1 << 1,
+}
+
+#[test]
+fn auto_derive_works() {
+    use std::collections::HashSet;
+
+    let flags = AutoDerived::A | AutoDerived::B;
+    let copy = flags;
+    assert_eq!(flags.bits(), copy.bits());
+
+    let set: HashSet<AutoDerivedFull> = [AutoDerivedFull::A, AutoDerivedFull::A].into();
+    assert_eq!(set.len(), 1);
+    assert_eq!(
+        AutoDerivedFull::A | AutoDerivedFull::B,
+        AutoDerivedFull::all()
+    );
+}
+
+#[bitflag(u8, iter_order = bits)]
+#[derive(Debug, Clone, Copy, PartialEq)]
+enum BitOrdered {
+    C = 1 << 2,
+    AB = A | B,
+    B = 1 << 1,
+    A = 1,
+    High = 1 << 7,
+}
+
+#[test]
+fn iter_order_works() {
+    let names: Vec<_> = BitOrdered::all()
+        .iter_names()
+        .map(|(name, _)| name)
+        .collect();
+    assert_eq!(names, ["A", "AB", "C", "High"]);
+
+    let mut text = String::new();
+    bitflag_attr::parser::to_writer(&(BitOrdered::C | BitOrdered::B), &mut text).unwrap();
+    assert_eq!(text, "B | C");
+}
+
+#[test]
+fn bit_works() {
+    const _: () = assert!(TestFlags::F1.bit(0));
+
+    let mut flags = TestFlags::F1;
+    assert!(!flags.bit(1));
+
+    flags.set_bit(1, true);
+    flags.set_bit(7, true);
+    assert_eq!(
+        flags,
+        TestFlags::F1 | TestFlags::F2 | TestFlags::from_bits_retain(1 << 7)
+    );
+    assert!(flags.bit(7));
+
+    flags.set_bit(0, false);
+    assert_eq!(flags.bits(), 0b1000_0010);
+}
+
+#[test]
+#[should_panic = "bit index out of range"]
+fn bit_out_of_range() {
+    TestFlags::F1.bit(32);
+}
+
+#[test]
+fn toggle_all_works() {
+    let mut flags = TestFlags::F1 | TestFlags::F3 | TestFlags::from_bits_retain(1 << 10);
+    flags.toggle_all();
+    assert_eq!(
+        flags,
+        TestFlags::F2 | TestFlags::F4 | TestFlags::from_bits_retain(1 << 10)
+    );
+
+    let mut flags = TestFlags::F2;
+    bitflag_attr::Flags::toggle_all(&mut flags);
+    assert_eq!(flags, TestFlags::F1 | TestFlags::F3 | TestFlags::F4);
+}
+
+#[bitflag(u32)]
+#[derive(Debug, Clone, Copy, PartialEq)]
+enum WideFlags {
+    A = 1,
+    Extra = 1 << 16,
+    B = 1 << 17,
+    AB = A | B,
+}
+
+#[bitflag(u8)]
+#[bitflag_widen(WideFlags)]
+#[derive(Debug, Clone, Copy, PartialEq)]
+enum NarrowFlags {
+    A = 1,
+    B = 1 << 1,
+    AB = A | B,
+}
+
+#[test]
+fn bitflag_widen_works() {
+    assert_eq!(
+        WideFlags::from(NarrowFlags::AB),
+        WideFlags::A | WideFlags::B
+    );
+    assert_eq!(
+        WideFlags::from(NarrowFlags::B | NarrowFlags::from_bits_retain(1 << 7)),
+        WideFlags::B | WideFlags::from_bits_retain(1 << 7)
+    );
+
+    assert_eq!(
+        NarrowFlags::try_from(WideFlags::A | WideFlags::B),
+        Ok(NarrowFlags::AB)
+    );
+    assert_eq!(
+        NarrowFlags::try_from(WideFlags::A | WideFlags::from_bits_retain(1 << 7)),
+        Ok(NarrowFlags::A | NarrowFlags::from_bits_retain(1 << 7))
+    );
+    assert_eq!(
+        NarrowFlags::try_from(WideFlags::A | WideFlags::Extra)
+            .unwrap_err()
+            .unknown_bits(),
+        1 << 16
+    );
+    assert!(NarrowFlags::try_from(WideFlags::from_bits_retain(1 << 30)).is_err());
+    assert!(NarrowFlags::try_from(WideFlags::from_bits_retain(1 << 1)).is_err());
+}
+
+#[bitflag(i16)]
+#[derive(Debug, Clone, Copy, PartialEq)]
+enum SignedWideFlags {
+    A = 1,
+    B = 1 << 9,
+}
+
+#[bitflag(i8)]
+#[bitflag_widen(SignedWideFlags)]
+#[extra_valid_bits = 1 << 6]
+#[derive(Debug, Clone, Copy, PartialEq)]
+enum SignedNarrowFlags {
+    A = 1,
+    B = 1 << 1,
+}
+
+#[test]
+fn bitflag_widen_keeps_unsigned_bits() {
+    // The sign bit of the narrow type is not extended over the bits of the wider one
+    let narrow = SignedNarrowFlags::B | SignedNarrowFlags::from_bits_retain(i8::MIN);
+    let wide = SignedWideFlags::from(narrow);
+    assert_eq!(
+        wide,
+        SignedWideFlags::B | SignedWideFlags::from_bits_retain(0x80)
+    );
+    assert_eq!(SignedNarrowFlags::try_from(wide), Ok(narrow));
+
+    // Bits only valid through `extra_valid_bits` are carried across
+    let narrow = SignedNarrowFlags::A | SignedNarrowFlags::from_bits_retain(1 << 6);
+    let wide = SignedWideFlags::from(narrow);
+    assert_eq!(
+        wide,
+        SignedWideFlags::A | SignedWideFlags::from_bits_retain(1 << 6)
+    );
+    assert_eq!(SignedNarrowFlags::try_from(wide), Ok(narrow));
+
+    assert!(SignedNarrowFlags::try_from(SignedWideFlags::from_bits_retain(i16::MIN)).is_err());
+}
+
+#[bitflag(u32)]
+#[derive(Debug, Clone, Copy, PartialEq)]
+enum LowLevelFlags {
+    F1 = 1 << 0,
+    F2 = 1 << 1,
+}
+
+#[test]
+fn cast_works() {
+    use bitflag_attr::Flags;
+
+    let flags = TestFlags::F1 | TestFlags::F2 | TestFlags::F4;
+
+    let low: LowLevelFlags = flags.cast();
+    assert_eq!(low.bits(), flags.bits());
+    assert_eq!(
+        flags.cast_truncate::<LowLevelFlags>(),
+        LowLevelFlags::F1 | LowLevelFlags::F2
+    );
+    assert_eq!(low.cast::<TestFlags>(), flags);
+
+    let strict: StrictFlags = flags.cast();
+    assert_eq!(strict, StrictFlags::F1 | StrictFlags::F2);
+    assert!(!strict.contains_unknown_bits());
+    assert_eq!(
+        bitflag_attr::ops::intersect_all::<StrictFlags>([]),
+        StrictFlags::all()
+    );
+}
+
+#[test]
+fn from_bits_unchecked_works() {
+    // SAFETY: Only known bits are set
+    let flags = unsafe { TestFlags::from_bits_unchecked(0b11) };
+    assert_eq!(flags, TestFlags::F1 | TestFlags::F2);
+}
+
+#[test]
+#[cfg(debug_assertions)]
+#[should_panic = "unknown bits set"]
+fn from_bits_unchecked_asserts_in_debug() {
+    // SAFETY: Not upheld on purpose, the debug assertion catches it
+    let _ = unsafe { TestFlags::from_bits_unchecked(1 << 20) };
+}
+
+#[bitflag(u32, width = 24)]
+#[derive(Debug, Clone, Copy, PartialEq)]
+enum Register24 {
+    Enable = 1 << 0,
+    Reset = 1 << 8,
+    Ready = 1 << 23,
+}
+
+#[test]
+fn width_works() {
+    use bitflag_attr::Flags;
+
+    assert_eq!(Register24::WIDTH, 24);
+    assert_eq!(Register24::all_bits().bits(), 0xFF_FFFF);
+    assert_eq!(<Register24 as Flags>::all_bits().bits(), 0xFF_FFFF);
+    assert!(Register24::from_bits_retain(0xFF_FFFF).is_all_bits());
+    assert_eq!(Register24::from_bits_retain(u32::MAX).bits(), 0xFF_FFFF);
+    assert_eq!(!Register24::Enable, Register24::Reset | Register24::Ready);
+
+    let flags = Register24::Enable | Register24::Ready;
+    assert_eq!(flags.to_le_bytes(), [0x01, 0x00, 0x80]);
+    assert_eq!(flags.to_be_bytes(), [0x80, 0x00, 0x01]);
+    assert_eq!(Register24::from_le_bytes([0x01, 0x00, 0x80]), flags);
+    assert_eq!(Register24::from_be_bytes([0x80, 0x00, 0x01]), flags);
+
+    assert!(Register24::Ready.bit(23));
+    assert!(format!("{flags:?}").contains(&format!("{:#026b}", flags.bits())));
+}
+
+#[test]
+#[should_panic = "bit index out of range"]
+fn width_bit_out_of_range() {
+    Register24::empty().bit(24);
+}
+
+#[test]
+fn bit_permutations_work() {
+    assert_eq!(TestFlags::F1.rotate_left(1), TestFlags::F2);
+    assert_eq!(TestFlags::F1.rotate_right(1).bits(), 1 << 31);
+    assert_eq!(TestFlags::F1.rotate_right(1).rotate_left(34), TestFlags::F2);
+    assert_eq!(TestFlags::F1.swap_bytes().bits(), 1 << 24);
+    assert_eq!(TestFlags::F1_3.reverse_bits().bits(), (1 << 31) | (1 << 28));
+    assert_eq!(SignedMode::Exec.rotate_right(1), SignedMode::Sticky);
+
+    // The bits are permuted within the width
+    assert_eq!(Register24::Ready.rotate_left(1), Register24::Enable);
+    assert_eq!(Register24::Enable.rotate_right(1), Register24::Ready);
+    assert_eq!(Register24::Reset.rotate_left(24), Register24::Reset);
+    assert_eq!(Register24::Enable.swap_bytes().bits(), 1 << 16);
+    assert_eq!(Register24::Enable.reverse_bits(), Register24::Ready);
+
+    // The bits moved to unknown positions are truncated
+    assert_eq!(StrictFlags::F2.rotate_left(1), StrictFlags::empty());
+    assert_eq!(StrictFlags::F1.rotate_left(1), StrictFlags::F2);
+}
+
+#[bitflag(i32)]
+#[derive(Debug, Clone, Copy, PartialEq)]
+enum SignedMode {
+    Exec = 1 << 0,
+    Sticky = 1 << 31,
+}
+
+#[test]
+fn signed_bits_unsigned_works() {
+    use bitflag_attr::Flags;
+
+    assert_eq!(SignedMode::Sticky.bits(), i32::MIN);
+    assert_eq!(SignedMode::Sticky.bits_unsigned(), 0x8000_0000_u32);
+    assert_eq!(
+        <SignedMode as Flags>::bits_unsigned(&SignedMode::Exec),
+        1_u32
+    );
+    assert_eq!(TestFlags::F1_3.bits_unsigned(), TestFlags::F1_3.bits());
+
+    let flags = SignedMode::Sticky | SignedMode::from_bits_retain(0x4000_0000);
+    let mut text = String::new();
+    bitflag_attr::parser::to_writer(&flags, &mut text).unwrap();
+    assert_eq!(text, "Sticky | 0x40000000");
+    assert_eq!("Sticky | 0x40000000".parse::<SignedMode>().unwrap(), flags);
+    assert_eq!(
+        "0xC0000001".parse::<SignedMode>().unwrap(),
+        flags | SignedMode::Exec
+    );
+    assert!("0x1C0000000".parse::<SignedMode>().is_err());
+}
+
+#[bitflag(u8, aggressive_inline)]
+#[derive(Debug, Clone, Copy, PartialEq)]
+enum InlineFlags {
+    A = 1 << 0,
+    B = 1 << 1,
+    AB = A | B,
+}
+
+#[test]
+fn aggressive_inline_works() {
+    let flags = InlineFlags::A | InlineFlags::B;
+    assert_eq!(flags, InlineFlags::AB);
+    assert!(flags.contains(InlineFlags::B));
+    assert_eq!(!InlineFlags::A, InlineFlags::B);
+    assert_eq!(InlineFlags::from_bits(0b100), None);
+    assert_eq!(InlineFlags::iter(&flags).count(), 2);
+}
+
+#[test]
+fn bit_index_works() {
+    assert_eq!(TestFlags::empty().lowest_bit_index(), None);
+    assert_eq!(TestFlags::empty().highest_bit_index(), None);
+    assert_eq!(TestFlags::F2.lowest_bit_index(), Some(1));
+    assert_eq!(TestFlags::F2.highest_bit_index(), Some(1));
+    assert_eq!(TestFlags::F1_3.lowest_bit_index(), Some(0));
+    assert_eq!(TestFlags::F1_3.highest_bit_index(), Some(3));
+
+    assert_eq!(SignedMode::Sticky.lowest_bit_index(), Some(31));
+    assert_eq!(SignedMode::all().highest_bit_index(), Some(31));
+}
+
+#[test]
+fn name_of_bit_works() {
+    assert_eq!(TestFlags::NAME_BY_BIT.len(), 32);
+    assert_eq!(TestFlags::name_of_bit(0), Some("F1"));
+    assert_eq!(TestFlags::name_of_bit(3), Some("F3"));
+    assert_eq!(TestFlags::name_of_bit(2), None);
+    assert_eq!(TestFlags::name_of_bit(32), None);
+
+    assert_eq!(SignedMode::name_of_bit(31), Some("Sticky"));
+    assert_eq!(OpenFlags::name_of_bit(6), Some("CREAT"));
+}
+
+#[test]
+fn max_text_len_works() {
+    assert_eq!(TestFlags::MAX_NAME_LEN, 4);
+    assert_eq!(OpenFlags::MAX_NAME_LEN, "CLOEXEC".len());
+
+    // Every flag, the separators and `0xFFFFFFFF`
+    assert_eq!(TestFlags::MAX_TEXT_LEN, 12 + 5 * 3 + 10);
+
+    let mut text = String::new();
+    bitflag_attr::parser::to_writer(&TestFlags::from_bits_retain(!0 ^ 0b100), &mut text).unwrap();
+    assert!(text.len() <= TestFlags::MAX_TEXT_LEN);
+
+    let mut text = String::new();
+    bitflag_attr::parser::to_writer(&OpenFlags::from_bits_retain(!0), &mut text).unwrap();
+    assert!(text.len() <= OpenFlags::MAX_TEXT_LEN);
+}
+
+#[test]
+fn as_bits_mut_works() {
+    extern "C" fn fill(out: *mut u32) {
+        unsafe { *out = 0b1_1001 };
+    }
+
+    let mut flags = TestFlags::empty();
+    fill(flags.as_bits_mut());
+    assert_eq!(flags, TestFlags::F1_3 | TestFlags::F4);
+
+    let mut flags = Register24::empty();
+    unsafe { *flags.as_bits_mut() = 1 << 23 };
+    assert_eq!(flags, Register24::Ready);
+}
+
+#[test]
+fn as_ref_and_borrow_works() {
+    use std::collections::BTreeMap;
+
+    let flags = TestFlags::F1 | TestFlags::F4;
+    let bits: &u32 = flags.as_ref();
+    assert_eq!(*bits, flags.bits());
+
+    let mut names = BTreeMap::new();
+    names.insert(TestFlags::F1_3, "F1_3");
+    names.insert(flags, "F1 | F4");
+    assert_eq!(names.get(&0b1001), Some(&"F1_3"));
+    assert_eq!(names.get(&0b10001), Some(&"F1 | F4"));
+    assert_eq!(names.get(&0b10), None);
+}
+
+// Flags types defined by a declarative macro, with the attributes, types and values passed as
+// fragments, which the macro receives wrapped in invisible groups
+macro_rules! define_flags {
+    (
+        $(#[$attr:meta])*
+        $vis:vis enum $name:ident: $ty:ty {
+            $($(#[$flag_attr:meta])* $flag:ident = $value:expr,)*
+        }
+    ) => {
+        #[bitflag($ty)]
+        $(#[$attr])*
+        $vis enum $name {
+            $($(#[$flag_attr])* $flag = $value,)*
+        }
+    };
+}
+
+define_flags! {
+    #[derive(Debug, Clone, Copy, PartialEq)]
+    #[extra_valid_bits = 1 << 7]
+    #[bitflag_convert(MacroTtFlags)]
+    enum MacroFlags: u8 {
+        /// The first flag.
+        A = 1,
+        B = 1 << 1,
+        AB = A | B,
+        #[flag(zero)]
+        None = 0,
+        #[flag(allow_stray_bits)]
+        Wide = AB | (1 << 3),
+    }
+}
+
+macro_rules! define_flags_tt {
+    ($($tt:tt)*) => {
+        #[bitflag(u8)]
+        $($tt)*
+    };
+}
+
+define_flags_tt! {
+    #[derive(Debug, Clone, Copy, PartialEq)]
+    enum MacroTtFlags {
+        A = 1,
+        B = 1 << 1,
+        AB = A | B,
+    }
+}
+
+#[test]
+fn macro_rules_definitions_works() {
+    assert_eq!(MacroFlags::AB, MacroFlags::A | MacroFlags::B);
+    assert_eq!(MacroFlags::Wide.bits(), 0b1011);
+    assert_eq!(MacroFlags::None.bits(), 0);
+    assert_eq!(MacroFlags::all().bits(), 0b1000_1011);
+    assert_eq!(
+        format!("{:?}", MacroFlags::AB),
+        "MacroFlags { flags: A | B, bits: 0b00000011 }"
+    );
+
+    assert_eq!(MacroTtFlags::AB, MacroTtFlags::A | MacroTtFlags::B);
+    assert_eq!(MacroTtFlags::from(MacroFlags::AB), MacroTtFlags::AB);
+
+    macro_rules! describe {
+        ($value:expr) => {
+            bitflag_attr::bitflag_match!($value, {
+                MacroFlags::A | MacroFlags::B => "both",
+                MacroFlags::A => "a",
+                _ => "other",
+            })
+        };
+    }
+
+    assert_eq!(describe!(MacroFlags::AB), "both");
+    assert_eq!(describe!(MacroFlags::A), "a");
+    assert_eq!(describe!(MacroFlags::B), "other");
+}
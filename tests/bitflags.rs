@@ -1,16 +1,37 @@
 #![allow(mixed_script_confusables, clippy::module_inception)]
+#![cfg_attr(nightly, feature(const_trait_impl, const_ops, adt_const_params))]
 #[path = "bitflags/all.rs"]
 mod all;
-// #[path = "bitflags/bitflags_match.rs"]
-// mod bitflags_match;
+#[path = "bitflags/bitflags_match.rs"]
+mod bitflags_match;
 #[path = "bitflags/bits.rs"]
 mod bits;
+#[path = "bitflags/builder.rs"]
+mod builder;
 #[path = "bitflags/complement.rs"]
 mod complement;
+#[path = "bitflags/conformance.rs"]
+mod conformance;
+#[cfg(nightly)]
+#[path = "bitflags/const_ops.rs"]
+mod const_ops;
+#[cfg(nightly)]
+#[path = "bitflags/const_param_ty.rs"]
+mod const_param_ty;
 #[path = "bitflags/contains.rs"]
 mod contains;
+#[path = "bitflags/debug_expand.rs"]
+mod debug_expand;
+#[path = "bitflags/debugger_visualizer.rs"]
+mod debugger_visualizer;
+#[cfg(feature = "serde")]
+#[path = "bitflags/deserialize.rs"]
+mod deserialize;
 #[path = "bitflags/difference.rs"]
 mod difference;
+#[cfg(feature = "differential")]
+#[path = "bitflags/differential.rs"]
+mod differential;
 #[path = "bitflags/empty.rs"]
 mod empty;
 #[path = "bitflags/eq.rs"]
@@ -27,8 +48,14 @@ mod from_bits;
 mod from_bits_retain;
 #[path = "bitflags/from_bits_truncate.rs"]
 mod from_bits_truncate;
+#[cfg(feature = "from-file")]
+#[path = "bitflags/from_file.rs"]
+mod from_file;
 #[path = "bitflags/from_name.rs"]
 mod from_name;
+#[cfg(feature = "fuzzing")]
+#[path = "bitflags/fuzzing.rs"]
+mod fuzzing;
 // #[path = "bitflags/insert.rs"]
 // mod insert;
 #[path = "bitflags/intersection.rs"]
@@ -41,12 +68,20 @@ mod is_all;
 mod is_empty;
 #[path = "bitflags/iter.rs"]
 mod iter;
+#[cfg(feature = "serde_json")]
+#[path = "bitflags/json_value.rs"]
+mod json_value;
+#[path = "bitflags/ops.rs"]
+mod ops;
 #[path = "bitflags/parser.rs"]
 mod parser;
 // #[path = "bitflags/remove.rs"]
 // mod remove;
 #[path = "bitflags/symmetric_difference.rs"]
 mod symmetric_difference;
+#[cfg(feature = "alloc")]
+#[path = "bitflags/to_map.rs"]
+mod to_map;
 #[path = "bitflags/truncate.rs"]
 mod truncate;
 #[path = "bitflags/union.rs"]
@@ -77,12 +112,14 @@ pub enum TestFlagsInvert {
 #[bitflag(u8)]
 #[derive(Clone, Copy, Debug, PartialEq, Eq, PartialOrd, Ord)]
 pub enum TestZero {
+    #[flag(zero)]
     ZERO = 0,
 }
 
 #[bitflag(u8)]
 #[derive(Clone, Copy, Debug, PartialEq, Eq, PartialOrd, Ord)]
 pub enum TestZeroOne {
+    #[flag(zero)]
     ZERO = 0,
     ONE = 1,
 }
@@ -101,7 +138,9 @@ pub enum TestEmpty {}
 #[bitflag(u8)]
 #[derive(Clone, Copy, Debug, PartialEq, Eq, PartialOrd, Ord)]
 pub enum TestOverlapping {
+    #[flag(allow_stray_bits)]
     AB = 1 | (1 << 1),
+    #[flag(allow_stray_bits)]
     BC = (1 << 1) | (1 << 2),
 }
 
@@ -1,30 +1,38 @@
-bitflags! {
-    #[derive(PartialEq)]
-    struct Flags: u8 {
-        const A = 1 << 0;
-        const B = 1 << 1;
-        const C = 1 << 2;
-        const D = 1 << 3;
-    }
+use bitflag_attr::{bitflag, bitflag_match};
+
+#[bitflag(u8)]
+#[derive(Clone, Copy, PartialEq)]
+enum Flags {
+    A = 1 << 0,
+    B = 1 << 1,
+    C = 1 << 2,
+    D = 1 << 3,
 }
 
 fn flag_to_string(flag: Flags) -> String {
-    bitflags_match!(flag, {
+    bitflag_match!(flag, {
         Flags::A => "A".to_string(),
         Flags::B => { "B".to_string() }
         Flags::C => "C".to_string(),
         Flags::D => "D".to_string(),
         Flags::A | Flags::B => "A or B".to_string(),
         Flags::A & Flags::B => { "A and B | empty".to_string() },
-        Flags::A ^ Flags::B => "A xor B".to_string(),
         Flags::A | Flags::B | Flags::C => "A or B or C".to_string(),
-        Flags::A & Flags::B & Flags::C => "A and B and C".to_string(),
-        Flags::A ^ Flags::B ^ Flags::C => "A xor B xor C".to_string(),
         Flags::A | Flags::B | Flags::C | Flags::D => "All flags".to_string(),
         _ => "Unknown combination".to_string()
     })
 }
 
+fn describe(flag: Flags) -> &'static str {
+    bitflag_match!(flag, {
+        Flags::empty() => "empty",
+        contains(Flags::A | Flags::B) => "contains A and B",
+        intersects(Flags::C | Flags::D) => "intersects C or D",
+        contains(Flags::A) => "contains A",
+        _ => "other",
+    })
+}
+
 #[test]
 fn test_single_flags() {
     assert_eq!(flag_to_string(Flags::A), "A");
@@ -54,7 +62,7 @@ fn test_and_operations() {
         flag_to_string(Flags::A & Flags::B & Flags::C),
         "A and B | empty"
     ); // Since A, B, and C are mutually exclusive, the result of A & B & C is 0 ==> A & B & C = 0000 (i.e., empty).
-       // However, in the bitflags_match! statement (actually is if {..} else if {..} .. else {..}),
+       // However, in the bitflag_match! statement (actually is if {..} else if {..} .. else {..}),
        // the "A & B = 0000" condition is listed first, so 0000 will match "A & B" first,
        // resulting in the output of the "A and B | empty" branch.
     assert_eq!(
@@ -91,3 +99,126 @@ fn test_empty_and_full_flags() {
     assert_eq!(flag_to_string(Flags::empty()), "A and B | empty");
     assert_eq!(flag_to_string(Flags::all()), "All flags");
 }
+
+#[test]
+fn test_contains_and_intersects() {
+    assert_eq!(describe(Flags::empty()), "empty");
+    assert_eq!(describe(Flags::A | Flags::B), "contains A and B");
+    assert_eq!(describe(Flags::A | Flags::B | Flags::C), "contains A and B");
+    assert_eq!(describe(Flags::A | Flags::D), "intersects C or D");
+    assert_eq!(describe(Flags::C), "intersects C or D");
+    assert_eq!(describe(Flags::A), "contains A");
+    assert_eq!(describe(Flags::B), "other");
+    assert_eq!(describe(Flags::from_bits_retain(1 << 7)), "other");
+}
+
+#[test]
+fn test_scrutinee_evaluated_once() {
+    let mut evaluations = 0;
+    let mut value = || {
+        evaluations += 1;
+        Flags::B
+    };
+
+    let res = bitflag_match!(value(), {
+        Flags::A => 1,
+        contains(Flags::C) => 2,
+        intersects(Flags::D) => 3,
+        _ => 4,
+    });
+
+    assert_eq!(res, 4);
+    assert_eq!(evaluations, 1);
+}
+
+#[test]
+fn test_binding() {
+    fn residual(flag: Flags) -> (u8, &'static str) {
+        bitflag_match!(flag, {
+            value @ Flags::A => (value.bits(), "exact"),
+            value @ contains(Flags::B) => {
+                let rest = value - Flags::B;
+                (rest.bits(), "contains")
+            }
+            value @ intersects(Flags::C | Flags::D) => (value.bits(), "intersects"),
+            rest @ _ => (rest.bits(), "default"),
+        })
+    }
+
+    assert_eq!(residual(Flags::A), (1, "exact"));
+    assert_eq!(residual(Flags::A | Flags::B), (1, "contains"));
+    assert_eq!(residual(Flags::B | Flags::C), (1 << 2, "contains"));
+    assert_eq!(
+        residual(Flags::C | Flags::D),
+        (1 << 2 | 1 << 3, "intersects")
+    );
+    assert_eq!(residual(Flags::empty()), (0, "default"));
+    assert_eq!(
+        residual(Flags::from_bits_retain(1 << 7)),
+        (1 << 7, "default")
+    );
+}
+
+#[test]
+fn test_binding_evaluated_once() {
+    let mut evaluations = 0;
+    let mut value = || {
+        evaluations += 1;
+        Flags::A | Flags::C
+    };
+
+    let res = bitflag_match!(value(), {
+        v @ contains(Flags::A) => v.bits(),
+        rest @ _ => rest.bits(),
+    });
+
+    assert_eq!(res, 1 | 1 << 2);
+    assert_eq!(evaluations, 1);
+}
+
+#[test]
+fn test_exhaustive_without_default() {
+    fn is_set(flag: Flags) -> bool {
+        bitflag_match!(flag, {
+            Flags::empty() => false,
+            intersects(Flags::all_bits()) => true,
+        })
+    }
+
+    fn kind(flag: Flags) -> &'static str {
+        bitflag_match!(flag, {
+            contains(Flags::A | Flags::B) => "both",
+            intersects(Flags::A | Flags::B) => "one",
+            intersects(Flags::all_bits()) => "other",
+            Flags::empty() => "none",
+        })
+    }
+
+    assert!(!is_set(Flags::empty()));
+    assert!(is_set(Flags::A));
+    assert!(is_set(Flags::from_bits_retain(1 << 7)));
+
+    assert_eq!(kind(Flags::A | Flags::B | Flags::C), "both");
+    assert_eq!(kind(Flags::B | Flags::D), "one");
+    assert_eq!(kind(Flags::C | Flags::D), "other");
+    assert_eq!(kind(Flags::empty()), "none");
+}
+
+#[test]
+fn test_crate_path() {
+    mod facade {
+        pub use bitflag_attr as flags;
+    }
+
+    let matched = |value: Flags| {
+        facade::flags::bitflag_match!(crate = facade::flags, value, {
+            contains(Flags::A | Flags::B) => "both",
+            intersects(Flags::A | Flags::B) => "one",
+            _ => "none",
+        })
+    };
+
+    assert_eq!(matched(Flags::A | Flags::B), "both");
+    assert_eq!(matched(Flags::B), "one");
+    assert_eq!(matched(Flags::C), "none");
+}
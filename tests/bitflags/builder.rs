@@ -0,0 +1,39 @@
+use super::*;
+
+use bitflag_attr::FlagsBuilder;
+
+#[test]
+fn cases() {
+    assert_eq!(TestFlags::empty(), FlagsBuilder::<TestFlags>::new().build());
+    assert_eq!(
+        TestFlags::A | TestFlags::C,
+        FlagsBuilder::new()
+            .set("A")
+            .and_then(|builder| builder.set_value("B", false))
+            .and_then(|builder| builder.set_value("C", true))
+            .unwrap()
+            .build()
+    );
+    assert_eq!(
+        TestFlags::B | TestFlags::from_bits_retain(1 << 3),
+        FlagsBuilder::from_flags(TestFlags::all() | TestFlags::from_bits_retain(1 << 3))
+            .unset("A")
+            .and_then(|builder| builder.unset("C"))
+            .unwrap()
+            .build()
+    );
+    assert_eq!(
+        TestFlags::all(),
+        FlagsBuilder::<TestFlags>::default()
+            .set("ABC")
+            .unwrap()
+            .build()
+    );
+
+    assert!(FlagsBuilder::<TestFlags>::new()
+        .set("D")
+        .unwrap_err()
+        .to_string()
+        .starts_with("unrecognized named flag"));
+    assert!(FlagsBuilder::<TestFlags>::new().set("").is_err());
+}
@@ -0,0 +1,77 @@
+use super::*;
+
+use bitflag_attr::{bitflag_test_suite, Flags};
+
+bitflag_test_suite!(test_flags, TestFlags);
+bitflag_test_suite!(test_flags_invert, TestFlagsInvert);
+bitflag_test_suite!(test_zero, TestZero);
+bitflag_test_suite!(test_zero_one, TestZeroOne);
+bitflag_test_suite!(test_unicode, TestUnicode);
+bitflag_test_suite!(test_empty, TestEmpty);
+bitflag_test_suite!(test_overlapping, TestOverlapping);
+bitflag_test_suite!(test_overlapping_full, TestOverlappingFull);
+bitflag_test_suite!(test_external, TestExternal);
+bitflag_test_suite!(test_external_full, TestExternalFull);
+
+#[bitflag(u8, strict)]
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum StrictFlags {
+    A = 1,
+    B = 1 << 1,
+    AB = A | B,
+}
+
+bitflag_test_suite!(strict_flags, StrictFlags);
+
+#[bitflag(i16)]
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum SignedFlags {
+    A = 1,
+    B = 1 << 1,
+    Sign = i16::MIN,
+}
+
+bitflag_test_suite!(signed_flags, SignedFlags);
+
+const fn requires_a(flags: &ValidatedFlags) -> Result<(), &'static str> {
+    if flags.contains(ValidatedFlags::B) && !flags.contains(ValidatedFlags::A) {
+        Err("`B` requires `A`")
+    } else {
+        Ok(())
+    }
+}
+
+#[bitflag(u8, validate = requires_a)]
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum ValidatedFlags {
+    A = 1,
+    B = 1 << 1,
+    C = 1 << 2,
+}
+
+bitflag_test_suite!(validated_flags, ValidatedFlags);
+
+#[derive(Clone, Copy)]
+pub struct ManualFlags(u16);
+
+impl Flags for ManualFlags {
+    const KNOWN_FLAGS: &'static [(&'static str, Self)] = &[
+        ("A", ManualFlags(1)),
+        ("B", ManualFlags(1 << 1)),
+        ("AB", ManualFlags(1 | (1 << 1))),
+    ];
+
+    const EXTRA_VALID_BITS: Self::Bits = 1 << 8;
+
+    type Bits = u16;
+
+    fn bits(&self) -> Self::Bits {
+        self.0
+    }
+
+    fn from_bits_retain(bits: Self::Bits) -> Self {
+        ManualFlags(bits)
+    }
+}
+
+bitflag_test_suite!(manual_flags, ManualFlags);
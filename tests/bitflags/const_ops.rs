@@ -0,0 +1,34 @@
+use bitflag_attr::bitflag;
+
+#[bitflag(u8, const_ops)]
+#[derive(Debug, Clone, Copy, PartialEq)]
+enum Flags {
+    A = 1,
+    B = 1 << 1,
+    C = 1 << 2,
+}
+
+const AB: Flags = Flags::A | Flags::B;
+const NOT_A: Flags = !Flags::A;
+const A: Flags = AB & Flags::A;
+const AC: Flags = AB ^ Flags::from_bits_retain(0b110);
+const B: Flags = AB - Flags::A;
+
+const ASSIGNED: Flags = {
+    let mut flags = Flags::A;
+    flags |= Flags::C;
+    flags &= !Flags::B;
+    flags ^= Flags::B;
+    flags -= Flags::A;
+    flags
+};
+
+#[test]
+fn operators_in_const_contexts() {
+    assert_eq!(AB.bits(), 0b011);
+    assert_eq!(NOT_A.bits(), 0b110);
+    assert_eq!(A.bits(), 0b001);
+    assert_eq!(AC.bits(), 0b101);
+    assert_eq!(B.bits(), 0b010);
+    assert_eq!(ASSIGNED.bits(), 0b110);
+}
@@ -0,0 +1,27 @@
+use bitflag_attr::bitflag;
+
+#[bitflag(u8, const_param_ty)]
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum OpenFlags {
+    Read = 1,
+    Write = 1 << 1,
+}
+
+fn open<const F: OpenFlags>() -> OpenFlags {
+    F
+}
+
+struct Capability<const F: OpenFlags>;
+
+impl<const F: OpenFlags> Capability<F> {
+    fn can_write(&self) -> bool {
+        F.contains(OpenFlags::Write)
+    }
+}
+
+#[test]
+fn flags_as_const_generic_parameters() {
+    assert_eq!(open::<{ OpenFlags::Read }>(), OpenFlags::Read);
+    assert!(Capability::<{ OpenFlags::all() }>.can_write());
+    assert!(!Capability::<{ OpenFlags::Read }>.can_write());
+}
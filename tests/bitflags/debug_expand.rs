@@ -0,0 +1,62 @@
+use bitflag_attr::bitflag;
+
+#[bitflag(u8, debug_expand = "target/tests/debug_expand.rs")]
+#[derive(Debug, Clone, Copy, PartialEq)]
+enum Expanded {
+    A = 1 << 0,
+    B = 1 << 1,
+    AB = A | B,
+}
+
+#[test]
+fn writes_expansion() {
+    let path = concat!(env!("CARGO_MANIFEST_DIR"), "/target/tests/debug_expand.rs");
+    let source = std::fs::read_to_string(path).unwrap();
+
+    assert!(source.starts_with("// Code generated by `#[bitflag]` for `Expanded`\n"));
+    assert!(source.contains("\nstruct Expanded(u8)"));
+    assert!(source.contains("\n    const A: Self = Self(1 << 0);\n"));
+    assert!(source.contains("\n    pub const fn bits(&self) -> u8 {\n        self.0\n    }\n"));
+
+    assert_eq!(Expanded::A | Expanded::B, Expanded::AB);
+}
+
+#[bitflag(u8, msrv = "1.65", parse_error, debug_expand = "target/tests/msrv_expand.rs")]
+#[derive(Debug, Clone, Copy, PartialEq)]
+enum OldCompiler {
+    A = 1 << 0,
+    B = 1 << 1,
+}
+
+#[bitflag(u8, parse_error, debug_expand = "target/tests/no_msrv_expand.rs")]
+#[derive(Debug, Clone, Copy, PartialEq)]
+enum NewCompiler {
+    A = 1 << 0,
+    B = 1 << 1,
+}
+
+#[test]
+fn msrv_uses_fallbacks() {
+    let path = concat!(env!("CARGO_MANIFEST_DIR"), "/target/tests/msrv_expand.rs");
+    let old = std::fs::read_to_string(path).unwrap();
+    let path = concat!(env!("CARGO_MANIFEST_DIR"), "/target/tests/no_msrv_expand.rs");
+    let new = std::fs::read_to_string(path).unwrap();
+
+    assert!(old.contains("\n    pub fn set(&mut self, other: Self) {\n"));
+    assert!(!old.contains("::core::error::Error"));
+
+    assert_eq!(
+        new.contains("\n    pub const fn set(&mut self, other: Self) {\n"),
+        cfg!(feature = "const-mut-ref")
+    );
+    assert_eq!(
+        new.contains("::core::error::Error"),
+        cfg!(feature = "core-error")
+    );
+
+    let mut value = OldCompiler::A;
+    value.set(OldCompiler::B);
+    assert_eq!(value, OldCompiler::A | OldCompiler::B);
+    assert!("C".parse::<OldCompiler>().is_err());
+    assert!("C".parse::<NewCompiler>().is_err());
+}
@@ -0,0 +1,32 @@
+use bitflag_attr::bitflag;
+
+#[bitflag(
+    u8,
+    debugger_visualizer = "target/tests/visualizers",
+    debugger_module = "debugger_visualizer"
+)]
+#[derive(Debug, Clone, Copy, PartialEq)]
+enum Visualized {
+    A = 1 << 0,
+    B = 1 << 1,
+    AB = A | B,
+    C = 1 << 3,
+}
+
+#[test]
+fn writes_visualizers() {
+    let dir = concat!(env!("CARGO_MANIFEST_DIR"), "/target/tests/visualizers");
+
+    let natvis = std::fs::read_to_string(format!("{dir}/Visualized.natvis")).unwrap();
+    assert!(natvis.contains(r#"<Type Name="bitflags::debugger_visualizer::Visualized">"#));
+    assert!(natvis.contains(r#"<Item Name="AB">(__0 &amp; 0x3) == 0x3</Item>"#));
+
+    for script in ["Visualized_gdb.py", "Visualized_lldb.py"] {
+        let source = std::fs::read_to_string(format!("{dir}/{script}")).unwrap();
+        assert!(source.contains(
+            "FLAGS = [\n    (\"A\", 0x1),\n    (\"B\", 0x2),\n    (\"AB\", 0x3),\n    (\"C\", 0x8),\n]\n"
+        ));
+    }
+
+    assert_eq!(Visualized::A | Visualized::B, Visualized::AB);
+}
@@ -0,0 +1,157 @@
+use bitflag_attr::bitflag;
+use serde::de::{value::Error, Deserializer, Visitor};
+use serde::{Deserialize, Serialize};
+
+#[bitflag(u8)]
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+enum Retained {
+    A = 1,
+    B = 1 << 1,
+}
+
+#[bitflag(u8, deserialize_unknown_bits = truncate)]
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+enum Truncated {
+    A = 1,
+    B = 1 << 1,
+}
+
+#[bitflag(u8, deserialize_unknown_bits = deny)]
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+enum Denied {
+    A = 1,
+    B = 1 << 1,
+}
+
+const fn requires_a(flags: &Validated) -> Result<(), &'static str> {
+    if flags.contains(Validated::B) && !flags.contains(Validated::A) {
+        Err("`B` requires `A`")
+    } else {
+        Ok(())
+    }
+}
+
+#[bitflag(u8, deserialize_unknown_bits = deny, validate = requires_a)]
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+enum Validated {
+    A = 1,
+    B = 1 << 1,
+}
+
+// The unknown bits are invalid, so the values with unknown bits only pass once they are truncated
+const fn no_unknown_bits(flags: &TruncatedValidated) -> Result<(), &'static str> {
+    if flags.bits() & !0b11 != 0 {
+        Err("unknown bits set")
+    } else {
+        Ok(())
+    }
+}
+
+#[bitflag(u8, deserialize_unknown_bits = truncate, validate = no_unknown_bits)]
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+enum TruncatedValidated {
+    A = 1,
+    B = 1 << 1,
+}
+
+/// A deserializer of bits that isn't human-readable, like the binary formats.
+struct Compact(u8);
+
+impl<'de> Deserializer<'de> for Compact {
+    type Error = Error;
+
+    fn deserialize_any<V: Visitor<'de>>(self, visitor: V) -> Result<V::Value, Error> {
+        visitor.visit_u8(self.0)
+    }
+
+    fn is_human_readable(&self) -> bool {
+        false
+    }
+
+    serde::forward_to_deserialize_any! {
+        bool i8 i16 i32 i64 i128 u8 u16 u32 u64 u128 f32 f64 char str string bytes byte_buf
+        option unit unit_struct newtype_struct seq tuple tuple_struct map struct enum identifier
+        ignored_any
+    }
+}
+
+#[test]
+fn retain_keeps_unknown_bits() {
+    let flags = Retained::A | Retained::from_bits_retain(0x80);
+    let text = serde_json::to_string(&flags).unwrap();
+
+    assert_eq!(text, "\"A | 0x80\"");
+    assert_eq!(serde_json::from_str::<Retained>(&text).unwrap(), flags);
+    assert_eq!(Retained::deserialize(Compact(0x81)).unwrap(), flags);
+}
+
+#[test]
+fn truncate_unsets_unknown_bits() {
+    let text = serde_json::to_string(&(Truncated::A | Truncated::from_bits_retain(0x80))).unwrap();
+
+    assert_eq!(
+        serde_json::from_str::<Truncated>(&text).unwrap(),
+        Truncated::A
+    );
+    assert_eq!(Truncated::deserialize(Compact(0x81)).unwrap(), Truncated::A);
+
+    let text = serde_json::to_string(&Truncated::all()).unwrap();
+    assert_eq!(
+        serde_json::from_str::<Truncated>(&text).unwrap(),
+        Truncated::all()
+    );
+}
+
+#[test]
+fn truncate_validates_truncated_value() {
+    assert_eq!(
+        serde_json::from_str::<TruncatedValidated>("\"A | 0x80\"").unwrap(),
+        TruncatedValidated::A
+    );
+    assert_eq!(
+        TruncatedValidated::deserialize(Compact(0x81)).unwrap(),
+        TruncatedValidated::A
+    );
+}
+
+#[test]
+fn deny_rejects_unknown_bits() {
+    let text = serde_json::to_string(&(Denied::A | Denied::from_bits_retain(0x80))).unwrap();
+    let error = serde_json::from_str::<Denied>(&text).unwrap_err();
+    assert!(
+        error.to_string().starts_with("unknown bits set: 0x80"),
+        "{error}"
+    );
+
+    let error = Denied::deserialize(Compact(0x81)).unwrap_err();
+    assert_eq!(error.to_string(), "unknown bits set: 0x80");
+
+    let text = serde_json::to_string(&Denied::all()).unwrap();
+    assert_eq!(
+        serde_json::from_str::<Denied>(&text).unwrap(),
+        Denied::all()
+    );
+    assert_eq!(Denied::deserialize(Compact(0b11)).unwrap(), Denied::all());
+}
+
+#[test]
+fn deny_reports_invalid_values() {
+    let error = Validated::deserialize(Compact(0b10)).unwrap_err();
+    assert_eq!(error.to_string(), "invalid flags value: `B` requires `A`");
+
+    let error = Validated::deserialize(Compact(0x82)).unwrap_err();
+    assert_eq!(error.to_string(), "unknown bits set: 0x80");
+
+    let error = serde_json::from_str::<Validated>("\"B\"").unwrap_err();
+    assert!(
+        error
+            .to_string()
+            .starts_with("invalid flags value: `B` requires `A`"),
+        "{error}"
+    );
+
+    assert_eq!(
+        Validated::deserialize(Compact(0b11)).unwrap(),
+        Validated::all()
+    );
+}
@@ -0,0 +1,124 @@
+use super::*;
+
+use core::fmt;
+
+use bitflag_attr::{
+    parser,
+    testing::differential::{check_differential, Reference},
+    Flags,
+};
+
+/// A reference implementation over the `Flags` trait, with an optional deliberate difference.
+#[derive(Clone, Copy)]
+struct Mirror<B, const BROKEN: bool>(B);
+
+impl<B: Flags, const BROKEN: bool> Reference for Mirror<B, BROKEN>
+where
+    B::Bits: parser::ParseHex,
+{
+    type Bits = B::Bits;
+
+    fn from_bits_retain(bits: Self::Bits) -> Self {
+        Self(B::from_bits_retain(bits))
+    }
+
+    fn bits(&self) -> Self::Bits {
+        self.0.bits()
+    }
+
+    fn from_bits(bits: Self::Bits) -> Option<Self> {
+        B::from_bits(bits).map(Self)
+    }
+
+    fn from_bits_truncate(bits: Self::Bits) -> Self {
+        Self(B::from_bits_truncate(bits))
+    }
+
+    fn is_empty(&self) -> bool {
+        self.0.is_empty()
+    }
+
+    fn is_all(&self) -> bool {
+        self.0.is_all()
+    }
+
+    fn contains(&self, other: Self) -> bool {
+        self.0.contains(other.0)
+    }
+
+    fn intersects(&self, other: Self) -> bool {
+        self.0.intersects(other.0)
+    }
+
+    fn union(self, other: Self) -> Self {
+        Self(self.0.union(other.0))
+    }
+
+    fn intersection(self, other: Self) -> Self {
+        Self(self.0.intersection(other.0))
+    }
+
+    fn difference(self, other: Self) -> Self {
+        Self(self.0.difference(other.0))
+    }
+
+    fn symmetric_difference(self, other: Self) -> Self {
+        Self(self.0.symmetric_difference(other.0))
+    }
+
+    fn complement(self) -> Self {
+        if BROKEN {
+            // Doesn't truncate the unknown bits
+            Self(B::from_bits_retain(!self.0.bits()))
+        } else {
+            Self(self.0.complement())
+        }
+    }
+
+    fn for_each_name(&self, f: &mut dyn FnMut(&str)) {
+        for (name, _) in self.0.iter_names() {
+            f(name);
+        }
+    }
+
+    fn to_writer(&self, writer: &mut dyn fmt::Write) -> fmt::Result {
+        parser::to_writer(&self.0, writer)
+    }
+
+    fn to_writer_truncate(&self, writer: &mut dyn fmt::Write) -> fmt::Result {
+        parser::to_writer_truncate(&self.0, writer)
+    }
+
+    fn to_writer_strict(&self, writer: &mut dyn fmt::Write) -> fmt::Result {
+        parser::to_writer_strict(&self.0, writer)
+    }
+
+    fn from_text(input: &str) -> Option<Self> {
+        parser::from_text(input).ok().map(Self)
+    }
+
+    fn from_text_truncate(input: &str) -> Option<Self> {
+        parser::from_text_truncate(input).ok().map(Self)
+    }
+
+    fn from_text_strict(input: &str) -> Option<Self> {
+        parser::from_text_strict(input).ok().map(Self)
+    }
+}
+
+#[test]
+fn same_behavior() {
+    check_differential::<TestFlags, Mirror<TestFlags, false>>(1, 100);
+    check_differential::<TestZero, Mirror<TestZero, false>>(2, 100);
+    check_differential::<TestUnicode, Mirror<TestUnicode, false>>(3, 100);
+    check_differential::<TestEmpty, Mirror<TestEmpty, false>>(4, 100);
+    check_differential::<TestOverlapping, Mirror<TestOverlapping, false>>(5, 100);
+    check_differential::<TestExternal, Mirror<TestExternal, false>>(6, 100);
+    check_differential::<TestExternalFull, Mirror<TestExternalFull, false>>(7, 100);
+}
+
+#[test]
+#[should_panic = "`0x0.complement()` differs: 0x7 is not the reference 0xFF"]
+fn different_behavior() {
+    check_differential::<TestFlags, Mirror<TestFlags, true>>(1, 100);
+}
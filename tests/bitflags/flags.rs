@@ -1,6 +1,6 @@
 use super::*;
 
-// use bitflag_attr::Flags;
+use bitflag_attr::Flags;
 
 #[test]
 fn cases() {
@@ -22,6 +22,20 @@ fn cases() {
     assert_eq!(0, TestEmpty::KNOWN_FLAGS.len());
 }
 
+#[test]
+fn iter_flags() {
+    fn names<T: Flags>() -> Vec<&'static str> {
+        T::ITER_FLAGS.iter().map(|(name, _)| *name).collect()
+    }
+
+    assert_eq!(vec!["A", "B", "C", "ABC"], names::<TestFlags>());
+    assert_eq!(Vec::<&str>::new(), names::<TestZero>());
+    assert_eq!(vec!["ONE"], names::<TestZeroOne>());
+    assert_eq!(vec!["AB", "BC"], names::<TestOverlapping>());
+    assert_eq!(vec!["A", "D"], names::<TestOverlappingFull>());
+    assert_eq!(Vec::<&str>::new(), names::<TestEmpty>());
+}
+
 mod external {
     use super::*;
 
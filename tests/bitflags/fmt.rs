@@ -106,6 +106,26 @@ fn cases() {
     );
 }
 
+#[test]
+fn alternate_debug() {
+    assert_eq!(
+        "TestFlags {\n    flags: 0x0,\n    bits: 0b00000000,\n}",
+        format!("{:#?}", TestFlags::empty())
+    );
+    assert_eq!(
+        "TestFlags {\n    flags: [\n        A,\n    ],\n    bits: 0b00000001,\n}",
+        format!("{:#?}", TestFlags::A)
+    );
+    assert_eq!(
+        "TestExternal {\n    flags: [\n        A,\n        B,\n        0x8,\n    ],\n    bits: 0b00001011,\n}",
+        format!("{:#?}", TestExternal::from_bits_retain(1 | (1 << 1) | (1 << 3)))
+    );
+    assert_eq!(
+        "TestExternalFull {\n    flags: [\n        0xFF,\n    ],\n    bits: 0b11111111,\n}",
+        format!("{:#?}", TestExternalFull::all())
+    );
+}
+
 #[track_caller]
 fn case<T>(value: T, debug: &str, uhex: &str, lhex: &str, oct: &str, bin: &str)
 where
@@ -0,0 +1,39 @@
+use bitflag_attr::{bitflag, Flags};
+
+#[bitflag(u8, from_file = "tests/bitflags/from_file.toml")]
+#[derive(Debug, Clone, Copy, PartialEq)]
+enum FromToml {
+    Exec = 1 << 2,
+}
+
+#[bitflag(u64, from_file = "tests/bitflags/from_file.json")]
+#[derive(Debug, Clone, Copy, PartialEq)]
+enum FromJson {}
+
+#[test]
+fn reads_toml() {
+    assert_eq!(FromToml::Read.bits(), 1);
+    assert_eq!(FromToml::Write.bits(), 1 << 1);
+    assert_eq!(FromToml::ReadWrite, FromToml::Read | FromToml::Write);
+
+    let names: Vec<_> = FromToml::KNOWN_FLAGS
+        .iter()
+        .map(|(name, _)| *name)
+        .collect();
+    assert_eq!(names, ["Exec", "Read", "Write", "ReadWrite"]);
+
+    assert_eq!(
+        <FromToml as Flags>::DOCS,
+        &[
+            ("Read", "Allows reading."),
+            ("ReadWrite", "Allows reading and writing.")
+        ]
+    );
+}
+
+#[test]
+fn reads_json() {
+    assert_eq!(FromJson::Low.bits(), 1);
+    assert_eq!(FromJson::High.bits(), 1 << 63);
+    assert_eq!("Low | High".parse::<FromJson>().unwrap(), FromJson::all());
+}
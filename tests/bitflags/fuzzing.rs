@@ -0,0 +1,54 @@
+use super::*;
+
+use bitflag_attr::{bitflag, fuzzing::fuzz_parser_roundtrip};
+
+#[bitflag(i16)]
+#[derive(Clone, Copy)]
+enum SignedFlags {
+    A = 1 << 0,
+    B = 1 << 1,
+    Sign = i16::MIN,
+}
+
+const INPUTS: &[&[u8]] = &[
+    b"",
+    b" ",
+    b"|",
+    b"A",
+    b"A | B",
+    b"A|0x80",
+    b"0x",
+    b"0xFF",
+    b"0x1FF",
+    b"-0x1",
+    b"A || B",
+    b"a | b",
+    b"\xFF\xFE",
+    b"\x80\x00",
+    "\u{1f600}".as_bytes(),
+];
+
+#[test]
+fn parser_roundtrip() {
+    for input in INPUTS {
+        fuzz_parser_roundtrip::<TestFlags>(input);
+        fuzz_parser_roundtrip::<TestZero>(input);
+        fuzz_parser_roundtrip::<TestUnicode>(input);
+        fuzz_parser_roundtrip::<TestEmpty>(input);
+        fuzz_parser_roundtrip::<TestOverlapping>(input);
+        fuzz_parser_roundtrip::<TestExternal>(input);
+        fuzz_parser_roundtrip::<TestExternalFull>(input);
+        fuzz_parser_roundtrip::<SignedFlags>(input);
+    }
+}
+
+#[test]
+#[cfg(not(miri))] // Very slow in miri
+fn parser_roundtrip_all_bytes() {
+    for a in 0u8..=255 {
+        for b in 0u8..=255 {
+            fuzz_parser_roundtrip::<TestFlags>(&[a, b]);
+            fuzz_parser_roundtrip::<SignedFlags>(&[a, b]);
+        }
+    }
+}
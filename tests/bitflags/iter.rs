@@ -207,3 +207,236 @@ mod iter_names {
         );
     }
 }
+
+mod for_each_set {
+    use super::*;
+
+    #[test]
+    fn cases() {
+        case(TestFlags::empty(), |f, cb| f.for_each_set(cb));
+        case(TestFlags::A, |f, cb| f.for_each_set(cb));
+        case(
+            TestFlags::ABC | TestFlags::from_bits_retain(1 << 3),
+            |f, cb| f.for_each_set(cb),
+        );
+        case(TestFlagsInvert::ABC, |f, cb| f.for_each_set(cb));
+        case(TestZero::ZERO, |f, cb| f.for_each_set(cb));
+        case(TestOverlappingFull::A | TestOverlappingFull::D, |f, cb| {
+            f.for_each_set(cb)
+        });
+    }
+
+    #[track_caller]
+    fn case<T: Flags + std::fmt::Debug>(
+        value: T,
+        inherent: impl FnOnce(&T, &mut dyn FnMut(&'static str, T)),
+    ) where
+        T::Bits: std::fmt::Debug + PartialEq,
+    {
+        let mut names = Vec::new();
+        inherent(&value, &mut |name, flag| names.push((name, flag.bits())));
+
+        assert_eq!(
+            value
+                .iter_names()
+                .map(|(n, f)| (n, f.bits()))
+                .collect::<Vec<_>>(),
+            names,
+            "{:?}.for_each_set()",
+            value
+        );
+    }
+}
+
+mod any_set {
+    use super::*;
+
+    #[test]
+    fn cases() {
+        assert!(!TestFlags::empty().any_set(|_| true));
+        assert!(TestFlags::empty().all_set(|_| false));
+
+        let abc = TestFlags::ABC | TestFlags::from_bits_retain(1 << 3);
+        assert!(abc.any_set(|f| f == TestFlags::B));
+        assert!(!abc.any_set(|f| f.contains_unknown_bits()));
+        assert!(abc.all_set(|f| f.bits().count_ones() == 1));
+        assert!(!abc.all_set(|f| f != TestFlags::C));
+
+        // The flags are checked in the order of `iter_names`, stopping at the first match
+        let mut checked = Vec::new();
+        assert!(abc.any_set(|f| {
+            checked.push(f);
+            f == TestFlags::B
+        }));
+        assert_eq!(checked, [TestFlags::A, TestFlags::B]);
+
+        // Flags fully overlapping a previous one are skipped
+        let mut count = 0;
+        assert!(TestOverlappingFull::all().all_set(|_| {
+            count += 1;
+            true
+        }));
+        assert_eq!(count, 2);
+
+        assert!(!TestZero::ZERO.any_set(|_| true));
+    }
+}
+
+mod iter_unknown_bits {
+    use super::*;
+
+    #[test]
+    fn cases() {
+        case(&[], TestFlags::empty(), TestFlags::iter_unknown_bits);
+        case(&[], TestFlags::all(), TestFlags::iter_unknown_bits);
+
+        case(
+            &[1 << 3],
+            TestFlags::A | TestFlags::from_bits_retain(1 << 3),
+            TestFlags::iter_unknown_bits,
+        );
+        case(
+            &[1 << 3, 1 << 5, 1 << 7],
+            TestFlags::ABC | TestFlags::from_bits_retain(0b1010_1000),
+            TestFlags::iter_unknown_bits,
+        );
+
+        case(
+            &[],
+            TestExternal::from_bits_retain(!0),
+            TestExternal::iter_unknown_bits,
+        );
+        case(
+            &[1 << 7],
+            TestZeroOne::from_bits_retain(1 << 7),
+            TestZeroOne::iter_unknown_bits,
+        );
+    }
+
+    #[track_caller]
+    fn case<T: Flags + std::fmt::Debug>(
+        expected: &[T::Bits],
+        value: T,
+        inherent: impl FnOnce(&T) -> bitflag_attr::iter::IterUnknownBits<T>,
+    ) where
+        T::Bits: std::fmt::Debug + PartialEq,
+    {
+        assert_eq!(
+            expected,
+            inherent(&value).map(|f| f.bits()).collect::<Vec<_>>(),
+            "{:?}.iter_unknown_bits()",
+            value
+        );
+        assert_eq!(
+            expected,
+            Flags::iter_unknown_bits(&value)
+                .map(|f| f.bits())
+                .collect::<Vec<_>>(),
+            "Flags::iter_unknown_bits({:?})",
+            value
+        );
+    }
+}
+
+mod iter_states {
+    use super::*;
+
+    #[test]
+    fn cases() {
+        case(
+            &[("A", false), ("B", false), ("C", false), ("ABC", false)],
+            TestFlags::empty(),
+            TestFlags::iter_states,
+        );
+        case(
+            &[("A", true), ("B", false), ("C", true), ("ABC", false)],
+            TestFlags::A | TestFlags::C | TestFlags::from_bits_retain(1 << 3),
+            TestFlags::iter_states,
+        );
+        case(
+            &[("A", true), ("B", true), ("C", true), ("ABC", true)],
+            TestFlags::all(),
+            TestFlags::iter_states,
+        );
+
+        case(
+            &[("ZERO", true), ("ONE", false)],
+            TestZeroOne::empty(),
+            TestZeroOne::iter_states,
+        );
+        case(&[], TestEmpty::all(), TestEmpty::iter_states);
+    }
+
+    #[track_caller]
+    fn case<T: Flags + std::fmt::Debug>(
+        expected: &[(&'static str, bool)],
+        value: T,
+        inherent: impl FnOnce(&T) -> bitflag_attr::iter::IterStates<T>,
+    ) {
+        assert_eq!(
+            expected,
+            inherent(&value).collect::<Vec<_>>(),
+            "{:?}.iter_states()",
+            value
+        );
+        assert_eq!(
+            expected,
+            Flags::iter_states(&value).collect::<Vec<_>>(),
+            "Flags::iter_states({:?})",
+            value
+        );
+        assert_eq!(expected.len(), Flags::iter_states(&value).len());
+    }
+}
+
+mod iter_missing {
+    use super::*;
+
+    #[test]
+    fn cases() {
+        case(
+            &[("A", 1), ("B", 1 << 1), ("C", 1 << 2), ("ABC", 0b111)],
+            TestFlags::empty(),
+            TestFlags::iter_missing,
+        );
+        case(
+            &[("B", 1 << 1), ("ABC", 0b111)],
+            TestFlags::A | TestFlags::C | TestFlags::from_bits_retain(1 << 3),
+            TestFlags::iter_missing,
+        );
+        case(&[], TestFlags::all(), TestFlags::iter_missing);
+
+        case(
+            &[("ONE", 1)],
+            TestZeroOne::empty(),
+            TestZeroOne::iter_missing,
+        );
+        case(&[], TestEmpty::all(), TestEmpty::iter_missing);
+    }
+
+    #[track_caller]
+    fn case<T: Flags + std::fmt::Debug>(
+        expected: &[(&'static str, T::Bits)],
+        value: T,
+        inherent: impl FnOnce(&T) -> bitflag_attr::iter::IterMissing<T>,
+    ) where
+        T::Bits: std::fmt::Debug + PartialEq,
+    {
+        assert_eq!(
+            expected,
+            inherent(&value)
+                .map(|(n, f)| (n, f.bits()))
+                .collect::<Vec<_>>(),
+            "{:?}.iter_missing()",
+            value
+        );
+        assert_eq!(
+            expected,
+            Flags::iter_missing(&value)
+                .map(|(n, f)| (n, f.bits()))
+                .collect::<Vec<_>>(),
+            "Flags::iter_missing({:?})",
+            value
+        );
+    }
+}
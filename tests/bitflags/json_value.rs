@@ -0,0 +1,48 @@
+use bitflag_attr::bitflag;
+use serde::{Deserialize, Serialize};
+use serde_json::json;
+
+#[bitflag(u8)]
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+enum Flags {
+    A = 1,
+    B = 1 << 1,
+}
+
+#[bitflag(u8)]
+#[derive(Debug, Clone, Copy, PartialEq, Serialize)]
+enum SerializeOnly {
+    A = 1,
+}
+
+#[test]
+fn to_json_value() {
+    assert_eq!((Flags::A | Flags::B).to_json_value(), json!("A | B"));
+    assert_eq!(Flags::empty().to_json_value(), json!(""));
+    assert_eq!(
+        Flags::from_bits_retain(0x81).to_json_value(),
+        json!("A | 0x80")
+    );
+    assert_eq!(SerializeOnly::A.to_json_value(), json!("A"));
+}
+
+#[test]
+fn from_json_value() {
+    assert_eq!(
+        Flags::from_json_value(&json!("A | B")).unwrap(),
+        Flags::A | Flags::B
+    );
+    assert_eq!(Flags::from_json_value(&json!("")).unwrap(), Flags::empty());
+    assert!(Flags::from_json_value(&json!("C")).is_err());
+    assert!(Flags::from_json_value(&json!([1])).is_err());
+}
+
+#[test]
+fn json_value_roundtrip() {
+    let flags = Flags::B | Flags::from_bits_retain(0x40);
+
+    assert_eq!(
+        Flags::from_json_value(&flags.to_json_value()).unwrap(),
+        flags
+    );
+}
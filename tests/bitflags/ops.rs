@@ -0,0 +1,37 @@
+use super::*;
+
+use bitflag_attr::ops::*;
+
+#[test]
+fn cases() {
+    assert_eq!(TestFlags::empty(), union_all::<TestFlags>([]));
+    assert_eq!(
+        TestFlags::A | TestFlags::C | TestFlags::from_bits_retain(1 << 3),
+        union_all([
+            TestFlags::A,
+            TestFlags::C,
+            TestFlags::from_bits_retain(1 << 3)
+        ])
+    );
+
+    assert_eq!(TestFlags::all_bits(), intersect_all::<TestFlags>([]));
+    assert_eq!(
+        TestFlags::B,
+        intersect_all([TestFlags::ABC, TestFlags::A | TestFlags::B, TestFlags::B])
+    );
+    assert_eq!(
+        TestFlags::from_bits_retain(1 << 3),
+        intersect_all([TestFlags::from_bits_retain(1 << 3)])
+    );
+
+    assert!(contains_all(&TestFlags::A, []));
+    assert!(contains_all(&TestFlags::ABC, [TestFlags::A, TestFlags::C]));
+    assert!(!contains_all(&TestFlags::A, [TestFlags::A, TestFlags::B]));
+
+    assert!(!intersects_any(&TestFlags::ABC, []));
+    assert!(intersects_any(
+        &TestFlags::A,
+        [TestFlags::B, TestFlags::ABC]
+    ));
+    assert!(!intersects_any(&TestFlags::A, [TestFlags::B, TestFlags::C]));
+}
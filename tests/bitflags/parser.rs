@@ -64,6 +64,18 @@ fn roundtrip_strict() {
     }
 }
 
+#[cfg(feature = "core-error")]
+#[test]
+fn parse_error_is_core_error() {
+    fn parse(input: &str) -> Result<TestFlags, Box<dyn core::error::Error>> {
+        Ok(from_text(input)?)
+    }
+
+    let err = parse("A | D").unwrap_err();
+    assert!(err.downcast_ref::<ParseError>().is_some());
+    assert!(err.source().is_none());
+}
+
 mod from_text {
     use super::*;
 
@@ -327,3 +339,191 @@ mod to_writer_strict {
         s
     }
 }
+
+mod to_writer_sorted {
+    use super::*;
+
+    #[bitflag(u8)]
+    #[derive(Clone, Copy, Debug, PartialEq)]
+    enum Reordered {
+        Write = 1 << 1,
+        Exec = 1 << 2,
+        Read = 1,
+        ReadWrite = Read | Write,
+    }
+
+    #[test]
+    fn cases() {
+        assert_eq!("", write(TestFlags::empty(), NameOrder::Bits));
+        assert_eq!("A | B | C", write(TestFlags::all(), NameOrder::Bits));
+        assert_eq!(
+            "B | C | 0x8",
+            write(
+                TestFlags::C | TestFlags::B | TestFlags::from_bits_retain(1 << 3),
+                NameOrder::Bits
+            )
+        );
+        assert_eq!(
+            "0x8",
+            write(TestFlags::from_bits_retain(1 << 3), NameOrder::Alphabetical)
+        );
+
+        assert_eq!("ABC", write(TestFlagsInvert::all(), NameOrder::Bits));
+
+        assert_eq!(
+            "Read | Write | Exec",
+            write(Reordered::all(), NameOrder::Bits)
+        );
+        assert_eq!(
+            "Exec | Read | Write",
+            write(Reordered::all(), NameOrder::Alphabetical)
+        );
+        assert_eq!(
+            "Exec | Read | Write",
+            write(
+                Reordered::Exec | Reordered::ReadWrite,
+                NameOrder::Alphabetical
+            )
+        );
+    }
+
+    fn write<F: Flags>(value: F, order: NameOrder) -> String {
+        let mut s = String::new();
+
+        to_writer_sorted(&value, order, &mut s).unwrap();
+        s
+    }
+}
+
+mod to_writer_styled {
+    use super::*;
+
+    #[bitflag(i8)]
+    #[derive(Clone, Copy, Debug, PartialEq)]
+    enum Signed {
+        A = 1,
+    }
+
+    #[bitflag(u16)]
+    #[derive(Clone, Copy, Debug, PartialEq)]
+    enum Wide {
+        A = 1,
+    }
+
+    #[test]
+    fn cases() {
+        let unknown = TestFlags::A | TestFlags::from_bits_retain(0b1100_0000);
+        let binary = UnknownBitsStyle::new(BitsFormat::Binary);
+        let decimal = UnknownBitsStyle::new(BitsFormat::Decimal);
+
+        assert_eq!("", write(TestFlags::empty(), binary));
+        assert_eq!("A | B | C", write(TestFlags::all(), binary.split()));
+
+        assert_eq!("A | 0xC0", write(unknown, UnknownBitsStyle::default()));
+        assert_eq!(
+            "A | 0x40 | 0x80",
+            write(unknown, UnknownBitsStyle::new(BitsFormat::Hex).split())
+        );
+        assert_eq!("A | 0b1100_0000", write(unknown, binary));
+        assert_eq!(
+            "A | 0b0100_0000 | 0b1000_0000",
+            write(unknown, binary.split())
+        );
+        assert_eq!("A | 192", write(unknown, decimal));
+        assert_eq!("A | 64 | 128", write(unknown, decimal.split()));
+
+        assert_eq!(
+            "A | 0b0000_0001_0000_0000",
+            write(Wide::A | Wide::from_bits_retain(1 << 8), binary)
+        );
+
+        let signed = Signed::A | Signed::from_bits_retain(i8::MIN);
+        assert_eq!("A | 0x80", write(signed, UnknownBitsStyle::default()));
+        assert_eq!("A | 0b1000_0000", write(signed, binary));
+        assert_eq!("A | 128", write(signed, decimal));
+    }
+
+    fn write<F: Flags>(value: F, style: UnknownBitsStyle) -> String {
+        let mut s = String::new();
+
+        to_writer_styled(&value, style, &mut s).unwrap();
+        s
+    }
+}
+
+mod as_display {
+    use super::*;
+
+    #[test]
+    fn cases() {
+        assert_eq!("", format!("{}", AsDisplay::from_ref(&TestFlags::empty())));
+        assert_eq!(
+            "A | B | C",
+            format!("{}", AsDisplay::from_ref(&TestFlags::all()))
+        );
+        assert_eq!(
+            "A | 0x8",
+            format!(
+                "{}",
+                AsDisplay::from_ref(&(TestFlags::A | TestFlags::from_bits_retain(1 << 3)))
+            )
+        );
+        assert_eq!(
+            "A | B",
+            format!("{:?}", AsDisplay::from_ref(&(TestFlags::A | TestFlags::B)))
+        );
+    }
+}
+
+mod to_writer_localized {
+    use super::*;
+
+    use bitflag_attr::bitflag;
+
+    #[bitflag(u8)]
+    #[derive(Clone, Copy, Debug, PartialEq)]
+    enum Labeled {
+        #[flag(label = "Read only")]
+        ReadOnly = 1,
+        Hidden = 1 << 1,
+        #[flag(label = "System file")]
+        System = 1 << 2,
+    }
+
+    #[test]
+    fn cases() {
+        assert_eq!("", write(Labeled::empty(), &[]));
+        assert_eq!(
+            "Read only | Hidden | System file",
+            write(Labeled::all(), &[])
+        );
+        assert_eq!(
+            "Solo lectura | Oculto | System file",
+            write(
+                Labeled::all(),
+                &[("ReadOnly", "Solo lectura"), ("Hidden", "Oculto")]
+            )
+        );
+        assert_eq!(
+            "Hidden | 0x8",
+            write(Labeled::Hidden | Labeled::from_bits_retain(1 << 3), &[])
+        );
+
+        assert_eq!("A | B | C", write(TestFlags::all(), &[]));
+        assert_eq!("A | Bee | C", write(TestFlags::all(), &[("B", "Bee")]));
+
+        assert_eq!(
+            "Read only | Hidden",
+            (Labeled::ReadOnly | Labeled::Hidden)
+                .display_localized(&[])
+                .to_string()
+        );
+    }
+
+    fn write<F: Flags>(value: F, table: &[(&str, &str)]) -> String {
+        let mut s = String::new();
+
+        to_writer_localized(&value, table, &mut s).unwrap();
+        s
+    }
+}
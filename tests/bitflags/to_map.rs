@@ -0,0 +1,32 @@
+use super::*;
+
+use bitflag_attr::Flags;
+
+#[test]
+fn cases() {
+    case(
+        &[("A", false), ("ABC", false), ("B", false), ("C", false)],
+        TestFlags::empty(),
+    );
+    case(
+        &[("A", true), ("ABC", false), ("B", true), ("C", false)],
+        TestFlags::A | TestFlags::B | TestFlags::from_bits_retain(1 << 3),
+    );
+    case(
+        &[("A", true), ("ABC", true), ("B", true), ("C", true)],
+        TestFlags::all(),
+    );
+
+    case(&[("ONE", false), ("ZERO", true)], TestZeroOne::empty());
+    case(&[], TestEmpty::all());
+}
+
+#[track_caller]
+fn case<T: Flags + std::fmt::Debug>(expected: &[(&'static str, bool)], value: T) {
+    assert_eq!(
+        expected,
+        value.to_map().into_iter().collect::<Vec<_>>(),
+        "{:?}.to_map()",
+        value
+    );
+}
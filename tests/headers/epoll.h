@@ -0,0 +1,19 @@
+#ifndef EPOLL_TEST_H
+#define EPOLL_TEST_H
+
+/* Flags for epoll_ctl, a trimmed down copy of the Linux header. */
+#define EPOLL_CLOEXEC 02000000
+
+enum EPOLL_EVENTS {
+    EPOLLIN = 0x001,
+#define EPOLLIN EPOLLIN
+    EPOLLPRI = 0x002,
+    EPOLLOUT = 0x004, // writable
+    EPOLLERR = 0x008,
+    EPOLLET = 1u << 31
+};
+
+#define EPOLL_CTL_ADD(fd) (fd)
+#define OTHER_FLAG 0x10
+
+#endif
@@ -7,4 +7,23 @@ fn tests() {
     t.compile_fail("tests/03-too_many_args");
     t.compile_fail("tests/04-repetitive_args");
     t.pass("tests/05-no_std");
+    t.compile_fail("tests/06-bitflag_match_unreachable");
+    t.compile_fail("tests/07-bitflag_match_non_exhaustive");
+    t.compile_fail("tests/08-subset_ord_with_ord");
+    t.compile_fail("tests/09-deny_gaps");
+    t.compile_fail("tests/10-misspelled_helper_attrs");
+    t.compile_fail("tests/11-invalid_discriminant");
+    t.compile_fail("tests/12-zero_flag");
+    t.compile_fail("tests/13-stray_bits");
+    t.compile_fail("tests/14-width");
+    t.pass("tests/15-no_implicit_prelude");
+    t.compile_fail("tests/16-alias");
+    t.compile_fail("tests/17-fields");
+    t.compile_fail("tests/18-strip_prefix");
+    t.compile_fail("tests/19-variant_fields");
+    t.compile_fail("tests/20-accessors");
+    t.compile_fail("tests/21-derive");
+    t.compile_fail("tests/22-feature_options");
+    t.compile_fail("tests/23-msrv");
+    t.compile_fail("tests/24-reserved_names");
 }